@@ -24,8 +24,24 @@ pub struct MainCamera;
 #[derive(Component)]
 pub struct MovementOrder(pub Vec3);
 
+/// how a player unit reacts to enemies without direct orders
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
+pub enum UnitStance {
+    /// стреляет по любому врагу в радиусе
+    #[default]
+    Aggressive,
+    /// стреляет только по тем, кто недавно попал по нему
+    ReturnFire,
+    /// не стреляет сам, только по приказу игрока
+    HoldFire,
+}
+
+/// attack-move order: move to the point, stopping to fight enemies met on the way
+#[derive(Component)]
+pub struct AttackMove(pub Vec3);
+
 /// component for storing the shape type of an object
-#[derive(Component, Debug, Clone, Copy)]
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ShapeType {
     Cube,
     Infantry,
@@ -49,6 +65,10 @@ pub struct Tower {
     pub height: f32,
 }
 
+/// which of the faction's three tower models this tower uses
+#[derive(Component, Clone, Copy)]
+pub struct TowerSlot(pub usize);
+
 /// marker for enemy towers that can be attacked
 #[derive(Component)]
 pub struct EnemyTower;
@@ -70,6 +90,10 @@ pub struct CanShoot {
     pub damage: f32,
 }
 
+/// how far a unit or building sees through the fog of war
+#[derive(Component, Clone, Copy)]
+pub struct Vision(pub f32);
+
 /// marker for aircraft
 #[derive(Component)]
 pub struct Aircraft {
@@ -121,6 +145,59 @@ pub struct OilProductionRate(pub f32);
 #[derive(Component)]
 pub struct Trench;
 
+/// infantry inside a completed trench; the index is the occupant's place along the trench
+#[derive(Component, Default, Debug)]
+pub struct TrenchGarrison {
+    pub occupants: Vec<Option<Entity>>,
+}
+
+impl TrenchGarrison {
+    pub fn count(&self) -> usize {
+        self.occupants.iter().flatten().count()
+    }
+
+    /// Lowest place no current occupant is using, if there are fewer than `capacity` occupants
+    pub fn free_slot(&self, capacity: usize) -> Option<usize> {
+        (0..capacity).find(|slot| self.occupants.get(*slot).copied().flatten().is_none())
+    }
+
+    pub fn remove(&mut self, soldier: Entity) {
+        for occupant in self.occupants.iter_mut().filter(|occupant| **occupant == Some(soldier)) {
+            *occupant = None;
+        }
+    }
+}
+
+/// infantry unit sitting in a trench: takes less damage while inside
+#[derive(Component, Debug, Clone, Copy)]
+pub struct Garrisoned {
+    pub trench: Entity,
+}
+
+/// infantry unit on its way to occupy a trench
+#[derive(Component, Debug, Clone, Copy)]
+pub struct GarrisonOrder(pub Entity);
+
+/// Building that engineers are still putting up; becomes `building` when `progress` reaches `work_required`
+#[derive(Component, Debug, Clone, Copy)]
+pub struct ConstructionSite {
+    pub building: ShapeType,
+    /// Секунды работы одного инженера, уже вложенные в стройку
+    pub progress: f32,
+    pub work_required: f32,
+}
+
+/// Infantry that builds construction sites and trenches and repairs buildings and towers
+#[derive(Component, Debug, Clone, Copy)]
+pub struct Engineer;
+
+/// Engineer is on the way to `target` (or already working on it) and stands at `stand` while working
+#[derive(Component, Debug, Clone, Copy)]
+pub struct BuildOrder {
+    pub target: Entity,
+    pub stand: Vec3,
+}
+
 /// component to track trench construction time
 #[derive(Component)]
 pub struct TrenchConstruction {
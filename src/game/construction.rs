@@ -0,0 +1,303 @@
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+use bevy_mod_picking::prelude::*;
+
+use crate::game::{
+    BuildOrder, ConstructionSite, Enemy, EnemyTower, Engineer, ForestFarm, GarrisonOrder, Health, HoveredOutline,
+    Mine, MovementOrder, PetrochemicalPlant, Selectable, Selection, ShapeType, SteelFactory, Tower, Trench,
+    TrenchConstruction,
+};
+use crate::systems::ai_opponent::{spawn_ai_building, AIPlayers, AISide, SideResources};
+use crate::systems::formations::FormationSpeed;
+use crate::systems::pathfinding::{structure_footprint, MovementPath};
+use crate::systems::tower::{repair_tower, TowerRepair, TowerRepairResult, TOWER_REPAIR_PER_TURN};
+use crate::systems::turn_system::{PlayerTurn, TurnState};
+use crate::ui::money_ui::PurchasableItem;
+
+/// Очки прочности в секунду, которые восстанавливает один инженер
+pub const ENGINEER_REPAIR_RATE: f32 = 10.0;
+/// Насколько дальше края постройки инженер еще дотягивается до работы
+const ENGINEER_WORK_REACH: f32 = 2.5;
+/// На таком расстоянии от края постройки инженер встает работать
+const ENGINEER_STANDOFF: f32 = 1.5;
+const CONSTRUCTION_SITE_HEALTH: f32 = 60.0;
+
+/// Anything an engineer can be sent to: a construction site, a trench, a tower or a building
+pub type EngineerWorkplace = Or<(
+    With<ConstructionSite>,
+    With<Trench>,
+    With<Tower>,
+    With<ForestFarm>,
+    With<Mine>,
+    With<SteelFactory>,
+    With<PetrochemicalPlant>,
+)>;
+
+type PlayerStructure = (Without<Enemy>, Without<EnemyTower>, Without<Engineer>);
+type WorkingEngineer<'a> = (Entity, &'a Transform, &'a BuildOrder, Option<&'a MovementOrder>, Has<Enemy>);
+type WorkplaceData<'a> = (&'a Transform, &'a ShapeType, &'a Health, Has<ConstructionSite>, Has<TrenchConstruction>);
+/// Постройка любой стороны: сторона нужна, чтобы инженер работал только на своих
+type StructureData<'a> = (&'a Transform, &'a ShapeType, &'a mut Health, Option<&'a mut TowerRepair>, Has<Enemy>, Has<EnemyTower>);
+
+/// Seconds of work of a single engineer a building needs
+pub fn construction_work(building: ShapeType) -> f32 {
+    match building {
+        ShapeType::Farm => 15.0,
+        ShapeType::Mine => 20.0,
+        ShapeType::SteelFactory | ShapeType::PetrochemicalPlant => 30.0,
+        _ => 20.0,
+    }
+}
+
+/// Distance from the center of a structure to its farthest edge
+fn structure_radius(shape: ShapeType) -> f32 {
+    structure_footprint(shape).map_or(1.0, |half_extents| half_extents.length())
+}
+
+fn flat_distance(a: Vec3, b: Vec3) -> f32 {
+    Vec2::new(a.x - b.x, a.z - b.z).length()
+}
+
+/// Where an engineer coming from `from` stands to work on a structure at `center`: each on its own side of it
+pub fn engineer_stand(center: Vec3, shape: ShapeType, from: Vec3) -> Vec3 {
+    let side = Vec3::new(from.x - center.x, 0.0, from.z - center.z).try_normalize().unwrap_or(Vec3::X);
+    Vec3::new(center.x, 0.0, center.z) + side * (structure_radius(shape) + ENGINEER_STANDOFF)
+}
+
+/// Player construction sites that will become `building`; building limits count them as already built
+pub fn pending_player_sites(sites: &Query<&ConstructionSite, Without<Enemy>>, building: ShapeType) -> usize {
+    sites.iter().filter(|site| site.building == building).count()
+}
+
+/// Spawns the scaffolding of a purchased building; engineers turn it into `building`
+pub fn spawn_construction_site(
+    commands: &mut Commands,
+    meshes: &mut Assets<Mesh>,
+    materials: &mut Assets<StandardMaterial>,
+    building: ShapeType,
+    position: Vec3,
+) -> Entity {
+    commands.spawn((
+        PbrBundle {
+            mesh: meshes.add(Mesh::from(Cuboid::new(2.0, 0.6, 2.0))),
+            material: materials.add(StandardMaterial {
+                base_color: Color::rgba(0.55, 0.45, 0.3, 0.6), // леса и разметка будущего здания
+                alpha_mode: AlphaMode::Blend,
+                ..default()
+            }),
+            transform: Transform::from_translation(Vec3::new(position.x, 0.3, position.z)),
+            ..default()
+        },
+        ConstructionSite {
+            building,
+            progress: 0.0,
+            work_required: construction_work(building),
+        },
+        building,
+        Selectable,
+        PickableBundle::default(),
+        Health { current: CONSTRUCTION_SITE_HEALTH, max: CONSTRUCTION_SITE_HEALTH },
+        Name::new("Construction Site"),
+        On::<Pointer<Over>>::run(|mut commands: Commands, event: Listener<Pointer<Over>>| {
+            commands.entity(event.target).insert(HoveredOutline);
+        }),
+        On::<Pointer<Out>>::run(|mut commands: Commands, event: Listener<Pointer<Out>>| {
+            commands.entity(event.target).remove::<HoveredOutline>();
+        }),
+    )).id()
+}
+
+/// Right-click on a construction site, a trench being dug or a damaged building sends the selected engineers to work
+pub fn order_engineers(
+    mut commands: Commands,
+    mut pointer_down: EventReader<Pointer<Down>>,
+    selection: Res<Selection>,
+    turn_state: Res<TurnState>,
+    engineers: Query<&Transform, (With<Engineer>, Without<Enemy>)>,
+    workplaces: Query<WorkplaceData, PlayerStructure>,
+    parents: Query<&Parent>,
+) {
+    for event in pointer_down.read() {
+        if event.button != PointerButton::Secondary || turn_state.current_player != PlayerTurn::Human {
+            continue;
+        }
+        // Клик мог прийти в дочерний меш модели здания
+        let Some(target) = std::iter::once(event.target)
+            .chain(parents.iter_ancestors(event.target))
+            .find(|entity| workplaces.get(*entity).is_ok())
+        else {
+            continue;
+        };
+        let Ok((target_transform, shape, health, is_site, is_digging)) = workplaces.get(target) else {
+            continue;
+        };
+
+        let selected: Vec<(Entity, Vec3)> = selection.iter()
+            .filter_map(|entity| engineers.get(entity).ok().map(|transform| (entity, transform.translation)))
+            .collect();
+        if selected.is_empty() {
+            continue;
+        }
+
+        let job = if is_site || is_digging {
+            "build"
+        } else if matches!(shape, ShapeType::Trench) {
+            // Готовый окоп занимают, а не чинят - это order_trench_garrison
+            continue;
+        } else if health.current < health.max {
+            "repair"
+        } else {
+            info!("🔧 {:?} {:?} needs no repair", shape, target);
+            continue;
+        };
+
+        for (engineer, position) in selected.iter() {
+            let stand = engineer_stand(target_transform.translation, *shape, *position);
+            commands.entity(*engineer)
+                .remove::<(FormationSpeed, MovementPath, GarrisonOrder)>()
+                .insert((MovementOrder(stand), BuildOrder { target, stand }));
+        }
+        info!("🔧 {} engineer(s) sent to {} {:?} {:?}", selected.len(), job, shape, target);
+    }
+}
+
+/// Engineers next to their target build it up or repair it; several engineers work faster.
+/// Engineers work only on their own side's structures. Tower repair is paid and limited per turn,
+/// so engineers fix towers only during their side's turn
+#[allow(clippy::too_many_arguments)]
+pub fn engineer_work(
+    mut commands: Commands,
+    time: Res<Time>,
+    turn_state: Res<TurnState>,
+    mut resources: SideResources,
+    engineers: Query<WorkingEngineer, With<Engineer>>,
+    mut workplaces: Query<StructureData, Without<Engineer>>,
+    mut sites: Query<&mut ConstructionSite>,
+    mut trenches: Query<&mut TrenchConstruction>,
+) {
+    let mut crews: HashMap<Entity, (AISide, Vec<Entity>)> = HashMap::new();
+
+    for (engineer, transform, order, movement_order, is_enemy) in engineers.iter() {
+        let side = AISide::of(is_enemy);
+        let target = workplaces.get(order.target).ok()
+            .filter(|(.., is_enemy_structure, is_enemy_tower)| side.owns(*is_enemy_structure || *is_enemy_tower));
+        let Some((target_transform, shape, ..)) = target else {
+            commands.entity(engineer).remove::<BuildOrder>();
+            info!("🔧 Engineer {:?} finished with {:?}", engineer, order.target);
+            continue;
+        };
+        let reordered = movement_order.is_some_and(|movement| movement.0.distance(order.stand) > 0.1);
+        let in_reach = flat_distance(transform.translation, target_transform.translation)
+            <= structure_radius(*shape) + ENGINEER_WORK_REACH;
+
+        // Новый приказ движения или инженер остановился, не дойдя, - работа отменяется
+        if reordered || (!in_reach && movement_order.is_none()) {
+            commands.entity(engineer).remove::<BuildOrder>();
+            continue;
+        }
+        if in_reach {
+            crews.entry(order.target).or_insert_with(|| (side, Vec::new())).1.push(engineer);
+        }
+    }
+
+    let delta_time = time.delta_seconds();
+    for (target, (side, crew)) in crews.iter() {
+        let work = crew.len() as f32 * delta_time;
+        if let Ok(mut site) = sites.get_mut(*target) {
+            site.progress += work;
+        } else if let Ok(mut construction) = trenches.get_mut(*target) {
+            construction.time_remaining -= work;
+        } else if let Ok((_, shape, mut health, tower_repair, ..)) = workplaces.get_mut(*target) {
+            let stop = match tower_repair {
+                Some(mut tower_repair) => {
+                    if turn_state.current_player != side.turn() {
+                        continue;
+                    }
+                    let mut wallet = resources.wallet(*side);
+                    match repair_tower(&mut health, &mut tower_repair, &mut wallet, ENGINEER_REPAIR_RATE * work, &turn_state, time.elapsed_seconds()) {
+                        TowerRepairResult::Repaired(_) => health.current >= health.max,
+                        TowerRepairResult::Intact => true,
+                        TowerRepairResult::TurnLimit => {
+                            info!("🔧 Tower {:?} got all the repair allowed this turn ({} HP)", target, TOWER_REPAIR_PER_TURN);
+                            true
+                        }
+                        TowerRepairResult::NotEnoughResources => {
+                            info!("🔧 Not enough resources to repair tower {:?}", target);
+                            true
+                        }
+                    }
+                }
+                None => {
+                    health.current = (health.current + ENGINEER_REPAIR_RATE * work).min(health.max);
+                    health.current >= health.max
+                }
+            };
+            if stop {
+                for engineer in crew.iter() {
+                    commands.entity(*engineer).remove::<BuildOrder>();
+                }
+                info!("🔧 {:?} {:?} repair stopped at {:.0}/{:.0}", shape, target, health.current, health.max);
+            }
+        }
+    }
+}
+
+/// Replaces finished construction sites with working buildings
+pub fn finish_construction_sites(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    asset_server: Res<AssetServer>,
+    ai_players: Res<AIPlayers>,
+    sites: Query<(Entity, &ConstructionSite, &Transform, Has<Enemy>)>,
+) {
+    for (entity, site, transform, is_enemy) in sites.iter() {
+        if site.progress < site.work_required {
+            continue;
+        }
+        commands.entity(entity).despawn_recursive();
+
+        let position = Vec3::new(transform.translation.x, 0.0, transform.translation.z);
+        let side = AISide::of(is_enemy);
+        // Стройки ИИ становятся зданиями ИИ
+        if ai_players.get(side).is_some() {
+            if let Some(item) = PurchasableItem::for_building(site.building) {
+                spawn_ai_building(&mut commands, &asset_server, item, side, position);
+                info!("🏗️ AI ({:?}) {:?} finished at {:?}", side, site.building, position);
+            }
+            continue;
+        }
+        match site.building {
+            ShapeType::Farm => {
+                crate::game::farm::spawn_active_forest_farm(&mut commands, &mut meshes, &mut materials, position, &asset_server);
+            }
+            ShapeType::Mine => {
+                crate::game::mine::spawn_active_mine(&mut commands, &mut meshes, &mut materials, position, &asset_server);
+            }
+            ShapeType::SteelFactory => {
+                crate::game::steel_factory::spawn_active_steel_factory(&mut commands, &mut meshes, &mut materials, position, &asset_server);
+            }
+            ShapeType::PetrochemicalPlant => {
+                crate::game::petrochemical_plant::spawn_active_petrochemical_plant(&mut commands, &mut meshes, &mut materials, position, &asset_server);
+            }
+            other => {
+                warn!("Construction site {:?} holds {:?}, which is not a building", entity, other);
+                continue;
+            }
+        }
+        info!("🏗️ {:?} finished at {:?}", site.building, position);
+    }
+}
+
+/// Progress bar above every construction site
+pub fn draw_construction_site_progress(
+    mut gizmos: Gizmos,
+    sites: Query<(&ConstructionSite, &Transform)>,
+) {
+    for (site, transform) in sites.iter() {
+        let progress = (site.progress / site.work_required).clamp(0.0, 1.0);
+        let start = transform.translation + Vec3::new(-1.0, 1.0, 0.0);
+        gizmos.line(start, start + Vec3::new(2.0, 0.0, 0.0), Color::DARK_GRAY);
+        gizmos.line(start, start + Vec3::new(2.0 * progress, 0.0, 0.0), Color::GREEN);
+    }
+}
@@ -3,7 +3,11 @@ use bevy_mod_picking::prelude::*;
 use bevy::gizmos::gizmos::Gizmos;
 use crate::game::components::{Farm, ForestFarm, FarmActive, FarmIncomeRate, Selectable, HoveredOutline, ShapeType, Health};
 use crate::game::resources::FarmIncomeTimer;
-use crate::ui::money_ui::Money;
+use crate::game::construction::spawn_construction_site;
+use crate::ui::money_ui::{Money, PurchasableItem};
+use crate::ui::cost_table::CostTable;
+use crate::systems::ai_opponent::{AISide, SideResources};
+use crate::input::key_bindings::{InputAction, KeyBindings};
 
 /// System to update farm income
 pub fn update_farm_income(
@@ -200,7 +204,7 @@ pub fn spawn_active_forest_farm(
     _materials: &mut Assets<StandardMaterial>,
     position: Vec3,
     asset_server: &AssetServer,
-) {
+) -> Entity {
     commands.spawn((
         SceneBundle {
             scene: asset_server.load("models/farm/forest.glb#Scene0"),
@@ -226,33 +230,36 @@ pub fn spawn_active_forest_farm(
         On::<Pointer<Out>>::run(|mut commands: Commands, event: Listener<Pointer<Out>>| {
             commands.entity(event.target).remove::<HoveredOutline>();
         }),
-    ));
+    )).id()
 }
 
-/// Spawn a test forest farm with keyboard shortcut
+/// Lay out a forest farm construction site with keyboard shortcut; engineers build it up
+#[allow(clippy::too_many_arguments)]
 pub fn spawn_forest_farm_on_keystroke(
     mut commands: Commands,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<StandardMaterial>>,
-    asset_server: Res<AssetServer>,
     keyboard_input: Res<ButtonInput<KeyCode>>,
+    key_bindings: Res<KeyBindings>,
+    mut resources: SideResources,
+    costs: Res<CostTable>,
     query: Query<&Transform, With<Farm>>,
 ) {
-    if keyboard_input.just_pressed(KeyCode::KeyF) {
-        info!("Spawning a test forest farm");
-        
+    if key_bindings.just_pressed(InputAction::PlaceFarm, &keyboard_input) {
+        let cost = costs.get(PurchasableItem::Farm);
+        let mut wallet = resources.wallet(AISide::Player);
+        if !wallet.amounts().covers(&cost) {
+            info!("Not enough resources to build a forest farm! Need {:?}", cost);
+            return;
+        }
+        wallet.deduct(&cost);
+
         // Determine the position for the new farm at the top of the map
         let position = find_free_position_in_area(&query, 5.0, Vec3::new(0.0, 0.0, -15.0), 8.0);
         
-        info!("Found position for farm in upper area: {:?}", position);
+        info!("Laying out a forest farm at {:?}, cost: {:?}", position, cost);
         
-        spawn_forest_farm(
-            &mut commands,
-            &mut meshes,
-            &mut materials,
-            position,
-            &asset_server,
-        );
+        spawn_construction_site(&mut commands, &mut meshes, &mut materials, ShapeType::Farm, position);
     }
 }
 
@@ -4,7 +4,12 @@ use bevy::gizmos::gizmos::Gizmos;
 use bevy_rapier3d::prelude::*;
 use crate::game::components::{Mine, FarmActive, FarmIncomeRate, MineIronRate, Selectable, HoveredOutline, ShapeType, Health};
 use crate::game::resources::FarmIncomeTimer;
-use crate::ui::money_ui::{Money, Iron, Wood};
+use crate::ui::money_ui::{Money, Iron, PurchasableItem};
+use crate::ui::cost_table::CostTable;
+use crate::game::components::ConstructionSite;
+use crate::game::construction::{pending_player_sites, spawn_construction_site};
+use crate::systems::ai_opponent::{AISide, SideResources};
+use crate::input::key_bindings::{InputAction, KeyBindings};
 
 /// System to update mine income (money and iron)
 pub fn update_mine_income(
@@ -178,42 +183,37 @@ pub fn spawn_mine_on_keystroke(
     mut commands: Commands,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<StandardMaterial>>,
-    asset_server: Res<AssetServer>,
     keyboard_input: Res<ButtonInput<KeyCode>>,
-    mut money: ResMut<Money>,
-    mut wood: ResMut<Wood>,
+    key_bindings: Res<KeyBindings>,
+    mut resources: SideResources,
+    costs: Res<CostTable>,
     query: Query<&Transform, With<Mine>>,
     // Добавляем запрос для проверки существующих шахт игрока (без Enemy компонента)
     player_mines: Query<Entity, (With<Mine>, Without<crate::game::Enemy>)>,
+    sites: Query<&ConstructionSite, Without<crate::game::Enemy>>,
 ) {
-    if keyboard_input.just_pressed(KeyCode::KeyM) {
+    if key_bindings.just_pressed(InputAction::PlaceMine, &keyboard_input) {
         // Проверяем, есть ли уже шахта у игрока
-        let existing_mines_count = player_mines.iter().count();
+        let existing_mines_count = player_mines.iter().count() + pending_player_sites(&sites, ShapeType::Mine);
         if existing_mines_count >= 1 {
             info!("Cannot build more mines! Player can only have 1 mine maximum.");
             return;
         }
         
-        // Check if player has enough resources (100 money, 35 wood)
-        if money.0 >= 100.0 && wood.0 >= 35.0 {
-            info!("Spawning a mine, cost: 100 money, 35 wood");
-            money.0 -= 100.0;
-            wood.0 -= 35.0;
+        let cost = costs.get(PurchasableItem::Mine);
+        let mut wallet = resources.wallet(AISide::Player);
+        if wallet.amounts().covers(&cost) {
+            info!("Laying out a mine, cost: {:?}", cost);
+            wallet.deduct(&cost);
             
             // Determine the position for the new mine on the left side of the map
             let position = find_free_position_in_area(&query, 5.0, Vec3::new(-15.0, 0.0, 0.0), 8.0);
             
             info!("Found position for mine in left area: {:?}", position);
             
-            spawn_active_mine(
-                &mut commands,
-                &mut meshes,
-                &mut materials,
-                position,
-                &asset_server,
-            );
+            spawn_construction_site(&mut commands, &mut meshes, &mut materials, ShapeType::Mine, position);
         } else {
-            info!("Not enough resources to spawn a mine! Need 100 money and 35 wood");
+            info!("Not enough resources to spawn a mine! Need {:?}", cost);
         }
     }
 }
@@ -225,7 +225,7 @@ pub fn spawn_active_mine(
     _materials: &mut Assets<StandardMaterial>,
     position: Vec3,
     asset_server: &AssetServer,
-) {
+) -> Entity {
     commands.spawn((
         SceneBundle {
             scene: asset_server.load("models/farm/mine.glb#Scene0"),
@@ -251,7 +251,7 @@ pub fn spawn_active_mine(
         On::<Pointer<Out>>::run(|mut commands: Commands, event: Listener<Pointer<Out>>| {
             commands.entity(event.target).remove::<HoveredOutline>();
         }),
-    ));
+    )).id()
 }
 
 /// Finds a free position to place a building in the specified area
@@ -1,4 +1,5 @@
 pub mod components;
+pub mod construction;
 pub mod game;
 pub mod farm;
 pub mod mine;
@@ -39,6 +40,7 @@ pub fn game_plugin(app: &mut App) {
         .init_resource::<AISteel>()
         .init_resource::<AIOil>()
         .init_resource::<TrenchCost>()
+        .init_resource::<TrenchDrag>()
         .init_resource::<PlacementState>()
         .init_resource::<units::PlayerFaction>()
         .add_plugins(units::UnitsPlugin)
@@ -50,11 +52,6 @@ pub fn game_plugin(app: &mut App) {
             Update,
             update_farm_visuals.run_if(in_state(GameState::Game)),
         )
-
-        .add_systems(
-            Update,
-            spawn_forest_farm_on_keystroke.run_if(in_state(GameState::Game)),
-        )
         // Mine systems
         .add_systems(Update, update_mine_income.run_if(in_state(GameState::Game)))
         .add_systems(Update, handle_mine_clicks.run_if(in_state(GameState::Game)))
@@ -62,11 +59,6 @@ pub fn game_plugin(app: &mut App) {
             Update,
             update_mine_visuals.run_if(in_state(GameState::Game)),
         )
-
-        .add_systems(
-            Update,
-            spawn_mine_on_keystroke.run_if(in_state(GameState::Game)),
-        )
         // Steel factory systems
         .add_systems(
             Update,
@@ -80,11 +72,6 @@ pub fn game_plugin(app: &mut App) {
             Update,
             update_steel_factory_visuals.run_if(in_state(GameState::Game)),
         )
-
-        .add_systems(
-            Update,
-            spawn_steel_factory_on_keystroke.run_if(in_state(GameState::Game)),
-        )
         // Petrochemical plant systems
         .add_systems(
             Update,
@@ -98,11 +85,6 @@ pub fn game_plugin(app: &mut App) {
             Update,
             update_petrochemical_plant_visuals.run_if(in_state(GameState::Game)),
         )
-
-        .add_systems(
-            Update,
-            spawn_petrochemical_plant_on_keystroke.run_if(in_state(GameState::Game)),
-        )
         // Scene collider systems
         .add_systems(
             Update,
@@ -129,7 +111,9 @@ pub fn game_plugin(app: &mut App) {
 
         .add_systems(
             Update,
-            spawn_trench_on_keystroke.run_if(in_state(GameState::Game)),
+            (toggle_trench_placement, place_trench_line, draw_trench_placement_preview)
+                .chain()
+                .run_if(in_state(GameState::Game)),
         );
 }
 
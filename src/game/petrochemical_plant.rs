@@ -4,7 +4,12 @@ use bevy::gizmos::gizmos::Gizmos;
 use bevy_rapier3d::prelude::*;
 use crate::game::components::{PetrochemicalPlant, FarmActive, FarmIncomeRate, OilProductionRate, Selectable, HoveredOutline, ShapeType, Health};
 use crate::game::resources::FarmIncomeTimer;
-use crate::ui::money_ui::{Money, Oil, Wood, Steel};
+use crate::ui::money_ui::{Money, Oil, PurchasableItem};
+use crate::ui::cost_table::CostTable;
+use crate::game::components::ConstructionSite;
+use crate::game::construction::{pending_player_sites, spawn_construction_site};
+use crate::systems::ai_opponent::{AISide, SideResources};
+use crate::input::key_bindings::{InputAction, KeyBindings};
 
 /// System to update petrochemical plant income (money and oil)
 pub fn update_petrochemical_plant_income(
@@ -180,7 +185,7 @@ pub fn spawn_active_petrochemical_plant(
     _materials: &mut Assets<StandardMaterial>,
     position: Vec3,
     asset_server: &AssetServer,
-) {
+) -> Entity {
     commands.spawn((
         SceneBundle {
             scene: asset_server.load("models/farm/oil_pump.glb#Scene0"),
@@ -206,50 +211,43 @@ pub fn spawn_active_petrochemical_plant(
         On::<Pointer<Out>>::run(|mut commands: Commands, event: Listener<Pointer<Out>>| {
             commands.entity(event.target).remove::<HoveredOutline>();
         }),
-    ));
+    )).id()
 }
 
 /// System for creating petrochemical plants using a key press
 pub fn spawn_petrochemical_plant_on_keystroke(
     mut commands: Commands,
     keys: Res<ButtonInput<KeyCode>>,
+    key_bindings: Res<KeyBindings>,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<StandardMaterial>>,
-    asset_server: Res<AssetServer>,
-    mut money: ResMut<Money>,
-    mut wood: ResMut<Wood>,
-    mut steel: ResMut<Steel>,
+    mut resources: SideResources,
+    costs: Res<CostTable>,
     // Добавляем запрос для проверки существующих нефтезаводов игрока
     player_plants: Query<Entity, (With<PetrochemicalPlant>, Without<crate::game::Enemy>)>,
+    sites: Query<&ConstructionSite, Without<crate::game::Enemy>>,
 ) {
-    if keys.just_pressed(KeyCode::KeyP) {
+    if key_bindings.just_pressed(InputAction::PlacePetrochemicalPlant, &keys) {
         // Проверяем, есть ли уже нефтезавод у игрока
-        let existing_plants_count = player_plants.iter().count();
+        let existing_plants_count = player_plants.iter().count() + pending_player_sites(&sites, ShapeType::PetrochemicalPlant);
         if existing_plants_count >= 1 {
             info!("Cannot build more petrochemical plants! Player can only have 1 petrochemical plant maximum.");
             return;
         }
         
-        // Check if player has enough resources (10 money, 5 wood, 5 steel)
-        if money.0 >= 10.0 && wood.0 >= 5.0 && steel.0 >= 5.0 {
-            info!("Spawning a petrochemical plant, cost: 10 money, 5 wood, 5 steel");
-            money.0 -= 10.0;
-            wood.0 -= 5.0;
-            steel.0 -= 5.0;
+        let cost = costs.get(PurchasableItem::PetrochemicalPlant);
+        let mut wallet = resources.wallet(AISide::Player);
+        if wallet.amounts().covers(&cost) {
+            info!("Laying out a petrochemical plant, cost: {:?}", cost);
+            wallet.deduct(&cost);
             
             // Spawn the petrochemical plant at a predetermined position
             let position = Vec3::new(10.0, 0.0, 5.0);
             
-            // Immediately create an active plant, not an inactive one
-            spawn_active_petrochemical_plant(
-                &mut commands,
-                &mut meshes,
-                &mut materials,
-                position,
-                &asset_server,
-            );
+            // Инженеры достроят площадку до рабочего нефтезавода
+            spawn_construction_site(&mut commands, &mut meshes, &mut materials, ShapeType::PetrochemicalPlant, position);
         } else {
-            info!("Not enough resources to spawn a petrochemical plant! Need 10 money, 5 wood and 5 steel");
+            info!("Not enough resources to spawn a petrochemical plant! Need {:?}", cost);
         }
     }
 } 
\ No newline at end of file
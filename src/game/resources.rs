@@ -2,9 +2,51 @@ use bevy::prelude::*;
 use bevy_hanabi::prelude::*;
 use super::components::ShapeType;
 
-/// resource for storing the selected entity
+/// resource for storing the selected entities; the first one is the primary (the camera follows it)
 #[derive(Resource, Default)]
-pub struct SelectedEntity(pub Option<Entity>);
+pub struct Selection(pub Vec<Entity>);
+
+impl Selection {
+    pub fn primary(&self) -> Option<Entity> {
+        self.0.first().copied()
+    }
+
+    pub fn contains(&self, entity: Entity) -> bool {
+        self.0.contains(&entity)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = Entity> + '_ {
+        self.0.iter().copied()
+    }
+
+    pub fn clear(&mut self) {
+        self.0.clear();
+    }
+
+    /// Shift: добавляет к выделению, без дубликатов
+    pub fn add(&mut self, entity: Entity) {
+        if !self.contains(entity) {
+            self.0.push(entity);
+        }
+    }
+
+    /// Ctrl: убирает из выделения, если уже выделен, иначе добавляет
+    pub fn toggle(&mut self, entity: Entity) {
+        if let Some(index) = self.0.iter().position(|selected| *selected == entity) {
+            self.0.remove(index);
+        } else {
+            self.0.push(entity);
+        }
+    }
+}
 
 /// resource for storing information about the click circle for gizmos
 #[derive(Resource, Default)]
@@ -24,6 +66,16 @@ pub struct CameraSettings {
     pub min_zoom: f32,
     pub max_zoom: f32,
     pub zoom_speed: f32,
+    /// Pan speed for WASD/arrows and screen edges, world units per second at zoom 1
+    pub pan_speed: f32,
+    /// Distance from the window edge in pixels where edge scrolling starts
+    pub edge_scroll_margin: f32,
+    /// Q/E rotation speed, radians per second
+    pub rotation_speed: f32,
+    /// How fast the camera catches up with the wanted position; bigger is snappier
+    pub smoothing: f32,
+    /// The camera focus stays within this distance of the map center along X and Z
+    pub bounds: f32,
 }
 
 /// resource for storing the camera movement state
@@ -32,7 +84,10 @@ pub struct CameraMovementState {
     pub is_right_button_pressed: bool,
     pub last_mouse_position: Option<Vec2>,
     pub movement_speed: f32,
+    /// The player moved the camera; following is paused until the next selection
     pub manual_camera_mode: bool,
+    /// Follow mode: the camera follows the selected unit. Off - free camera
+    pub follow_selection: bool,
 }
 
 /// resource for timer to update farm income
@@ -50,6 +105,8 @@ pub struct PlacementState {
     pub active: bool,
     /// Index of specific unit type (0, 1, 2 for different variants of the same unit type)
     pub unit_type_index: Option<usize>,
+    /// Price of the object, charged only once it is actually placed
+    pub cost: Option<crate::ui::money_ui::ResourceCost>,
 }
 
 impl Default for FarmIncomeTimer {
@@ -67,6 +124,11 @@ impl Default for CameraSettings {
             min_zoom: 0.5,
             max_zoom: 10.0,
             zoom_speed: 0.1,
+            pan_speed: 20.0,
+            edge_scroll_margin: 12.0,
+            rotation_speed: 1.5,
+            smoothing: 10.0,
+            bounds: 60.0,
         }
     }
 }
@@ -78,6 +140,7 @@ impl Default for CameraMovementState {
             last_mouse_position: None,
             movement_speed: 0.02,
             manual_camera_mode: false,
+            follow_selection: true,
         }
     }
 }
\ No newline at end of file
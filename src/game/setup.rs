@@ -1,6 +1,6 @@
 use bevy::prelude::*;
 use bevy_mod_picking::prelude::*;
-use crate::game::{Ground, MainCamera, ShapeType, Health, HoveredOutline, Tower, EnemyTower};
+use crate::game::{Ground, MainCamera, ShapeType, Health, HoveredOutline, Tower, TowerSlot, EnemyTower};
 use crate::game::farm::{spawn_active_forest_farm};
 use crate::menu::main_menu::Faction;
 use crate::game::units::{PlayerFaction, AIFaction};
@@ -88,28 +88,28 @@ pub fn setup(
     ));
 
     // Spawn player faction towers (closer to camera, at positive Z)
-    spawn_faction_towers(
-        &mut commands,
-        &asset_server,
-        player_faction.0,
-        Vec3::new(-35.0, 0.0, 40.0), // Left tower - moved further
-        Vec3::new(0.0, 0.0, 40.0),   // Center tower - moved further
-        Vec3::new(35.0, 0.0, 40.0),  // Right tower - moved further
-        false, // Not enemy towers
-    );
+    let [left, center, right] = PLAYER_TOWER_POSITIONS;
+    spawn_faction_towers(&mut commands, &asset_server, player_faction.0, left, center, right, false);
 
     // Spawn AI faction towers (far from camera, at negative Z)  
-    spawn_faction_towers(
-        &mut commands,
-        &asset_server,
-        ai_faction.0,
-        Vec3::new(-35.0, 0.0, -40.0), // Left tower - moved further
-        Vec3::new(0.0, 0.0, -40.0),   // Center tower - moved further
-        Vec3::new(35.0, 0.0, -40.0),  // Right tower - moved further
-        true, // Enemy towers
-    );
+    let [left, center, right] = ENEMY_TOWER_POSITIONS;
+    spawn_faction_towers(&mut commands, &asset_server, ai_faction.0, left, center, right, true);
 }
 
+/// Player towers: left, center, right (closer to camera, at positive Z)
+pub const PLAYER_TOWER_POSITIONS: [Vec3; 3] = [
+    Vec3::new(-35.0, 0.0, 40.0),
+    Vec3::new(0.0, 0.0, 40.0),
+    Vec3::new(35.0, 0.0, 40.0),
+];
+
+/// AI towers: left, center, right (far from camera, at negative Z)
+pub const ENEMY_TOWER_POSITIONS: [Vec3; 3] = [
+    Vec3::new(-35.0, 0.0, -40.0),
+    Vec3::new(0.0, 0.0, -40.0),
+    Vec3::new(35.0, 0.0, -40.0),
+];
+
 /// Create a tower at the specified position
 pub fn spawn_tower(
     commands: &mut Commands,
@@ -153,6 +153,22 @@ pub fn spawn_faction_towers(
     pos3: Vec3,
     is_enemy: bool,
 ) {
+    let positions = vec![pos1, pos2, pos3];
+    
+    for (i, position) in positions.iter().enumerate() {
+        spawn_faction_tower(commands, asset_server, faction, i, *position, is_enemy);
+    }
+}
+
+/// Spawn one of the three faction towers (`slot` selects the model)
+pub fn spawn_faction_tower(
+    commands: &mut Commands,
+    asset_server: &AssetServer,
+    faction: Faction,
+    slot: usize,
+    position: Vec3,
+    is_enemy: bool,
+) -> Entity {
     let (tower_models, tower_scales) = match faction {
         Faction::Entente => (
             vec![
@@ -172,42 +188,44 @@ pub fn spawn_faction_towers(
         ),
     };
 
-    let positions = vec![pos1, pos2, pos3];
+    let tower_scene = asset_server.load(tower_models[slot]);
+    let tower_scale = tower_scales[slot];
     
-    for (i, position) in positions.iter().enumerate() {
-        let tower_scene = asset_server.load(tower_models[i]);
-        let tower_scale = tower_scales[i];
-        
-        println!("🏰 Loading tower model: {} at position {:?} with scale {}", tower_models[i], position, tower_scale);
-        
-        let tower_bundle = (
-            SceneBundle {
-                scene: tower_scene,
-                transform: Transform::from_translation(*position + Vec3::new(0.0, 5.0, 0.0))
-                    .with_scale(Vec3::splat(tower_scale)),
-                ..default()
-            },
-            Tower { height: 10.0 },
-            PickableBundle::default(),
-            ShapeType::Tower,
-            Health { current: 600.0, max: 600.0 },
-            bevy_rapier3d::prelude::RigidBody::Fixed,
-            bevy_rapier3d::prelude::Collider::cuboid(6.0, 8.0, 6.0), // Большой коллайдер для легкого клика по башням
-            bevy_rapier3d::prelude::Sensor,
-            bevy_rapier3d::prelude::LockedAxes::all(),
-            Name::new(format!("FactionTower_{}", i)),
-            On::<Pointer<Over>>::run(|mut commands: Commands, event: Listener<Pointer<Over>>| {
-                commands.entity(event.target).insert(HoveredOutline);
-            }),
-            On::<Pointer<Out>>::run(|mut commands: Commands, event: Listener<Pointer<Out>>| {
-                commands.entity(event.target).remove::<HoveredOutline>();
-            }),
-        );
-
-        if is_enemy {
-            commands.spawn((tower_bundle, EnemyTower));
-        } else {
-            commands.spawn(tower_bundle);
-        }
-    }
+    println!("🏰 Loading tower model: {} at position {:?} with scale {}", tower_models[slot], position, tower_scale);
+    
+    let tower_bundle = (
+        SceneBundle {
+            scene: tower_scene,
+            transform: Transform::from_translation(position + Vec3::new(0.0, 5.0, 0.0))
+                .with_scale(Vec3::splat(tower_scale)),
+            ..default()
+        },
+        Tower { height: 10.0 },
+        TowerSlot(slot),
+        PickableBundle::default(),
+        ShapeType::Tower,
+        Health { current: 600.0, max: 600.0 },
+        bevy_rapier3d::prelude::RigidBody::Fixed,
+        bevy_rapier3d::prelude::Collider::cuboid(6.0, 8.0, 6.0), // Большой коллайдер для легкого клика по башням
+        bevy_rapier3d::prelude::Sensor,
+        bevy_rapier3d::prelude::LockedAxes::all(),
+        Name::new(format!("FactionTower_{}", slot)),
+        On::<Pointer<Over>>::run(|mut commands: Commands, event: Listener<Pointer<Over>>| {
+            commands.entity(event.target).insert(HoveredOutline);
+        }),
+        On::<Pointer<Out>>::run(|mut commands: Commands, event: Listener<Pointer<Out>>| {
+            commands.entity(event.target).remove::<HoveredOutline>();
+        }),
+    );
+
+    let tower = if is_enemy {
+        commands.spawn((tower_bundle, EnemyTower)).id()
+    } else {
+        commands.spawn(tower_bundle).id()
+    };
+    commands.entity(tower).insert((
+        crate::systems::tower::TowerRepair::default(),
+        crate::systems::tower::TowerTurret::new(crate::systems::tower::TurretRole::for_slot(slot)),
+    ));
+    tower
 }
\ No newline at end of file
@@ -4,7 +4,12 @@ use bevy::gizmos::gizmos::Gizmos;
 use bevy_rapier3d::prelude::*;
 use crate::game::components::{SteelFactory, FarmActive, FarmIncomeRate, SteelProductionRate, Selectable, HoveredOutline, ShapeType, Health};
 use crate::game::resources::FarmIncomeTimer;
-use crate::ui::money_ui::{Money, Steel, Wood, Iron};
+use crate::ui::money_ui::{Money, Steel, PurchasableItem};
+use crate::ui::cost_table::CostTable;
+use crate::game::components::ConstructionSite;
+use crate::game::construction::{pending_player_sites, spawn_construction_site};
+use crate::systems::ai_opponent::{AISide, SideResources};
+use crate::input::key_bindings::{InputAction, KeyBindings};
 
 /// System to update steel factory income (money and steel)
 pub fn update_steel_factory_income(
@@ -173,49 +178,42 @@ pub fn spawn_inactive_steel_factory(
     ));
 }
 
-/// Spawn a steel factory on keystroke (S key)
+/// Spawn a steel factory on keystroke (I key by default)
 pub fn spawn_steel_factory_on_keystroke(
     mut commands: Commands,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<StandardMaterial>>,
-    asset_server: Res<AssetServer>,
     keyboard_input: Res<ButtonInput<KeyCode>>,
-    mut money: ResMut<Money>,
-    mut wood: ResMut<Wood>,
-    mut iron: ResMut<Iron>,
+    key_bindings: Res<KeyBindings>,
+    mut resources: SideResources,
+    costs: Res<CostTable>,
     query: Query<&Transform, With<SteelFactory>>,
     // Добавляем запрос для проверки существующих сталелитейных заводов игрока
     player_factories: Query<Entity, (With<SteelFactory>, Without<crate::game::Enemy>)>,
+    sites: Query<&ConstructionSite, Without<crate::game::Enemy>>,
 ) {
-    if keyboard_input.just_pressed(KeyCode::KeyS) {
+    if key_bindings.just_pressed(InputAction::PlaceSteelFactory, &keyboard_input) {
         // Проверяем, есть ли уже сталелитейный завод у игрока
-        let existing_factories_count = player_factories.iter().count();
+        let existing_factories_count = player_factories.iter().count() + pending_player_sites(&sites, ShapeType::SteelFactory);
         if existing_factories_count >= 1 {
             info!("Cannot build more steel factories! Player can only have 1 steel factory maximum.");
             return;
         }
         
-        // Check if player has enough resources (10 money, 2 wood, 2 iron)
-        if money.0 >= 10.0 && wood.0 >= 2.0 && iron.0 >= 2.0 {
-            info!("Spawning a steel factory, cost: 10 money, 2 wood, 2 iron");
-            money.0 -= 10.0;
-            wood.0 -= 2.0;
-            iron.0 -= 2.0;
+        let cost = costs.get(PurchasableItem::SteelFactory);
+        let mut wallet = resources.wallet(AISide::Player);
+        if wallet.amounts().covers(&cost) {
+            info!("Laying out a steel factory, cost: {:?}", cost);
+            wallet.deduct(&cost);
             
             // Determine the position for the new factory on the right side of the map
             let position = find_free_position_in_area(&query, 5.0, Vec3::new(15.0, 0.0, 0.0), 8.0);
             
             info!("Found position for steel factory in right area: {:?}", position);
             
-            spawn_active_steel_factory(
-                &mut commands,
-                &mut meshes,
-                &mut materials,
-                position,
-                &asset_server,
-            );
+            spawn_construction_site(&mut commands, &mut meshes, &mut materials, ShapeType::SteelFactory, position);
         } else {
-            info!("Not enough resources to spawn a steel factory! Need 10 money, 2 wood and 2 iron");
+            info!("Not enough resources to spawn a steel factory! Need {:?}", cost);
         }
     }
 }
@@ -227,7 +225,7 @@ pub fn spawn_active_steel_factory(
     _materials: &mut Assets<StandardMaterial>,
     position: Vec3,
     asset_server: &AssetServer,
-) {
+) -> Entity {
     commands.spawn((
         SceneBundle {
             scene: asset_server.load("models/farm/factory.glb#Scene0"),
@@ -253,7 +251,7 @@ pub fn spawn_active_steel_factory(
         On::<Pointer<Out>>::run(|mut commands: Commands, event: Listener<Pointer<Out>>| {
             commands.entity(event.target).remove::<HoveredOutline>();
         }),
-    ));
+    )).id()
 }
 
 /// Finds a free position to place a building in the specified area
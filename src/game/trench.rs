@@ -2,9 +2,16 @@ use bevy::prelude::*;
 use bevy_mod_picking::prelude::*;
 use crate::game::{
     Health, Selectable, ShapeType, TrenchConstruction,
-    Trench, HoveredOutline, TrenchCost
+    Trench, HoveredOutline, TrenchCost, TrenchGarrison, Garrisoned, GarrisonOrder,
+    MovementOrder, Selection, Enemy, PlacementState,
 };
+use crate::game::units::infantry::Infantry;
+use crate::systems::formations::FormationSpeed;
+use crate::input::selection::MouseWorldPosition;
+use crate::systems::pathfinding::{structure_footprint, MovementPath};
+use crate::systems::turn_system::{PlayerTurn, TurnState};
 use crate::ui::money_ui::{Money, Wood};
+use crate::input::key_bindings::{InputAction, KeyBindings};
 
 // Функция создания окопа под строительство
 pub fn spawn_constructing_trench(
@@ -12,14 +19,15 @@ pub fn spawn_constructing_trench(
     meshes: &mut ResMut<Assets<Mesh>>,
     materials: &mut ResMut<Assets<StandardMaterial>>,
     position: Vec3,
-) {
-    let construction_time = 6.0; // 6 секунд на строительство
+    rotation: Quat,
+) -> Entity {
+    let construction_time = 6.0; // 6 секунд работы одного инженера
     
     commands.spawn((
         PbrBundle {
             mesh: meshes.add(Mesh::from(Cuboid::new(2.0, 0.5, 1.5))),
             material: materials.add(Color::rgb(0.6, 0.4, 0.2)), // цвет земли/грязи для окопа
-            transform: Transform::from_translation(position + Vec3::new(0.0, 0.25, 0.0)),
+            transform: Transform::from_translation(position + Vec3::new(0.0, 0.25, 0.0)).with_rotation(rotation),
             ..default()
         },
         Trench,
@@ -38,7 +46,7 @@ pub fn spawn_constructing_trench(
         On::<Pointer<Out>>::run(|mut commands: Commands, event: Listener<Pointer<Out>>| {
             commands.entity(event.target).remove::<HoveredOutline>();
         }),
-    ));
+    )).id()
 }
 
 // Функция для создания уже построенного окопа
@@ -47,12 +55,13 @@ pub fn spawn_completed_trench(
     meshes: &mut ResMut<Assets<Mesh>>,
     materials: &mut ResMut<Assets<StandardMaterial>>,
     position: Vec3,
-) {
+    rotation: Quat,
+) -> Entity {
     commands.spawn((
         PbrBundle {
             mesh: meshes.add(Mesh::from(Cuboid::new(2.0, 0.5, 1.5))),
             material: materials.add(Color::rgb(0.5, 0.35, 0.15)), // цвет готового окопа
-            transform: Transform::from_translation(position + Vec3::new(0.0, 0.25, 0.0)),
+            transform: Transform::from_translation(position + Vec3::new(0.0, 0.25, 0.0)).with_rotation(rotation),
             ..default()
         },
         Trench,
@@ -60,6 +69,7 @@ pub fn spawn_completed_trench(
         PickableBundle::default(),
         ShapeType::Trench,
         Health { current: 100.0, max: 100.0 }, 
+        TrenchGarrison::default(),
         Name::new("Trench"),
         On::<Pointer<Over>>::run(|mut commands: Commands, event: Listener<Pointer<Over>>| {
             commands.entity(event.target).insert(HoveredOutline);
@@ -67,20 +77,17 @@ pub fn spawn_completed_trench(
         On::<Pointer<Out>>::run(|mut commands: Commands, event: Listener<Pointer<Out>>| {
             commands.entity(event.target).remove::<HoveredOutline>();
         }),
-    ));
+    )).id()
 }
 
-// Система для обработки строительства окопов
+// Система для завершения строительства окопов: копают инженеры (construction::engineer_work)
 pub fn update_trench_construction(
     mut commands: Commands,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<StandardMaterial>>,
-    mut query: Query<(Entity, &mut TrenchConstruction, &Transform), With<Trench>>,
-    time: Res<Time>,
+    query: Query<(Entity, &TrenchConstruction, &Transform), With<Trench>>,
 ) {
-    for (entity, mut construction, transform) in query.iter_mut() {
-        construction.time_remaining -= time.delta_seconds();
-        
+    for (entity, construction, transform) in query.iter() {
         if construction.time_remaining <= 0.0 {
             info!("Trench construction completed!");
             
@@ -92,7 +99,8 @@ pub fn update_trench_construction(
                 &mut commands, 
                 &mut meshes, 
                 &mut materials, 
-                transform.translation - Vec3::new(0.0, 0.25, 0.0) // Отнимаем смещение, которое было добавлено при создании
+                transform.translation - Vec3::new(0.0, 0.25, 0.0), // Отнимаем смещение, которое было добавлено при создании
+                transform.rotation,
             );
         }
     }
@@ -115,51 +123,358 @@ pub fn draw_trench_construction_progress(
     }
 }
 
-// Система для постройки окопа по нажатию клавиши
-pub fn spawn_trench_on_keystroke(
+/// Длина одного сегмента окопа вдоль линии
+pub const TRENCH_SEGMENT_LENGTH: f32 = 2.0;
+/// Самая длинная линия окопов, которую можно проложить за раз
+const MAX_TRENCH_SEGMENTS: usize = 12;
+/// Короче этого перетаскивание считается простым кликом
+const TRENCH_DRAG_THRESHOLD: f32 = 0.5;
+
+const TRENCH_GHOST_COLOR: Color = Color::rgba(0.3, 1.0, 0.3, 0.8);
+const TRENCH_GHOST_BLOCKED_COLOR: Color = Color::rgba(1.0, 0.2, 0.2, 0.8);
+const TRENCH_GHOST_EXPENSIVE_COLOR: Color = Color::rgba(1.0, 0.6, 0.0, 0.8);
+
+/// Start of the trench line while the left button is held in trench placement mode
+#[derive(Resource, Default)]
+pub struct TrenchDrag(pub Option<Vec3>);
+
+/// Ground rectangle of a structure: center, half extents and the direction of its local x axis
+struct Footprint {
+    center: Vec2,
+    half_extents: Vec2,
+    axis: Vec2,
+}
+
+impl Footprint {
+    fn new(translation: Vec3, rotation: Quat, half_extents: Vec2) -> Self {
+        let axis = rotation * Vec3::X;
+        Self {
+            center: Vec2::new(translation.x, translation.z),
+            half_extents,
+            axis: Vec2::new(axis.x, axis.z).try_normalize().unwrap_or(Vec2::X),
+        }
+    }
+
+    fn axes(&self) -> [Vec2; 2] {
+        [self.axis, self.axis.perp()]
+    }
+
+    fn radius_along(&self, direction: Vec2) -> f32 {
+        let [x, z] = self.axes();
+        self.half_extents.x * x.dot(direction).abs() + self.half_extents.y * z.dot(direction).abs()
+    }
+
+    /// Separating axis test for two rotated rectangles
+    fn overlaps(&self, other: &Footprint) -> bool {
+        let offset = other.center - self.center;
+        self.axes().into_iter().chain(other.axes()).all(|axis| {
+            offset.dot(axis).abs() < self.radius_along(axis) + other.radius_along(axis)
+        })
+    }
+}
+
+/// Splits the line from `start` to `end` into trench segments: a click without a drag places one trench
+pub fn trench_line_segments(start: Vec3, end: Vec3) -> Vec<Transform> {
+    let delta = Vec3::new(end.x - start.x, 0.0, end.z - start.z);
+    let length = delta.length();
+    if length < TRENCH_DRAG_THRESHOLD {
+        return vec![Transform::from_xyz(start.x, 0.0, start.z)];
+    }
+
+    let direction = delta / length;
+    let rotation = Quat::from_rotation_arc(Vec3::X, direction);
+    let count = ((length / TRENCH_SEGMENT_LENGTH).ceil() as usize).clamp(1, MAX_TRENCH_SEGMENTS);
+    (0..count)
+        .map(|index| {
+            let center = start + direction * TRENCH_SEGMENT_LENGTH * (index as f32 + 0.5);
+            Transform::from_xyz(center.x, 0.0, center.z).with_rotation(rotation)
+        })
+        .collect()
+}
+
+/// Whether a trench segment would overlap a building, a tower or another trench
+fn segment_blocked(segment: &Transform, structures: &Query<(&Transform, &ShapeType)>) -> bool {
+    let Some(trench_half_extents) = structure_footprint(ShapeType::Trench) else {
+        return false;
+    };
+    let footprint = Footprint::new(segment.translation, segment.rotation, trench_half_extents);
+    structures.iter().any(|(transform, shape)| {
+        structure_footprint(*shape).is_some_and(|half_extents| {
+            footprint.overlaps(&Footprint::new(transform.translation, transform.rotation, half_extents))
+        })
+    })
+}
+
+fn trench_placement_active(placement_state: &PlacementState) -> bool {
+    placement_state.active && matches!(placement_state.shape_type, Some(ShapeType::Trench))
+}
+
+/// The trench hotkey toggles trench placement mode
+pub fn toggle_trench_placement(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    key_bindings: Res<KeyBindings>,
+    turn_state: Res<TurnState>,
+    mut placement_state: ResMut<PlacementState>,
+    mut trench_drag: ResMut<TrenchDrag>,
+) {
+    if !key_bindings.just_pressed(InputAction::PlaceTrench, &keyboard) || turn_state.current_player != PlayerTurn::Human {
+        return;
+    }
+
+    if trench_placement_active(&placement_state) {
+        placement_state.active = false;
+        placement_state.shape_type = None;
+        trench_drag.0 = None;
+        info!("🪖 Trench placement cancelled");
+    } else if !placement_state.active {
+        // Начатое размещение здания не перебиваем
+        placement_state.active = true;
+        placement_state.shape_type = Some(ShapeType::Trench);
+        placement_state.unit_type_index = None;
+        placement_state.cost = None;
+        info!("🪖 Trench placement: click to dig one trench, drag to dig a line");
+    }
+}
+
+/// Press on the ground starts a trench line, release pays for every segment and starts digging.
+/// Runs after the ground click handler, so the release does not also send the selected units there.
+#[allow(clippy::too_many_arguments)]
+pub fn place_trench_line(
     mut commands: Commands,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<StandardMaterial>>,
-    keyboard: Res<ButtonInput<KeyCode>>,
+    buttons: Res<ButtonInput<MouseButton>>,
+    mouse_world_position: Res<MouseWorldPosition>,
+    turn_state: Res<TurnState>,
+    mut placement_state: ResMut<PlacementState>,
+    mut trench_drag: ResMut<TrenchDrag>,
     mut money: ResMut<Money>,
     mut wood: ResMut<Wood>,
-    trench_cost: Option<Res<TrenchCost>>,
-    time: Res<Time>,
+    trench_cost: Res<TrenchCost>,
+    structures: Query<(&Transform, &ShapeType)>,
+    ui_interactions: Query<&Interaction>,
 ) {
-    let cost = match trench_cost {
-        Some(ref cost) => cost,
-        None => {
-            // Если ресурс не инициализирован, используем значения по умолчанию
-            let default_cost = TrenchCost::default();
-            commands.insert_resource(default_cost);
-            return; // Возвращаемся, чтобы в следующем кадре использовать созданный ресурс
+    if !trench_placement_active(&placement_state) || turn_state.current_player != PlayerTurn::Human {
+        trench_drag.0 = None;
+        return;
+    }
+    let Some(cursor) = mouse_world_position.0 else {
+        return;
+    };
+
+    if buttons.just_pressed(MouseButton::Left) {
+        // Нажатие по кнопкам интерфейса и миникарте не начинает линию
+        if ui_interactions.iter().any(|interaction| *interaction != Interaction::None) {
+            return;
         }
+        trench_drag.0 = Some(cursor);
+    }
+
+    if !buttons.just_released(MouseButton::Left) {
+        return;
+    }
+    let Some(start) = trench_drag.0.take() else {
+        return;
     };
-    
-    // Построить окоп при нажатии на клавишу 'B' (от слова Build)
-    if keyboard.just_pressed(KeyCode::KeyB) {
-        // Проверка наличия ресурсов
-        if money.0 >= cost.money as f32 && wood.0 >= cost.wood as f32 {
-            // Списываем ресурсы
-            money.0 -= cost.money as f32;
-            wood.0 -= cost.wood as f32;
-            
-            // Определяем положение для окопа, используя время вместо rand
-            let seed = time.elapsed_seconds_f64().fract() as f32;
-            let x = (seed * 100.0).sin() * 10.0 - 5.0;
-            let z = (seed * 100.0).cos() * 10.0 - 5.0;
-            let trench_position = Vec3::new(x, 0.0, z);
-            
-            info!("Spawning new trench at position: {:?}, cost: {} wood, {} money", trench_position, cost.wood, cost.money);
-            
-            spawn_constructing_trench(
-                &mut commands,
-                &mut meshes,
-                &mut materials,
-                trench_position,
-            );
-        } else {
-            info!("Not enough resources to build a trench! Need {} wood and {} money", cost.wood, cost.money);
+
+    let segments = trench_line_segments(start, cursor);
+    if segments.iter().any(|segment| segment_blocked(segment, &structures)) {
+        info!("🪖 Cannot dig a trench over buildings");
+        return;
+    }
+
+    let count = segments.len() as f32;
+    let (money_needed, wood_needed) = (trench_cost.money as f32 * count, trench_cost.wood as f32 * count);
+    if money.0 < money_needed || wood.0 < wood_needed {
+        info!("Not enough resources to build {} trench segment(s)! Need {} wood and {} money", segments.len(), wood_needed, money_needed);
+        return;
+    }
+    money.0 -= money_needed;
+    wood.0 -= wood_needed;
+
+    for segment in segments.iter() {
+        spawn_constructing_trench(&mut commands, &mut meshes, &mut materials, segment.translation, segment.rotation);
+    }
+    info!("🪖 Digging {} trench segment(s), cost: {} wood, {} money", segments.len(), wood_needed, money_needed);
+
+    placement_state.active = false;
+    placement_state.shape_type = None;
+}
+
+/// Draws the ghost of the trench line: green when it can be dug, red over buildings, orange when too expensive
+#[allow(clippy::too_many_arguments)]
+pub fn draw_trench_placement_preview(
+    mut gizmos: Gizmos,
+    placement_state: Res<PlacementState>,
+    mouse_world_position: Res<MouseWorldPosition>,
+    trench_drag: Res<TrenchDrag>,
+    trench_cost: Res<TrenchCost>,
+    money: Res<Money>,
+    wood: Res<Wood>,
+    structures: Query<(&Transform, &ShapeType)>,
+) {
+    if !trench_placement_active(&placement_state) {
+        return;
+    }
+    let Some(cursor) = mouse_world_position.0 else {
+        return;
+    };
+
+    let segments = trench_line_segments(trench_drag.0.unwrap_or(cursor), cursor);
+    let count = segments.len() as f32;
+    let affordable = money.0 >= trench_cost.money as f32 * count && wood.0 >= trench_cost.wood as f32 * count;
+    let blocked = segments.iter().any(|segment| segment_blocked(segment, &structures));
+    let color = if blocked {
+        TRENCH_GHOST_BLOCKED_COLOR
+    } else if !affordable {
+        TRENCH_GHOST_EXPENSIVE_COLOR
+    } else {
+        TRENCH_GHOST_COLOR
+    };
+
+    let Some(half_extents) = structure_footprint(ShapeType::Trench) else {
+        return;
+    };
+    for segment in segments.iter() {
+        let corners = [
+            Vec3::new(-half_extents.x, 0.05, -half_extents.y),
+            Vec3::new(half_extents.x, 0.05, -half_extents.y),
+            Vec3::new(half_extents.x, 0.05, half_extents.y),
+            Vec3::new(-half_extents.x, 0.05, half_extents.y),
+        ]
+        .map(|corner| segment.translation + segment.rotation * corner);
+        gizmos.linestrip(corners.into_iter().chain(std::iter::once(corners[0])), color);
+    }
+}
+
+/// Сколько пехотинцев помещается в окоп
+pub const TRENCH_CAPACITY: usize = 4;
+/// Доля урона, которую получает пехота в окопе
+pub const TRENCH_DAMAGE_TAKEN: f32 = 0.4;
+/// На таком расстоянии от окопа пехота спрыгивает в него
+const TRENCH_ENTER_DISTANCE: f32 = 2.0;
+
+/// Place of the `index`-th occupant along the trench
+fn garrison_slot(trench: &Transform, index: usize, height: f32) -> Vec3 {
+    let offset = -0.75 + 0.5 * index as f32;
+    let slot = trench.translation + trench.rotation * Vec3::new(offset, 0.0, 0.0);
+    Vec3::new(slot.x, height, slot.z)
+}
+
+type GarrisonCandidate = (With<Infantry>, Without<Enemy>);
+type CompletedTrench = (With<Trench>, Without<TrenchConstruction>);
+
+/// Right-click on a completed trench sends the selected infantry into it
+pub fn order_trench_garrison(
+    mut commands: Commands,
+    mut pointer_down: EventReader<Pointer<Down>>,
+    selection: Res<Selection>,
+    turn_state: Res<TurnState>,
+    trenches: Query<(&Transform, &TrenchGarrison), CompletedTrench>,
+    infantry: Query<(), GarrisonCandidate>,
+) {
+    for event in pointer_down.read() {
+        if event.button != PointerButton::Secondary || turn_state.current_player != PlayerTurn::Human {
+            continue;
+        }
+        let Ok((trench_transform, garrison)) = trenches.get(event.target) else {
+            continue;
+        };
+
+        let soldiers: Vec<Entity> = selection.iter().filter(|entity| infantry.get(*entity).is_ok()).collect();
+        if soldiers.is_empty() {
+            info!("🪖 Only infantry can occupy a trench");
+            continue;
+        }
+        let free = TRENCH_CAPACITY.saturating_sub(garrison.count());
+        if free == 0 {
+            info!("🪖 The trench is full ({} / {})", garrison.count(), TRENCH_CAPACITY);
+            continue;
+        }
+
+        // Лишние бойцы остаются на месте - места на всех не хватит
+        for soldier in soldiers.iter().take(free) {
+            commands.entity(*soldier)
+                .remove::<(FormationSpeed, MovementPath)>()
+                .insert((MovementOrder(trench_transform.translation), GarrisonOrder(event.target)));
+        }
+        info!("🪖 {} soldier(s) ordered into trench {:?}", soldiers.len().min(free), event.target);
+    }
+}
+
+/// Infantry that reached its trench takes a place inside, if there is one left
+pub fn enter_trenches(
+    mut commands: Commands,
+    mut soldiers: Query<(Entity, &GarrisonOrder, &mut Transform, Option<&MovementOrder>), Without<Trench>>,
+    mut trenches: Query<(&Transform, &mut TrenchGarrison), With<Trench>>,
+) {
+    for (soldier, order, mut transform, movement_order) in soldiers.iter_mut() {
+        let Ok((trench_transform, mut garrison)) = trenches.get_mut(order.0) else {
+            commands.entity(soldier).remove::<GarrisonOrder>();
+            continue;
+        };
+        let trench_position = trench_transform.translation;
+        let flat_distance = Vec2::new(transform.translation.x - trench_position.x, transform.translation.z - trench_position.z).length();
+
+        if flat_distance <= TRENCH_ENTER_DISTANCE {
+            // Занимаем место, освобожденное ушедшим бойцом, а не следующее за последним
+            if let Some(slot) = garrison.free_slot(TRENCH_CAPACITY) {
+                transform.translation = garrison_slot(trench_transform, slot, transform.translation.y);
+                if garrison.occupants.len() <= slot {
+                    garrison.occupants.resize(slot + 1, None);
+                }
+                garrison.occupants[slot] = Some(soldier);
+                commands.entity(soldier)
+                    .remove::<(GarrisonOrder, MovementOrder, FormationSpeed, MovementPath)>()
+                    .insert(Garrisoned { trench: order.0 });
+                info!("🪖 Soldier {:?} took cover in trench {:?} ({} / {})", soldier, order.0, garrison.count(), TRENCH_CAPACITY);
+            } else {
+                commands.entity(soldier).remove::<(GarrisonOrder, MovementOrder, FormationSpeed, MovementPath)>();
+                info!("🪖 Trench {:?} is full, soldier {:?} stays outside", order.0, soldier);
+            }
+        } else if movement_order.is_none_or(|movement| movement.0.distance(trench_position) > 0.1) {
+            // Новый приказ движения или юнит остановился раньше - окоп отменяется
+            commands.entity(soldier).remove::<GarrisonOrder>();
+        }
+    }
+}
+
+/// A new move order takes the soldier out of the trench
+pub fn leave_trenches(
+    mut commands: Commands,
+    leaving: Query<(Entity, &Garrisoned), Changed<MovementOrder>>,
+    mut trenches: Query<&mut TrenchGarrison>,
+) {
+    for (soldier, garrisoned) in leaving.iter() {
+        if let Ok(mut garrison) = trenches.get_mut(garrisoned.trench) {
+            garrison.remove(soldier);
+        }
+        commands.entity(soldier).remove::<Garrisoned>();
+        info!("🪖 Soldier {:?} left trench {:?}", soldier, garrisoned.trench);
+    }
+}
+
+/// Forgets killed occupants and throws the garrison out of a trench that collapsed
+pub fn update_trench_garrisons(
+    mut commands: Commands,
+    mut trenches: Query<&mut TrenchGarrison>,
+    garrisoned: Query<(Entity, &Garrisoned)>,
+    infantry: Query<(), With<Infantry>>,
+) {
+    for mut garrison in trenches.iter_mut() {
+        let killed: Vec<Entity> = garrison.occupants.iter()
+            .flatten()
+            .copied()
+            .filter(|occupant| infantry.get(*occupant).is_err())
+            .collect();
+        for occupant in killed {
+            garrison.remove(occupant);
         }
     }
-} 
\ No newline at end of file
+    for (soldier, garrisoned) in garrisoned.iter() {
+        if trenches.get(garrisoned.trench).is_err() {
+            commands.entity(soldier).remove::<Garrisoned>();
+            info!("🪖 Trench {:?} collapsed, soldier {:?} is in the open", garrisoned.trench, soldier);
+        }
+    }
+}
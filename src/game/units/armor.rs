@@ -0,0 +1,146 @@
+use bevy::prelude::*;
+use serde::Deserialize;
+use crate::utils::BalanceAsset;
+use super::catalog::UnitClass;
+
+/// Путь к матрице урона внутри assets/
+pub const DAMAGE_MATRIX_PATH: &str = "balance/default.damage.toml";
+
+/// What kind of weapon a shot comes from
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DamageType {
+    SmallArms,
+    Cannon,
+    AerialBomb,
+}
+
+impl DamageType {
+    pub fn for_shooter(is_tank: bool, is_aircraft: bool) -> Self {
+        if is_tank {
+            DamageType::Cannon
+        } else if is_aircraft {
+            DamageType::AerialBomb
+        } else {
+            DamageType::SmallArms
+        }
+    }
+
+    pub fn for_class(class: UnitClass) -> Self {
+        Self::for_shooter(class == UnitClass::Tank, class == UnitClass::Aircraft)
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            DamageType::SmallArms => "Small arms",
+            DamageType::Cannon => "Cannon",
+            DamageType::AerialBomb => "Aerial bombs",
+        }
+    }
+}
+
+/// How well the target is protected
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ArmorClass {
+    Light,
+    Heavy,
+    Structure,
+    Air,
+}
+
+impl ArmorClass {
+    pub const ALL: [ArmorClass; 4] = [ArmorClass::Light, ArmorClass::Heavy, ArmorClass::Structure, ArmorClass::Air];
+
+    /// Пехота - легкая броня, танки - тяжелая, авиация - воздух, все остальное - здания
+    pub fn of(is_infantry: bool, is_tank: bool, is_aircraft: bool) -> Self {
+        if is_infantry {
+            ArmorClass::Light
+        } else if is_tank {
+            ArmorClass::Heavy
+        } else if is_aircraft {
+            ArmorClass::Air
+        } else {
+            ArmorClass::Structure
+        }
+    }
+
+    pub fn for_class(class: UnitClass) -> Self {
+        Self::of(class == UnitClass::Infantry, class == UnitClass::Tank, class == UnitClass::Aircraft)
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ArmorClass::Light => "Light",
+            ArmorClass::Heavy => "Heavy",
+            ArmorClass::Structure => "Structure",
+            ArmorClass::Air => "Air",
+        }
+    }
+}
+
+/// Damage multipliers of one weapon type against each armor class
+#[derive(Deserialize, Clone, Copy, Debug)]
+pub struct ArmorMultipliers {
+    #[serde(default = "full_damage")]
+    pub light: f32,
+    #[serde(default = "full_damage")]
+    pub heavy: f32,
+    #[serde(default = "full_damage")]
+    pub structure: f32,
+    #[serde(default = "full_damage")]
+    pub air: f32,
+}
+
+fn full_damage() -> f32 {
+    1.0
+}
+
+impl ArmorMultipliers {
+    pub fn against(&self, armor: ArmorClass) -> f32 {
+        match armor {
+            ArmorClass::Light => self.light,
+            ArmorClass::Heavy => self.heavy,
+            ArmorClass::Structure => self.structure,
+            ArmorClass::Air => self.air,
+        }
+    }
+}
+
+impl Default for ArmorMultipliers {
+    fn default() -> Self {
+        Self { light: 1.0, heavy: 1.0, structure: 1.0, air: 1.0 }
+    }
+}
+
+/// Damage-type vs armor-class multipliers loaded from assets/balance/default.damage.toml.
+/// Until the file is loaded every multiplier is 1.0, i.e. plain `CanShoot.damage`.
+#[derive(Asset, TypePath, Resource, Deserialize, Clone, Debug, Default)]
+pub struct DamageMatrix {
+    #[serde(default)]
+    pub small_arms: ArmorMultipliers,
+    #[serde(default)]
+    pub cannon: ArmorMultipliers,
+    #[serde(default)]
+    pub aerial_bomb: ArmorMultipliers,
+}
+
+impl DamageMatrix {
+    pub fn multiplier(&self, damage: DamageType, armor: ArmorClass) -> f32 {
+        let row = match damage {
+            DamageType::SmallArms => &self.small_arms,
+            DamageType::Cannon => &self.cannon,
+            DamageType::AerialBomb => &self.aerial_bomb,
+        };
+        row.against(armor)
+    }
+
+    /// Урон одного выстрела по цели с учетом брони
+    pub fn apply(&self, base_damage: f32, damage: DamageType, armor: ArmorClass) -> f32 {
+        base_damage * self.multiplier(damage, armor)
+    }
+}
+
+impl BalanceAsset for DamageMatrix {
+    const PATH: &'static str = DAMAGE_MATRIX_PATH;
+    const EXTENSION: &'static str = "damage.toml";
+    const NAME: &'static str = "Damage matrix";
+}
@@ -0,0 +1,124 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use crate::menu::main_menu::Faction;
+use crate::ui::money_ui::ResourceCost;
+use super::MilitaryUnit;
+use crate::game::components::CanShoot;
+use crate::utils::BalanceAsset;
+
+/// Путь к каталогу юнитов внутри assets/
+pub const UNIT_CATALOG_PATH: &str = "balance/default.units.toml";
+
+/// Class of a catalog unit (one purchase menu row per class)
+#[derive(Component, Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum UnitClass {
+    Infantry,
+    Tank,
+    Aircraft,
+}
+
+/// Which catalog entry a spawned unit was built from
+#[derive(Component, Clone, Copy, Debug)]
+pub struct UnitVariant {
+    pub faction: Faction,
+    pub class: UnitClass,
+    pub index: usize,
+}
+
+/// Combat and movement stats of a catalog unit
+#[derive(Deserialize, Clone, Copy, Debug)]
+pub struct UnitStats {
+    pub speed: f32,
+    pub health: f32,
+    pub attack_damage: f32,
+    pub attack_speed: f32, // attacks per second
+    pub range: f32,
+    /// Радиус обзора в тумане войны; если не задан - по классу юнита
+    #[serde(default)]
+    pub vision: Option<f32>,
+}
+
+/// One unit entry: display name, model, stats and cost
+#[derive(Deserialize, Clone, Debug)]
+pub struct UnitDefinition {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    pub model: String,
+    pub scale: f32,
+    /// Высота полета, используется только для авиации
+    #[serde(default)]
+    pub altitude: f32,
+    pub stats: UnitStats,
+    pub cost: ResourceCost,
+    /// Инженер: строит здания и окопы, чинит здания и башни
+    #[serde(default)]
+    pub engineer: bool,
+}
+
+impl UnitDefinition {
+    pub fn military_unit(&self) -> MilitaryUnit {
+        MilitaryUnit {
+            speed: self.stats.speed,
+            health: self.stats.health,
+            max_health: self.stats.health,
+            attack_damage: self.stats.attack_damage,
+            attack_speed: self.stats.attack_speed,
+            cost: self.cost.money as u32,
+        }
+    }
+
+    pub fn can_shoot(&self) -> CanShoot {
+        CanShoot {
+            cooldown: 1.0 / self.stats.attack_speed,
+            last_shot: 0.0,
+            range: self.stats.range,
+            damage: self.stats.attack_damage,
+        }
+    }
+}
+
+/// Units available to one faction, grouped by class
+#[derive(Deserialize, Clone, Debug, Default)]
+pub struct FactionUnits {
+    #[serde(default)]
+    pub infantry: Vec<UnitDefinition>,
+    #[serde(default)]
+    pub tanks: Vec<UnitDefinition>,
+    #[serde(default)]
+    pub aircraft: Vec<UnitDefinition>,
+}
+
+/// Unit catalog loaded from assets/balance/default.units.toml.
+/// The asset is copied into a resource of the same type whenever it (re)loads, see `BalancePlugin`.
+#[derive(Asset, TypePath, Resource, Deserialize, Clone, Debug, Default)]
+pub struct UnitCatalog {
+    #[serde(default)]
+    pub entente: FactionUnits,
+    #[serde(default)]
+    pub central_powers: FactionUnits,
+}
+
+impl UnitCatalog {
+    pub fn units(&self, faction: Faction, class: UnitClass) -> &[UnitDefinition] {
+        let faction_units = match faction {
+            Faction::Entente => &self.entente,
+            Faction::CentralPowers => &self.central_powers,
+        };
+        match class {
+            UnitClass::Infantry => &faction_units.infantry,
+            UnitClass::Tank => &faction_units.tanks,
+            UnitClass::Aircraft => &faction_units.aircraft,
+        }
+    }
+
+    pub fn get(&self, faction: Faction, class: UnitClass, index: usize) -> Option<&UnitDefinition> {
+        self.units(faction, class).get(index)
+    }
+}
+
+impl BalanceAsset for UnitCatalog {
+    const PATH: &'static str = UNIT_CATALOG_PATH;
+    const EXTENSION: &'static str = "units.toml";
+    const NAME: &'static str = "Unit catalog";
+}
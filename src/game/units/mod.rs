@@ -1,9 +1,14 @@
 use bevy::prelude::*;
 use crate::menu::main_menu::Faction;
+use crate::game::components::{CanShoot, Engineer, Vision};
 
 pub mod infantry;
 pub mod tanks;
 pub mod aircraft;
+pub mod catalog;
+pub mod armor;
+
+use catalog::{UnitCatalog, UnitClass, UnitVariant};
 
 /// Resource to store the player's selected faction
 #[derive(Resource, Clone, Copy)]
@@ -50,6 +55,57 @@ pub struct MilitaryUnit {
     pub cost: u32,
 }
 
+impl MilitaryUnit {
+    /// Пауза между выстрелами в секундах
+    pub fn cooldown(&self) -> f32 {
+        1.0 / self.attack_speed
+    }
+}
+
+/// Скорость юнита, если он не каталожный (стартовые юниты, старые сохранения)
+pub const DEFAULT_MOVE_SPEED: f32 = 2.0;
+
+/// Movement speed of an entity: its `MilitaryUnit` stats, or the default for non-catalog objects
+pub fn move_speed(unit: Option<&MilitaryUnit>) -> f32 {
+    unit.map_or(DEFAULT_MOVE_SPEED, |unit| unit.speed)
+}
+
+/// Damage and cooldown of a shooter: its `MilitaryUnit` stats, or `CanShoot` for non-catalog shooters
+pub fn weapon_stats(can_shoot: &CanShoot, unit: Option<&MilitaryUnit>) -> (f32, f32) {
+    unit.map_or((can_shoot.damage, can_shoot.cooldown), |unit| (unit.attack_damage, unit.cooldown()))
+}
+
+/// Spawns a catalog unit of the given faction, class and index.
+/// Returns None if the catalog has no such entry (e.g. it has not finished loading yet)
+pub fn spawn_unit(
+    commands: &mut Commands,
+    asset_server: &AssetServer,
+    catalog: &UnitCatalog,
+    faction: Faction,
+    class: UnitClass,
+    index: usize,
+    position: Vec3,
+) -> Option<Entity> {
+    let Some(definition) = catalog.get(faction, class, index) else {
+        warn!("No {:?} #{} for {:?} in unit catalog", class, index, faction);
+        return None;
+    };
+    let variant = UnitVariant { faction, class, index };
+
+    let entity = match class {
+        UnitClass::Infantry => infantry::spawn_infantry(commands, asset_server, definition, variant, position),
+        UnitClass::Tank => tanks::spawn_tank(commands, asset_server, definition, variant, position),
+        UnitClass::Aircraft => aircraft::spawn_aircraft(commands, asset_server, definition, variant, position),
+    };
+    if let Some(vision) = definition.stats.vision {
+        commands.entity(entity).insert(Vision(vision));
+    }
+    if definition.engineer {
+        commands.entity(entity).insert(Engineer);
+    }
+    Some(entity)
+}
+
 /// Plugin to register all unit-related systems and resources
 pub struct UnitsPlugin;
 
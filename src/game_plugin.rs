@@ -1,5 +1,5 @@
 use crate::{
-    menu::common::{despawn_screen, GameState},
+    menu::common::{despawn_screen, OnMatchEnd, OnMatchStart},
     systems::aircraft::spawn_initial_aircraft,
     game::setup,
 };
@@ -14,8 +14,8 @@ struct GameTimer(Timer);
 
 pub fn game_plugin(app: &mut App) {
     app.add_systems(
-        OnEnter(GameState::Game),
+        OnMatchStart,
         (setup::setup, spawn_initial_aircraft),
     )
-    .add_systems(OnExit(GameState::Game), despawn_screen::<OnGameScreen>);
+    .add_systems(OnMatchEnd, despawn_screen::<OnGameScreen>);
 }
@@ -1,87 +1,246 @@
 use bevy::{prelude::*, input::mouse::MouseWheel};
 use bevy::input::mouse::MouseMotion;
 
-use crate::game::{MainCamera, CameraSettings, CameraMovementState, SelectedEntity};
+use crate::game::{MainCamera, CameraSettings, CameraMovementState, Selection};
+use crate::input::key_bindings::{InputAction, KeyBindings};
+use crate::ui::minimap::MinimapDrag;
 
-/// system for handling mouse wheel scrolling and changing the camera zoom
+/// Стартовое положение камеры относительно точки, на которую она смотрит
+const CAMERA_START_OFFSET: Vec3 = Vec3::new(-2.5, 15.0, 20.0);
+
+/// The RTS camera orbits a focus point on the ground. Input changes the `target_*` values,
+/// the camera itself glides towards them in `apply_camera_rig`.
+#[derive(Resource, Clone, Copy, Debug)]
+pub struct CameraRig {
+    pub focus: Vec3,
+    pub target_focus: Vec3,
+    /// Поворот вокруг точки фокуса, радианы
+    pub yaw: f32,
+    pub target_yaw: f32,
+    /// Расстояние до точки фокуса при zoom_level = 1
+    pub base_distance: f32,
+    pub distance: f32,
+    /// Наклон камеры к земле, радианы
+    pub pitch: f32,
+}
+
+impl Default for CameraRig {
+    fn default() -> Self {
+        let horizontal = Vec2::new(CAMERA_START_OFFSET.x, CAMERA_START_OFFSET.z);
+        let yaw = horizontal.x.atan2(horizontal.y);
+        let distance = CAMERA_START_OFFSET.length();
+        Self {
+            focus: Vec3::ZERO,
+            target_focus: Vec3::ZERO,
+            yaw,
+            target_yaw: yaw,
+            base_distance: distance,
+            distance,
+            pitch: CAMERA_START_OFFSET.y.atan2(horizontal.length()),
+        }
+    }
+}
+
+impl CameraRig {
+    /// Direction the camera looks at along the ground
+    pub fn forward(&self) -> Vec3 {
+        -Vec3::new(self.target_yaw.sin(), 0.0, self.target_yaw.cos())
+    }
+
+    pub fn right(&self) -> Vec3 {
+        let forward = self.forward();
+        Vec3::new(-forward.z, 0.0, forward.x)
+    }
+
+    /// Pans the wanted focus by `direction` (x - right, y - forward) scaled to the current zoom
+    fn pan(&mut self, direction: Vec2, amount: f32) {
+        let scale = self.distance / self.base_distance;
+        self.target_focus += (self.right() * direction.x + self.forward() * direction.y) * amount * scale;
+    }
+
+    fn transform(&self) -> Transform {
+        let offset = Vec3::new(
+            self.yaw.sin() * self.pitch.cos(),
+            self.pitch.sin(),
+            self.yaw.cos() * self.pitch.cos(),
+        ) * self.distance;
+        Transform::from_translation(self.focus + offset).looking_at(self.focus, Vec3::Y)
+    }
+}
+
+/// Point on the ground the camera ray through `viewport_position` hits
+pub fn ground_point(camera: &Camera, camera_transform: &GlobalTransform, viewport_position: Vec2) -> Option<Vec3> {
+    let ray = camera.viewport_to_world(camera_transform, viewport_position)?;
+    // Луч над горизонтом упирается в дальнюю точку вместо бесконечности
+    let distance = if ray.direction.y < -0.01 { -ray.origin.y / ray.direction.y } else { 240.0 };
+    Some(ray.get_point(distance))
+}
+
+/// system for handling mouse wheel scrolling: zooms towards the point under the cursor
 pub fn camera_zoom_system(
     mut mouse_wheel_events: EventReader<MouseWheel>,
     mut camera_settings: ResMut<CameraSettings>,
-    mut camera_query: Query<&mut Transform, With<MainCamera>>,
-    _selected_entity_res: Res<SelectedEntity>,
-    _transform_query: Query<&Transform, Without<MainCamera>>,
-    camera_movement_state: Res<CameraMovementState>,
+    mut camera_rig: ResMut<CameraRig>,
+    windows: Query<&Window>,
+    camera_query: Query<(&Camera, &GlobalTransform), With<MainCamera>>,
 ) {
     let mut zoom_delta = 0.0;
-    
+
     for event in mouse_wheel_events.read() {
         zoom_delta += event.y;
     }
-    
-    if zoom_delta != 0.0 {
-        camera_settings.zoom_level -= zoom_delta * camera_settings.zoom_speed;
-        
-        camera_settings.zoom_level = camera_settings.zoom_level
-            .clamp(camera_settings.min_zoom, camera_settings.max_zoom);
-        
-        if camera_movement_state.manual_camera_mode || camera_movement_state.is_right_button_pressed {
-            if let Ok(mut camera_transform) = camera_query.get_single_mut() {
-                let forward = camera_transform.forward();
-                let look_target = camera_transform.translation + forward * 10.0;
-                
-                let zoom_movement = forward * zoom_delta * camera_settings.zoom_speed * 5.0;
-                camera_transform.translation += zoom_movement;
-                
-                camera_transform.look_at(look_target, Vec3::Y);
-                
-                info!("camera_zoom_system: Camera transform updated - pos: {:?}, forward: {:?}, zoom_level: {}", 
-                       camera_transform.translation, camera_transform.forward(), camera_settings.zoom_level);
-            }
-        }
+
+    if zoom_delta == 0.0 {
+        return;
+    }
+
+    let old_zoom = camera_settings.zoom_level;
+    camera_settings.zoom_level = (old_zoom - zoom_delta * camera_settings.zoom_speed)
+        .clamp(camera_settings.min_zoom, camera_settings.max_zoom);
+
+    // Точка под курсором остается на месте: фокус сдвигается к ней пропорционально приближению
+    let cursor_point = windows.get_single().ok()
+        .and_then(|window| window.cursor_position())
+        .zip(camera_query.get_single().ok())
+        .and_then(|(cursor, (camera, camera_transform))| ground_point(camera, camera_transform, cursor));
+    if let Some(point) = cursor_point {
+        let ratio = camera_settings.zoom_level / old_zoom;
+        let point = Vec3::new(point.x, 0.0, point.z);
+        camera_rig.target_focus = point + (camera_rig.target_focus - point) * ratio;
+    }
+}
+
+/// WASD (rebindable) and arrows pan the camera along the ground
+pub fn camera_keyboard_pan(
+    keys: Res<ButtonInput<KeyCode>>,
+    key_bindings: Res<KeyBindings>,
+    time: Res<Time>,
+    camera_settings: Res<CameraSettings>,
+    mut camera_rig: ResMut<CameraRig>,
+    mut camera_movement_state: ResMut<CameraMovementState>,
+) {
+    // Стрелки работают всегда, вдобавок к назначенным клавишам
+    let held = |action: InputAction, arrow: KeyCode| key_bindings.pressed(action, &keys) || keys.pressed(arrow);
+    let mut direction = Vec2::ZERO;
+    if held(InputAction::CameraForward, KeyCode::ArrowUp) {
+        direction.y += 1.0;
+    }
+    if held(InputAction::CameraBack, KeyCode::ArrowDown) {
+        direction.y -= 1.0;
+    }
+    if held(InputAction::CameraRight, KeyCode::ArrowRight) {
+        direction.x += 1.0;
+    }
+    if held(InputAction::CameraLeft, KeyCode::ArrowLeft) {
+        direction.x -= 1.0;
+    }
+
+    if direction != Vec2::ZERO {
+        camera_rig.pan(direction.normalize(), camera_settings.pan_speed * time.delta_seconds());
+        camera_movement_state.manual_camera_mode = true;
     }
 }
 
-/// system for updating the camera position to follow the selected object
+/// Scrolls the camera when the cursor rests at the edge of the window
+pub fn camera_edge_scroll(
+    windows: Query<&Window>,
+    time: Res<Time>,
+    camera_settings: Res<CameraSettings>,
+    minimap_drag: Res<MinimapDrag>,
+    mut camera_rig: ResMut<CameraRig>,
+    mut camera_movement_state: ResMut<CameraMovementState>,
+) {
+    if camera_movement_state.is_right_button_pressed || minimap_drag.0 {
+        return;
+    }
+    let Ok(window) = windows.get_single() else {
+        return;
+    };
+    // Курсор за пределами окна или окно в фоне - не скроллим
+    let Some(cursor) = window.cursor_position().filter(|_| window.focused) else {
+        return;
+    };
+
+    let margin = camera_settings.edge_scroll_margin;
+    let mut direction = Vec2::ZERO;
+    if cursor.x <= margin {
+        direction.x -= 1.0;
+    } else if cursor.x >= window.width() - margin {
+        direction.x += 1.0;
+    }
+    // Экранная Y растет вниз
+    if cursor.y <= margin {
+        direction.y += 1.0;
+    } else if cursor.y >= window.height() - margin {
+        direction.y -= 1.0;
+    }
+
+    if direction != Vec2::ZERO {
+        camera_rig.pan(direction.normalize(), camera_settings.pan_speed * time.delta_seconds());
+        camera_movement_state.manual_camera_mode = true;
+    }
+}
+
+/// Q/E rotate the camera around its focus point
+pub fn camera_rotation(
+    keys: Res<ButtonInput<KeyCode>>,
+    key_bindings: Res<KeyBindings>,
+    time: Res<Time>,
+    camera_settings: Res<CameraSettings>,
+    mut camera_rig: ResMut<CameraRig>,
+) {
+    let step = camera_settings.rotation_speed * time.delta_seconds();
+    if key_bindings.pressed(InputAction::CameraRotateLeft, &keys) {
+        camera_rig.target_yaw -= step;
+    }
+    if key_bindings.pressed(InputAction::CameraRotateRight, &keys) {
+        camera_rig.target_yaw += step;
+    }
+}
+
+/// Space switches between following the selected unit and the free camera
+pub fn camera_follow_toggle(
+    keys: Res<ButtonInput<KeyCode>>,
+    key_bindings: Res<KeyBindings>,
+    mut camera_movement_state: ResMut<CameraMovementState>,
+) {
+    if key_bindings.just_pressed(InputAction::CameraFollowToggle, &keys) {
+        camera_movement_state.follow_selection = !camera_movement_state.follow_selection;
+        camera_movement_state.manual_camera_mode = false;
+        info!("📷 Camera mode: {}", if camera_movement_state.follow_selection { "follow" } else { "free" });
+    }
+}
+
+/// system for updating the camera focus to follow the selected object
 pub fn camera_follow_selected(
-    mut camera_query: Query<&mut Transform, With<MainCamera>>,
-    selected_entity_res: Res<SelectedEntity>,
+    mut camera_rig: ResMut<CameraRig>,
+    selection: Res<Selection>,
     transform_query: Query<&Transform, Without<MainCamera>>,
-    camera_settings: Res<CameraSettings>,
     camera_movement_state: Res<CameraMovementState>,
 ) {
-    if camera_movement_state.is_right_button_pressed || camera_movement_state.manual_camera_mode {
+    if !camera_movement_state.follow_selection
+        || camera_movement_state.is_right_button_pressed
+        || camera_movement_state.manual_camera_mode
+    {
         return;
     }
-    
-    if let Some(selected_entity) = selected_entity_res.0 {
-        if let Ok(selected_transform) = transform_query.get(selected_entity) {
-            if let Ok(mut camera_transform) = camera_query.get_single_mut() {
-    
-                let base_offset = Vec3::new(-2.5, 4.5, 4.0);
-                
-                // apply scaling to the offset based on the zoom level
-                let scaled_offset = base_offset * camera_settings.zoom_level;
-                
-                // set the camera position with the offset
-                camera_transform.translation = selected_transform.translation + scaled_offset;
-                
-                // look at the selected object
-                camera_transform.look_at(selected_transform.translation, Vec3::Y);
-                
-                info!("camera_follow_selected: Camera following entity - camera pos: {:?}, target pos: {:?}", 
-                       camera_transform.translation, selected_transform.translation);
-            }
-        }
+
+    // За группой камера не следит - только за единственным выделенным объектом
+    if selection.len() != 1 {
+        return;
+    }
+    if let Some(selected_transform) = selection.primary().and_then(|entity| transform_query.get(entity).ok()) {
+        let target = selected_transform.translation;
+        camera_rig.target_focus = Vec3::new(target.x, 0.0, target.z);
     }
 }
 
 /// system for controlling the camera with the right mouse button
 pub fn camera_right_button_movement(
-    mut camera_query: Query<&mut Transform, With<MainCamera>>,
     mouse_buttons: Res<ButtonInput<MouseButton>>,
     mut motion_events: EventReader<MouseMotion>,
     mut camera_movement_state: ResMut<CameraMovementState>,
-    _time: Res<Time>,
+    mut camera_rig: ResMut<CameraRig>,
 ) {
     if mouse_buttons.just_pressed(MouseButton::Right) {
         camera_movement_state.is_right_button_pressed = true;
@@ -94,32 +253,44 @@ pub fn camera_right_button_movement(
         info!("camera_right_button_movement: Right mouse button released, entering manual camera mode");
     }
 
-    if camera_movement_state.is_right_button_pressed {
-        let mut camera_transform = if let Ok(transform) = camera_query.get_single_mut() {
-            transform
-        } else {
-            return;
-        };
-        
-        let mut movement = Vec2::ZERO;
-        for event in motion_events.read() {
-            movement += event.delta;
-        }
-        
-        if movement != Vec2::ZERO {
-            let right = camera_transform.right();
-            let up = Vec3::Y;
-            let forward = camera_transform.forward().reject_from(up).normalize();
-            
-            camera_transform.translation -= right * movement.x * camera_movement_state.movement_speed;
-            camera_transform.translation += forward * movement.y * camera_movement_state.movement_speed;
-            
-            let look_dir = camera_transform.forward();
-            let target = camera_transform.translation + look_dir * 10.0;
-            camera_transform.look_at(target, Vec3::Y);
-            
-            info!("camera_right_button_movement: Camera moved - pos: {:?}, movement: {:?}", 
-                   camera_transform.translation, movement);
-        }
+    let mut movement = Vec2::ZERO;
+    for event in motion_events.read() {
+        movement += event.delta;
+    }
+
+    if camera_movement_state.is_right_button_pressed && movement != Vec2::ZERO {
+        // Мир тянется за мышью: вправо - камера влево, вниз - камера вперед
+        camera_rig.pan(Vec2::new(-movement.x, movement.y), camera_movement_state.movement_speed);
+    }
+}
+
+/// Keeps the focus on the map and moves the camera smoothly towards where the player wants it
+pub fn apply_camera_rig(
+    time: Res<Time>,
+    camera_settings: Res<CameraSettings>,
+    mut camera_rig: ResMut<CameraRig>,
+    mut camera_query: Query<&mut Transform, With<MainCamera>>,
+) {
+    let bounds = camera_settings.bounds;
+    camera_rig.target_focus = camera_rig.target_focus.clamp(Vec3::new(-bounds, 0.0, -bounds), Vec3::new(bounds, 0.0, bounds));
+
+    let blend = 1.0 - (-camera_settings.smoothing * time.delta_seconds()).exp();
+    let target_distance = camera_rig.base_distance * camera_settings.zoom_level;
+    let rig = camera_rig.as_mut();
+    rig.focus = rig.focus.lerp(rig.target_focus, blend);
+    rig.yaw += (rig.target_yaw - rig.yaw) * blend;
+    rig.distance += (target_distance - rig.distance) * blend;
+
+    if let Ok(mut camera_transform) = camera_query.get_single_mut() {
+        *camera_transform = camera_rig.transform();
     }
-}
\ No newline at end of file
+}
+
+/// A new game starts with the camera over the battlefield center
+pub fn reset_camera_rig(
+    mut camera_rig: ResMut<CameraRig>,
+    mut camera_settings: ResMut<CameraSettings>,
+) {
+    *camera_rig = CameraRig::default();
+    camera_settings.zoom_level = 1.0;
+}
@@ -1,12 +1,14 @@
 use bevy::prelude::*;
 use crate::menu::common::GameState;
+use crate::input::key_bindings::{InputAction, KeyBindings};
 
 pub fn handle_escape_key(
     keys: Res<ButtonInput<KeyCode>>,
+    key_bindings: Res<KeyBindings>,
     current_state: Res<State<GameState>>,
     mut next_state: ResMut<NextState<GameState>>,
 ) {
-    if keys.just_pressed(KeyCode::Escape) {
+    if key_bindings.just_pressed(InputAction::Pause, &keys) {
         match current_state.get() {
             GameState::Game => {
                 next_state.set(GameState::Paused);
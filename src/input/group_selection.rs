@@ -0,0 +1,236 @@
+use bevy::prelude::*;
+use bevy_mod_picking::prelude::*;
+
+use crate::game::{CanShoot, Enemy, MainCamera, PlacementState, Selectable, Selection};
+use crate::game::scene_colliders::ChildOfClickable;
+use crate::systems::turn_system::{PlayerTurn, TurnState};
+use crate::ui::minimap::MinimapFrame;
+
+/// Сдвиг мыши в пикселях, после которого нажатие считается рамкой, а не кликом
+const BOX_SELECT_THRESHOLD: f32 = 6.0;
+
+const CONTROL_GROUP_KEYS: [KeyCode; 9] = [
+    KeyCode::Digit1,
+    KeyCode::Digit2,
+    KeyCode::Digit3,
+    KeyCode::Digit4,
+    KeyCode::Digit5,
+    KeyCode::Digit6,
+    KeyCode::Digit7,
+    KeyCode::Digit8,
+    KeyCode::Digit9,
+];
+
+/// State of the left-button drag used for box selection (screen space)
+#[derive(Resource, Default)]
+pub struct BoxSelection {
+    pub start: Option<Vec2>,
+    pub dragging: bool,
+    /// Рамка отпущена в этом кадре - клик по земле не должен превращаться в приказ движения
+    pub finished_this_frame: bool,
+}
+
+/// Control groups 1..9: Ctrl+digit assigns the selection, digit recalls it
+#[derive(Resource, Default)]
+pub struct ControlGroups(pub [Vec<Entity>; 9]);
+
+/// Marker for the UI node that draws the selection box
+#[derive(Component)]
+pub struct SelectionBoxNode;
+
+type BoxSelectableFilter = (With<Selectable>, With<CanShoot>, Without<Enemy>, Without<ChildOfClickable>);
+
+fn shift_pressed(keys: &ButtonInput<KeyCode>) -> bool {
+    keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight])
+}
+
+fn ctrl_pressed(keys: &ButtonInput<KeyCode>) -> bool {
+    keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight])
+}
+
+/// Applies the click modifiers: Shift adds, Ctrl toggles, otherwise the selection is replaced
+pub fn apply_selection(selection: &mut Selection, keys: &ButtonInput<KeyCode>, entities: &[Entity]) {
+    if ctrl_pressed(keys) {
+        for entity in entities {
+            selection.toggle(*entity);
+        }
+    } else if shift_pressed(keys) {
+        for entity in entities {
+            selection.add(*entity);
+        }
+    } else {
+        selection.0 = entities.to_vec();
+    }
+}
+
+/// Drag with the left button to select every player unit inside the box
+#[allow(clippy::too_many_arguments)]
+pub fn box_select_system(
+    buttons: Res<ButtonInput<MouseButton>>,
+    keys: Res<ButtonInput<KeyCode>>,
+    windows: Query<&Window>,
+    camera_q: Query<(&Camera, &GlobalTransform), With<MainCamera>>,
+    units: Query<(Entity, &GlobalTransform), BoxSelectableFilter>,
+    turn_state: Res<TurnState>,
+    placement_state: Res<PlacementState>,
+    mut box_selection: ResMut<BoxSelection>,
+    mut selection: ResMut<Selection>,
+    minimap: Query<&Interaction, With<MinimapFrame>>,
+) {
+    box_selection.finished_this_frame = false;
+
+    if turn_state.current_player != PlayerTurn::Human || placement_state.active {
+        box_selection.start = None;
+        box_selection.dragging = false;
+        return;
+    }
+
+    let Some(cursor_position) = windows.get_single().ok().and_then(|window| window.cursor_position()) else {
+        return;
+    };
+
+    if buttons.just_pressed(MouseButton::Left) {
+        // Нажатие на миникарте двигает камеру, а не начинает рамку
+        if minimap.iter().any(|interaction| *interaction != Interaction::None) {
+            box_selection.start = None;
+            box_selection.dragging = false;
+            return;
+        }
+        box_selection.start = Some(cursor_position);
+        box_selection.dragging = false;
+    }
+
+    let Some(start) = box_selection.start else {
+        return;
+    };
+
+    if buttons.pressed(MouseButton::Left) && start.distance(cursor_position) > BOX_SELECT_THRESHOLD {
+        box_selection.dragging = true;
+    }
+
+    if !buttons.just_released(MouseButton::Left) {
+        return;
+    }
+
+    box_selection.start = None;
+    if !box_selection.dragging {
+        return;
+    }
+    box_selection.dragging = false;
+    box_selection.finished_this_frame = true;
+
+    let Ok((camera, camera_transform)) = camera_q.get_single() else {
+        return;
+    };
+    let rect = Rect::from_corners(start, cursor_position);
+    let boxed: Vec<Entity> = units.iter()
+        .filter(|(_, transform)| {
+            camera.world_to_viewport(camera_transform, transform.translation())
+                .is_some_and(|screen_position| rect.contains(screen_position))
+        })
+        .map(|(entity, _)| entity)
+        .collect();
+
+    apply_selection(&mut selection, &keys, &boxed);
+    info!("🔲 Box selection: {} unit(s) in the box, {} selected", boxed.len(), selection.len());
+}
+
+/// Draws the selection box as a UI node while the mouse is being dragged
+pub fn draw_selection_box(
+    mut commands: Commands,
+    box_selection: Res<BoxSelection>,
+    windows: Query<&Window>,
+    mut box_nodes: Query<(Entity, &mut Style), With<SelectionBoxNode>>,
+) {
+    let cursor_position = windows.get_single().ok().and_then(|window| window.cursor_position());
+    let drag = box_selection.start.zip(cursor_position).filter(|_| box_selection.dragging);
+
+    let Some((start, cursor_position)) = drag else {
+        for (entity, _) in box_nodes.iter() {
+            commands.entity(entity).despawn_recursive();
+        }
+        return;
+    };
+
+    let rect = Rect::from_corners(start, cursor_position);
+    if let Ok((_, mut style)) = box_nodes.get_single_mut() {
+        style.left = Val::Px(rect.min.x);
+        style.top = Val::Px(rect.min.y);
+        style.width = Val::Px(rect.width());
+        style.height = Val::Px(rect.height());
+        return;
+    }
+
+    commands.spawn((
+        NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                left: Val::Px(rect.min.x),
+                top: Val::Px(rect.min.y),
+                width: Val::Px(rect.width()),
+                height: Val::Px(rect.height()),
+                border: UiRect::all(Val::Px(1.0)),
+                ..default()
+            },
+            background_color: Color::rgba(0.2, 0.9, 0.2, 0.1).into(),
+            border_color: Color::rgb(0.2, 0.9, 0.2).into(),
+            z_index: ZIndex::Global(100),
+            ..default()
+        },
+        // Рамка не должна перехватывать клики по земле и юнитам
+        Pickable::IGNORE,
+        SelectionBoxNode,
+    ));
+}
+
+/// Ctrl+1..9 assigns the current selection to a group, 1..9 selects the group again
+pub fn control_group_hotkeys(
+    keys: Res<ButtonInput<KeyCode>>,
+    turn_state: Res<TurnState>,
+    alive: Query<(), With<Selectable>>,
+    mut control_groups: ResMut<ControlGroups>,
+    mut selection: ResMut<Selection>,
+) {
+    if turn_state.current_player != PlayerTurn::Human {
+        return;
+    }
+
+    let Some(index) = CONTROL_GROUP_KEYS.iter().position(|key| keys.just_pressed(*key)) else {
+        return;
+    };
+    let group = &mut control_groups.0[index];
+
+    if ctrl_pressed(&keys) {
+        *group = selection.0.clone();
+        info!("👥 Control group {}: {} unit(s) assigned", index + 1, group.len());
+        return;
+    }
+
+    // Погибшие юниты выпадают из группы
+    group.retain(|entity| alive.get(*entity).is_ok());
+    if group.is_empty() {
+        return;
+    }
+    if shift_pressed(&keys) {
+        for entity in group.iter() {
+            selection.add(*entity);
+        }
+    } else {
+        selection.0 = group.clone();
+    }
+    info!("👥 Control group {} selected: {} unit(s)", index + 1, group.len());
+}
+
+/// Clears the box and the control groups when the match is left
+pub fn reset_group_selection(
+    mut commands: Commands,
+    mut box_selection: ResMut<BoxSelection>,
+    mut control_groups: ResMut<ControlGroups>,
+    box_nodes: Query<Entity, With<SelectionBoxNode>>,
+) {
+    *box_selection = BoxSelection::default();
+    *control_groups = ControlGroups::default();
+    for entity in box_nodes.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
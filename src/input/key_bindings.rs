@@ -0,0 +1,353 @@
+use std::collections::BTreeMap;
+
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+use serde::{Deserialize, Serialize};
+
+/// Файл с переназначенными клавишами, рядом с сохранениями
+pub const KEY_BINDINGS_PATH: &str = "config/key_bindings.toml";
+
+/// Everything the player can do from the keyboard. Control groups stay on Ctrl+1..9 / 1..9.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum InputAction {
+    Pause,
+    QuickSave,
+    PlaceTrench,
+    PlaceFarm,
+    PlaceMine,
+    PlaceSteelFactory,
+    PlacePetrochemicalPlant,
+    StanceAggressive,
+    StanceReturnFire,
+    StanceHoldFire,
+    AttackMove,
+    NextFormation,
+    ToggleMoveTogether,
+    CameraForward,
+    CameraBack,
+    CameraLeft,
+    CameraRight,
+    CameraRotateLeft,
+    CameraRotateRight,
+    CameraFollowToggle,
+    MinimapPing,
+    AIDebugOverlay,
+    CheatDefeat,
+    CheatVictory,
+    CheatResources,
+    CheatTurnTime,
+    CheatRevealMap,
+    CheatTestTweet,
+}
+
+impl InputAction {
+    pub const ALL: [InputAction; 28] = [
+        InputAction::Pause,
+        InputAction::QuickSave,
+        InputAction::PlaceTrench,
+        InputAction::PlaceFarm,
+        InputAction::PlaceMine,
+        InputAction::PlaceSteelFactory,
+        InputAction::PlacePetrochemicalPlant,
+        InputAction::StanceAggressive,
+        InputAction::StanceReturnFire,
+        InputAction::StanceHoldFire,
+        InputAction::AttackMove,
+        InputAction::NextFormation,
+        InputAction::ToggleMoveTogether,
+        InputAction::CameraForward,
+        InputAction::CameraBack,
+        InputAction::CameraLeft,
+        InputAction::CameraRight,
+        InputAction::CameraRotateLeft,
+        InputAction::CameraRotateRight,
+        InputAction::CameraFollowToggle,
+        InputAction::MinimapPing,
+        InputAction::AIDebugOverlay,
+        InputAction::CheatDefeat,
+        InputAction::CheatVictory,
+        InputAction::CheatResources,
+        InputAction::CheatTurnTime,
+        InputAction::CheatRevealMap,
+        InputAction::CheatTestTweet,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            InputAction::Pause => "Pause",
+            InputAction::QuickSave => "Quick save",
+            InputAction::PlaceTrench => "Build trench",
+            InputAction::PlaceFarm => "Build farm",
+            InputAction::PlaceMine => "Build mine",
+            InputAction::PlaceSteelFactory => "Build steel factory",
+            InputAction::PlacePetrochemicalPlant => "Build petrochemical plant",
+            InputAction::StanceAggressive => "Stance: aggressive",
+            InputAction::StanceReturnFire => "Stance: return fire",
+            InputAction::StanceHoldFire => "Stance: hold fire",
+            InputAction::AttackMove => "Attack-move",
+            InputAction::NextFormation => "Next formation",
+            InputAction::ToggleMoveTogether => "Move together",
+            InputAction::CameraForward => "Camera forward",
+            InputAction::CameraBack => "Camera back",
+            InputAction::CameraLeft => "Camera left",
+            InputAction::CameraRight => "Camera right",
+            InputAction::CameraRotateLeft => "Rotate camera left",
+            InputAction::CameraRotateRight => "Rotate camera right",
+            InputAction::CameraFollowToggle => "Camera follow / free",
+            InputAction::MinimapPing => "Ping",
+            InputAction::AIDebugOverlay => "AI debug overlay",
+            InputAction::CheatDefeat => "Cheat: defeat",
+            InputAction::CheatVictory => "Cheat: victory",
+            InputAction::CheatResources => "Cheat: +1000 resources",
+            InputAction::CheatTurnTime => "Cheat: +10 s turn",
+            InputAction::CheatRevealMap => "Cheat: reveal map",
+            InputAction::CheatTestTweet => "Cheat: test tweet",
+        }
+    }
+
+    /// Default key; placing the steel factory and attack-move moved off S and A, which pan the camera
+    pub fn default_binding(&self) -> KeyBinding {
+        match self {
+            InputAction::Pause => KeyBinding::new(KeyCode::Escape),
+            InputAction::QuickSave => KeyBinding::new(KeyCode::F5),
+            InputAction::PlaceTrench => KeyBinding::new(KeyCode::KeyB),
+            InputAction::PlaceFarm => KeyBinding::new(KeyCode::KeyF),
+            InputAction::PlaceMine => KeyBinding::new(KeyCode::KeyM),
+            InputAction::PlaceSteelFactory => KeyBinding::new(KeyCode::KeyI),
+            InputAction::PlacePetrochemicalPlant => KeyBinding::new(KeyCode::KeyP),
+            InputAction::StanceAggressive => KeyBinding::new(KeyCode::KeyG),
+            InputAction::StanceReturnFire => KeyBinding::new(KeyCode::KeyR),
+            InputAction::StanceHoldFire => KeyBinding::new(KeyCode::KeyH),
+            InputAction::AttackMove => KeyBinding::new(KeyCode::KeyT),
+            InputAction::NextFormation => KeyBinding::new(KeyCode::KeyN),
+            InputAction::ToggleMoveTogether => KeyBinding::new(KeyCode::KeyU),
+            InputAction::CameraForward => KeyBinding::new(KeyCode::KeyW),
+            InputAction::CameraBack => KeyBinding::new(KeyCode::KeyS),
+            InputAction::CameraLeft => KeyBinding::new(KeyCode::KeyA),
+            InputAction::CameraRight => KeyBinding::new(KeyCode::KeyD),
+            InputAction::CameraRotateLeft => KeyBinding::new(KeyCode::KeyQ),
+            InputAction::CameraRotateRight => KeyBinding::new(KeyCode::KeyE),
+            InputAction::CameraFollowToggle => KeyBinding::new(KeyCode::Space),
+            InputAction::MinimapPing => KeyBinding::new(KeyCode::KeyC),
+            InputAction::AIDebugOverlay => KeyBinding::new(KeyCode::F3),
+            InputAction::CheatDefeat => KeyBinding::ctrl(KeyCode::KeyD),
+            InputAction::CheatVictory => KeyBinding::ctrl(KeyCode::KeyV),
+            InputAction::CheatResources => KeyBinding::ctrl(KeyCode::KeyM),
+            InputAction::CheatTurnTime => KeyBinding::ctrl(KeyCode::KeyT),
+            InputAction::CheatRevealMap => KeyBinding::ctrl(KeyCode::KeyX),
+            InputAction::CheatTestTweet => KeyBinding::ctrl(KeyCode::KeyP),
+        }
+    }
+
+    /// Name of the action in the config file
+    fn config_name(&self) -> String {
+        format!("{:?}", self)
+    }
+}
+
+/// A key, optionally with Ctrl. Without Ctrl the binding does not fire while Ctrl is held,
+/// so Ctrl+D does not also pan the camera.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct KeyBinding {
+    pub key: KeyCode,
+    pub ctrl: bool,
+}
+
+/// Клавиши, которые можно назначить; модификаторы и служебные клавиши не входят,
+/// как и 1..9 (группы юнитов) и стрелки (всегда двигают камеру)
+const BINDABLE_KEYS: [KeyCode; 49] = [
+    KeyCode::KeyA, KeyCode::KeyB, KeyCode::KeyC, KeyCode::KeyD, KeyCode::KeyE, KeyCode::KeyF,
+    KeyCode::KeyG, KeyCode::KeyH, KeyCode::KeyI, KeyCode::KeyJ, KeyCode::KeyK, KeyCode::KeyL,
+    KeyCode::KeyM, KeyCode::KeyN, KeyCode::KeyO, KeyCode::KeyP, KeyCode::KeyQ, KeyCode::KeyR,
+    KeyCode::KeyS, KeyCode::KeyT, KeyCode::KeyU, KeyCode::KeyV, KeyCode::KeyW, KeyCode::KeyX,
+    KeyCode::KeyY, KeyCode::KeyZ,
+    KeyCode::Digit0,
+    KeyCode::F1, KeyCode::F2, KeyCode::F3, KeyCode::F4, KeyCode::F5, KeyCode::F6,
+    KeyCode::F7, KeyCode::F8, KeyCode::F9, KeyCode::F10, KeyCode::F11, KeyCode::F12,
+    KeyCode::Space, KeyCode::Tab, KeyCode::Enter, KeyCode::Backspace, KeyCode::Escape,
+    KeyCode::Comma, KeyCode::Period, KeyCode::Slash, KeyCode::Semicolon, KeyCode::Minus,
+];
+
+fn ctrl_pressed(keys: &ButtonInput<KeyCode>) -> bool {
+    keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight])
+}
+
+impl KeyBinding {
+    pub const fn new(key: KeyCode) -> Self {
+        Self { key, ctrl: false }
+    }
+
+    pub const fn ctrl(key: KeyCode) -> Self {
+        Self { key, ctrl: true }
+    }
+
+    pub fn is_bindable(key: KeyCode) -> bool {
+        BINDABLE_KEYS.contains(&key)
+    }
+
+    /// "Ctrl+D", "F5", "Space"
+    pub fn label(&self) -> String {
+        let name = format!("{:?}", self.key);
+        let name = name.strip_prefix("Key").or_else(|| name.strip_prefix("Digit")).unwrap_or(&name).to_string();
+        if self.ctrl { format!("Ctrl+{}", name) } else { name }
+    }
+
+    /// Config form: "KeyD" or "Ctrl+KeyD"
+    fn to_config(self) -> String {
+        if self.ctrl { format!("Ctrl+{:?}", self.key) } else { format!("{:?}", self.key) }
+    }
+
+    fn from_config(value: &str) -> Option<Self> {
+        let (ctrl, name) = match value.strip_prefix("Ctrl+") {
+            Some(name) => (true, name),
+            None => (false, value),
+        };
+        BINDABLE_KEYS.iter()
+            .find(|key| format!("{:?}", key) == name)
+            .map(|key| KeyBinding { key: *key, ctrl })
+    }
+
+    fn modifiers_match(&self, keys: &ButtonInput<KeyCode>) -> bool {
+        self.ctrl == ctrl_pressed(keys)
+    }
+}
+
+/// Key bindings of every input action, loaded from `KEY_BINDINGS_PATH` at startup
+#[derive(Resource, Clone, Debug)]
+pub struct KeyBindings {
+    bindings: HashMap<InputAction, KeyBinding>,
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        Self {
+            bindings: InputAction::ALL.iter().map(|action| (*action, action.default_binding())).collect(),
+        }
+    }
+}
+
+/// On-disk form: action name -> key
+#[derive(Serialize, Deserialize, Default)]
+struct KeyBindingsFile {
+    #[serde(default)]
+    bindings: BTreeMap<String, String>,
+}
+
+#[derive(Debug)]
+pub enum KeyBindingsError {
+    Io(std::io::Error),
+    Toml(String),
+}
+
+impl std::fmt::Display for KeyBindingsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            KeyBindingsError::Io(err) => write!(f, "could not access key bindings: {}", err),
+            KeyBindingsError::Toml(err) => write!(f, "could not parse key bindings: {}", err),
+        }
+    }
+}
+
+impl KeyBindings {
+    pub fn get(&self, action: InputAction) -> KeyBinding {
+        self.bindings.get(&action).copied().unwrap_or_else(|| action.default_binding())
+    }
+
+    pub fn set(&mut self, action: InputAction, binding: KeyBinding) {
+        self.bindings.insert(action, binding);
+    }
+
+    /// The action's key went down this frame (with matching Ctrl)
+    pub fn just_pressed(&self, action: InputAction, keys: &ButtonInput<KeyCode>) -> bool {
+        let binding = self.get(action);
+        keys.just_pressed(binding.key) && binding.modifiers_match(keys)
+    }
+
+    /// The action's key is held (with matching Ctrl)
+    pub fn pressed(&self, action: InputAction, keys: &ButtonInput<KeyCode>) -> bool {
+        let binding = self.get(action);
+        keys.pressed(binding.key) && binding.modifiers_match(keys)
+    }
+
+    /// Pairs of actions bound to the same key, in `InputAction::ALL` order
+    pub fn conflicts(&self) -> Vec<(InputAction, InputAction)> {
+        let mut conflicts = Vec::new();
+        for (index, first) in InputAction::ALL.iter().enumerate() {
+            for second in InputAction::ALL.iter().skip(index + 1) {
+                if self.get(*first) == self.get(*second) {
+                    conflicts.push((*first, *second));
+                }
+            }
+        }
+        conflicts
+    }
+
+    /// Does this action share its key with another one
+    pub fn is_conflicting(&self, action: InputAction) -> bool {
+        let binding = self.get(action);
+        InputAction::ALL.iter().any(|other| *other != action && self.get(*other) == binding)
+    }
+
+    /// Reads bindings from a file; actions missing from it keep their defaults, unknown entries are skipped
+    pub fn load(path: &str) -> Result<Self, KeyBindingsError> {
+        let contents = std::fs::read_to_string(path).map_err(KeyBindingsError::Io)?;
+        let file: KeyBindingsFile = toml::from_str(&contents).map_err(|err| KeyBindingsError::Toml(err.to_string()))?;
+
+        let mut key_bindings = KeyBindings::default();
+        for (name, value) in file.bindings.iter() {
+            let action = InputAction::ALL.iter().find(|action| action.config_name() == *name);
+            match (action, KeyBinding::from_config(value)) {
+                (Some(action), Some(binding)) => key_bindings.set(*action, binding),
+                (None, _) => warn!("Unknown action '{}' in {}", name, path),
+                (_, None) => warn!("Unknown key '{}' for '{}' in {}", value, name, path),
+            }
+        }
+        Ok(key_bindings)
+    }
+
+    pub fn save(&self, path: &str) -> Result<(), KeyBindingsError> {
+        if let Some(dir) = std::path::Path::new(path).parent() {
+            std::fs::create_dir_all(dir).map_err(KeyBindingsError::Io)?;
+        }
+        let file = KeyBindingsFile {
+            bindings: InputAction::ALL.iter()
+                .map(|action| (action.config_name(), self.get(*action).to_config()))
+                .collect(),
+        };
+        let contents = toml::to_string_pretty(&file).map_err(|err| KeyBindingsError::Toml(err.to_string()))?;
+        std::fs::write(path, contents).map_err(KeyBindingsError::Io)
+    }
+
+    pub fn log_conflicts(&self) {
+        for (first, second) in self.conflicts() {
+            warn!("⌨️ Key conflict: {} is bound to both '{}' and '{}'", self.get(first).label(), first.label(), second.label());
+        }
+    }
+}
+
+/// Loads the key bindings; without a config file the defaults are used
+pub fn load_key_bindings(mut commands: Commands) {
+    let key_bindings = match KeyBindings::load(KEY_BINDINGS_PATH) {
+        Ok(key_bindings) => {
+            info!("⌨️ Key bindings loaded from {}", KEY_BINDINGS_PATH);
+            key_bindings
+        }
+        Err(KeyBindingsError::Io(_)) => KeyBindings::default(),
+        Err(err) => {
+            warn!("{}, using default key bindings", err);
+            KeyBindings::default()
+        }
+    };
+    key_bindings.log_conflicts();
+    commands.insert_resource(key_bindings);
+}
+
+/// Key bindings resource and its config file
+pub struct KeyBindingsPlugin;
+
+impl Plugin for KeyBindingsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<KeyBindings>()
+            .add_systems(Startup, load_key_bindings);
+    }
+}
@@ -1,6 +1,8 @@
 pub mod camera;
 pub mod selection;
+pub mod group_selection;
 pub mod game_controls;
+pub mod key_bindings;
 pub use camera::*;
 
 pub use game_controls::*;
\ No newline at end of file
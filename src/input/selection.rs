@@ -2,14 +2,17 @@ use bevy::prelude::*;
 use bevy_mod_picking::prelude::*;
 use bevy_rapier3d::prelude::*;
 
-use crate::game::{Selectable, SelectedEntity, Ground, MovementOrder, ClickCircle, Enemy, EnemyTower, Farm, Mine, SteelFactory, PetrochemicalPlant, ShapeType, Health, LinkedToEnemy, Tank, Aircraft};
+use crate::game::{Selectable, Selection, Ground, MovementOrder, AttackMove, ClickCircle, Enemy, EnemyTower, Farm, Mine, SteelFactory, PetrochemicalPlant, ShapeType, Health, LinkedToEnemy, Tank, Aircraft};
 use crate::game::scene_colliders::ChildOfClickable;
 use crate::game::units::infantry::Infantry;
 use crate::systems::turn_system::{TurnState, PlayerTurn};
+use crate::systems::stances::AttackMoveArmed;
+use crate::input::group_selection::{apply_selection, BoxSelection};
+use crate::systems::formations::{group_pace, plan_formation, FormationMember, FormationMemberData, FormationMemberFilter, FormationSettings, FormationSpeed};
 
 /// Resource for tracking mouse position in world space
 #[derive(Resource, Default)]
-pub struct MouseWorldPosition(#[allow(dead_code)] pub Option<Vec3>);
+pub struct MouseWorldPosition(pub Option<Vec3>);
 
 /// Resource for tracking processed clicks
 #[derive(Resource, Default)]
@@ -33,13 +36,15 @@ pub fn debug_all_clicks(
     }
 }
 
-/// Alternative unit selection system using mouse input and raycasting
+/// Alternative unit selection system using mouse input and raycasting (Shift adds, Ctrl toggles)
+#[allow(clippy::too_many_arguments)]
 pub fn raycast_unit_selection(
     buttons: Res<ButtonInput<MouseButton>>,
+    keys: Res<ButtonInput<KeyCode>>,
     windows: Query<&Window>,
     camera_q: Query<(&Camera, &GlobalTransform), With<crate::game::MainCamera>>,
     rapier_context: Res<RapierContext>,
-    mut selected_entity: ResMut<SelectedEntity>,
+    mut selection: ResMut<Selection>,
     query_selectable: Query<(), (With<Selectable>, Without<Enemy>, Without<EnemyTower>)>,
     turn_state: Res<TurnState>,
     mut camera_movement_state: ResMut<crate::game::CameraMovementState>,
@@ -75,7 +80,7 @@ pub fn raycast_unit_selection(
                             // Check if this entity is selectable (our units)
                             if query_selectable.get(entity).is_ok() {
                                 info!("🔥 RAYCAST: ✅ Entity {:?} is selectable! Selecting it.", entity);
-                                selected_entity.0 = Some(entity);
+                                apply_selection(&mut selection, &keys, &[entity]);
                                 camera_movement_state.manual_camera_mode = false;
                                 return false; // Stop at first selectable hit
                             }
@@ -85,7 +90,7 @@ pub fn raycast_unit_selection(
                                 info!("🔥 RAYCAST DEBUG: Entity {:?} is child of {:?}", entity, child_of_clickable.parent);
                                 if query_selectable.get(child_of_clickable.parent).is_ok() {
                                     info!("🔥 RAYCAST: ✅ Parent entity {:?} is selectable! Selecting it.", child_of_clickable.parent);
-                                    selected_entity.0 = Some(child_of_clickable.parent);
+                                    apply_selection(&mut selection, &keys, &[child_of_clickable.parent]);
                                     camera_movement_state.manual_camera_mode = false;
                                     return false; // Stop at first selectable parent
                                 }
@@ -110,7 +115,7 @@ pub fn raycast_unit_selection(
 /// system for selecting an entity
 pub fn select_entity_system(
     mut click_events: EventReader<Pointer<Click>>,
-    mut selected_entity: ResMut<SelectedEntity>,
+    mut selection: ResMut<Selection>,
     query_selectable: Query<(), (With<Selectable>, Without<Enemy>, Without<EnemyTower>)>,
     query_attackable: Query<Entity, Or<(With<Enemy>, With<EnemyTower>)>>,
     query_enemy_targetable: Query<Entity, (With<Enemy>, With<Health>)>, // Enemy units/buildings that can be targeted
@@ -165,18 +170,18 @@ pub fn select_entity_system(
               target_entity, event.target, is_selectable, is_attackable, is_enemy_targetable);
         
         if is_selectable {
-            info!("select_entity_system: ✅ Clicked on selectable object {:?}, previously selected: {:?}", target_entity, selected_entity.0);
+            info!("select_entity_system: ✅ Clicked on selectable object {:?}, previously selected: {:?}", target_entity, selection.0);
             
             // Всегда выделяем кликнутый юнит (позволяет переключаться между юнитами)
-            selected_entity.0 = Some(target_entity);
+            selection.0 = vec![target_entity];
             camera_movement_state.manual_camera_mode = false;
             
             return;
         }
         
         // Allow targeting enemy units/buildings for combat
-        if (is_attackable || is_enemy_targetable) && selected_entity.0.is_some() {
-            info!("select_entity_system: Clicked on enemy target {:?}, keeping selected: {:?}", target_entity, selected_entity.0);
+        if (is_attackable || is_enemy_targetable) && !selection.is_empty() {
+            info!("select_entity_system: Clicked on enemy target {:?}, keeping selected: {:?}", target_entity, selection.0);
             // Don't return here - let combat system handle the attack!
         }
     }
@@ -187,7 +192,7 @@ pub fn handle_enemy_clicks(
     mut click_events: EventReader<Pointer<Click>>,
     enemy_query: Query<(Entity, &Transform, &Health), With<Enemy>>,
     collider_query: Query<&LinkedToEnemy>,
-    selected_entity: Res<SelectedEntity>,
+    selection: Res<Selection>,
     turn_state: Res<TurnState>,
 ) {
     // Блокируем все клики во время хода ИИ
@@ -218,8 +223,8 @@ pub fn handle_enemy_clicks(
             info!("Clicked on enemy entity {:?} at position {:?} with health {:.1}/{:.1}", 
                   enemy_entity, enemy_transform.translation, enemy_health.current, enemy_health.max);
             
-            if let Some(selected) = selected_entity.0 {
-                info!("Selected unit {:?} will target enemy {:?}", selected, enemy_entity);
+            if !selection.is_empty() {
+                info!("Selected units {:?} will target enemy {:?}", selection.0, enemy_entity);
             } else {
                 info!("No unit selected to attack enemy {:?}", enemy_entity);
             }
@@ -228,72 +233,67 @@ pub fn handle_enemy_clicks(
 }
 
 /// system for updating mouse world position
-#[allow(dead_code)]
 pub fn update_mouse_world_position(
     mut mouse_position: ResMut<MouseWorldPosition>,
     camera: Query<(&Camera, &GlobalTransform), With<crate::game::MainCamera>>,
     windows: Query<&Window>,
 ) {
-    let window = windows.single();
-    if let Some(cursor_position) = window.cursor_position() {
-        if let Ok((camera, camera_transform)) = camera.get_single() {
-            if let Some(ray) = camera.viewport_to_world(camera_transform, cursor_position) {
-                // Create a ray from the cursor and project it into 3D world
-                // Find where the ray intersects with the y=0 plane
-                let plane_normal = Vec3::Y;
-                let plane_origin = Vec3::ZERO;
-                
-                let denominator = plane_normal.dot(*ray.direction);
-                
-                if denominator.abs() > 0.00001 {
-                    let t = (plane_normal.dot(plane_origin - ray.origin)) / denominator;
-                    
-                    if t >= 0.0 {
-                        let world_position = ray.origin + *ray.direction * t;
-                        
-                        info!("Mouse world position: {:?}, ray dir: {:?}, camera pos: {:?}", 
-                              world_position, ray.direction, camera_transform.translation());
-                        
-                        mouse_position.0 = Some(world_position);
-                        return;
-                    } else {
-                        info!("Ray intersection with ground plane is behind camera, t={}", t);
-                    }
-                } else {
-                    info!("Ray is nearly parallel to ground plane, denominator={}", denominator);
-                }
-            }
+    let Some(cursor_position) = windows.get_single().ok().and_then(|window| window.cursor_position()) else {
+        return;
+    };
+    let Ok((camera, camera_transform)) = camera.get_single() else {
+        return;
+    };
+    // Create a ray from the cursor and find where it intersects with the y=0 plane.
+    // If we couldn't get a valid position, don't change the existing one
+    if let Some(ray) = camera.viewport_to_world(camera_transform, cursor_position) {
+        if let Some(distance) = ray.intersect_plane(Vec3::ZERO, Plane3d::new(Vec3::Y)) {
+            mouse_position.0 = Some(ray.get_point(distance));
         }
     }
-    
-    // If we couldn't get a valid position, don't change the existing one
 }
 
 /// Система для сброса выделения по правому клику мыши
 pub fn deselect_on_right_click(
     mouse_button_input: Res<ButtonInput<MouseButton>>,
-    mut selected_entity: ResMut<SelectedEntity>,
+    mut selection: ResMut<Selection>,
+    workplaces: Query<&PickingInteraction, crate::game::construction::EngineerWorkplace>,
 ) {
-    if mouse_button_input.just_pressed(MouseButton::Right) && selected_entity.0.is_some() {
-        info!("🚫 Сбрасываем выделение юнита {:?} по правому клику", selected_entity.0);
-        selected_entity.0 = None;
+    // Правый клик по окопу, стройке или зданию - приказ пехоте или инженерам, выделение остается
+    if workplaces.iter().any(|interaction| *interaction != PickingInteraction::None) {
+        return;
+    }
+    if mouse_button_input.just_pressed(MouseButton::Right) && !selection.is_empty() {
+        info!("🚫 Сбрасываем выделение {:?} по правому клику", selection.0);
+        selection.clear();
     }
 }
 
 /// processing ground clicks for moving existing objects
+#[allow(clippy::too_many_arguments)]
 pub fn handle_ground_clicks(
     mut commands: Commands,
     mut click_events: EventReader<Pointer<Click>>,
     query_ground: Query<(), With<Ground>>,
     mut click_circle: ResMut<ClickCircle>,
     time: Res<Time>,
-    selected_entity_res: Res<SelectedEntity>,
+    selection: Res<Selection>,
     turn_state: Res<TurnState>,
+    mut attack_move_armed: ResMut<AttackMoveArmed>,
+    box_selection: Res<BoxSelection>,
+    formation_settings: Res<FormationSettings>,
+    formation_members: Query<FormationMemberData, FormationMemberFilter>,
+    placement_state: Res<crate::game::PlacementState>,
 ) {
     // Блокируем все клики во время хода ИИ
     if turn_state.current_player != PlayerTurn::Human {
         return;
     }
+    // Отпущенная рамка выделения и клик при размещении постройки - это не приказ движения
+    if box_selection.finished_this_frame || placement_state.active {
+        click_events.clear();
+        return;
+    }
     let mut clicked_on_ground = false;
     let mut ground_click_position: Option<Vec3> = None;
     
@@ -313,24 +313,47 @@ pub fn handle_ground_clicks(
     }
     
     // Logic for determining ground click for movement
-    if clicked_on_ground && selected_entity_res.0.is_some() && ground_click_position.is_some() {
+    if clicked_on_ground && !selection.is_empty() && ground_click_position.is_some() {
         let target_point = ground_click_position.unwrap();
+        // Атакующий марш взводится хоткеем на один приказ для всей группы
+        let attack_move = std::mem::take(&mut attack_move_armed.0);
         
-        if let Some(entity_to_move) = selected_entity_res.0 {
-            info!("handle_ground_clicks: Sending order to move for {:?} to point {:?}", entity_to_move, target_point);
+        // Юниты расходятся по местам в строю вокруг точки клика
+        let members: Vec<FormationMember> = selection.iter()
+            .filter_map(|entity| formation_members.get(entity).ok())
+            .map(FormationMember::from_query)
+            .collect();
+        let destinations = plan_formation(formation_settings.kind, target_point, &members);
+        let pace = group_pace(&members).filter(|_| formation_settings.move_together && members.len() > 1);
+        
+        for entity_to_move in selection.iter() {
+            let formation_slot = destinations.iter().find(|(entity, _)| *entity == entity_to_move).map(|(_, slot)| *slot);
+            let destination = formation_slot.unwrap_or(target_point);
+            info!("handle_ground_clicks: Sending order to move for {:?} to point {:?}", entity_to_move, destination);
             
             // Check if entity still exists before trying to move it
             if let Some(mut entity_commands) = commands.get_entity(entity_to_move) {
-                // Send movement command
-                entity_commands.insert(MovementOrder(target_point));
-                
-                // Update click circle display info
-                click_circle.position = Some(target_point);
-                click_circle.spawn_time = Some(time.elapsed_seconds());
+                // Send movement command; after the attack-move hotkey the unit fights on the way
+                if attack_move {
+                    entity_commands.insert((MovementOrder(destination), AttackMove(destination)));
+                    info!("handle_ground_clicks: Attack-move order for {:?}", entity_to_move);
+                } else {
+                    entity_commands.insert(MovementOrder(destination));
+                    entity_commands.remove::<AttackMove>();
+                }
+                // "Двигаться вместе": вся группа идет со скоростью самого медленного
+                match pace.filter(|_| formation_slot.is_some()) {
+                    Some(pace) => entity_commands.insert(FormationSpeed(pace)),
+                    None => entity_commands.remove::<FormationSpeed>(),
+                };
             } else {
                 info!("handle_ground_clicks: Entity {:?} no longer exists, cannot move", entity_to_move);
             }
         }
+        
+        // Update click circle display info
+        click_circle.position = Some(target_point);
+        click_circle.spawn_time = Some(time.elapsed_seconds());
     }
     
     // Добавляем логику для сброса выделения при правом клике по земле
@@ -355,6 +378,8 @@ pub fn handle_placement_clicks(
     mut processed_clicks: ResMut<ProcessedClicks>,
     turn_state: Res<TurnState>,
     player_faction: Res<crate::game::units::PlayerFaction>,
+    unit_catalog: Res<crate::game::units::catalog::UnitCatalog>,
+    mut resources: crate::systems::ai_opponent::SideResources,
 ) {
     // Блокируем все клики во время хода ИИ
     if turn_state.current_player != PlayerTurn::Human {
@@ -364,6 +389,10 @@ pub fn handle_placement_clicks(
     if !placement_state.active || placement_state.shape_type.is_none() {
         return;
     }
+    // Окопы прокладываются перетаскиванием в place_trench_line
+    if matches!(placement_state.shape_type, Some(crate::game::ShapeType::Trench)) {
+        return;
+    }
 
     let mut clicked_on_ground = false;
     let mut ground_click_position: Option<Vec3> = None;
@@ -398,7 +427,11 @@ pub fn handle_placement_clicks(
         
         // Use player faction from system resources for proper model selection
         info!("🔥 Calling place_shape with shape_type: {:?}", shape_type);
-        crate::ui::money_ui::place_shape(
+        let cost = placement_state.cost.unwrap_or_default();
+        let mut wallet = resources.wallet(crate::systems::ai_opponent::AISide::Player);
+        if !wallet.amounts().covers(&cost) {
+            info!("Not enough resources left to place {:?}", shape_type);
+        } else if crate::ui::money_ui::place_shape(
             &mut commands,
             shape_type,
             target_point,
@@ -406,14 +439,21 @@ pub fn handle_placement_clicks(
             &mut materials,
             &asset_server,
             &player_faction,
+            &unit_catalog,
             placement_state.unit_type_index,
-        );
+        ).is_some() {
+            // Платим только за то, что действительно появилось на карте
+            wallet.deduct(&cost);
+        } else {
+            info!("🔥 place_shape spawned nothing for {:?}, purchase cancelled", shape_type);
+        }
         info!("🔥 place_shape call completed!");
         
         // Reset placement mode after successful spawn
         placement_state.active = false;
         placement_state.shape_type = None;
         placement_state.unit_type_index = None;
+        placement_state.cost = None;
         
         /*
         OLD PRIMITIVE CREATION CODE REMOVED - was creating Cuboid/Sphere primitives instead of 3D models
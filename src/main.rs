@@ -1,5 +1,6 @@
 use bevy::prelude::*;
 use bevy_hanabi::prelude::*;
+use big_brain::prelude::BigBrainSet;
 use bevy_mod_picking::prelude::*;
 use bevy_mod_picking::DefaultPickingPlugins;
 use bevy_rapier3d::prelude::*;
@@ -16,7 +17,7 @@ mod utils;
 use game::*;
 use input::selection::{ProcessedClicks, handle_enemy_clicks, select_entity_system, handle_ground_clicks, handle_placement_clicks, debug_all_clicks, raycast_unit_selection, deselect_on_right_click};
 use input::*;
-use menu::common::{DisplayQuality, GameState, Volume};
+use menu::common::{DisplayQuality, GameState, OnMatchEnd, OnMatchStart, Volume};
 use systems::*;
 use ui::menu::menu_plugin;
 use ui::splash::splash_plugin;
@@ -43,6 +44,25 @@ impl Default for FpsLimiter {
 }
 
 fn main() {
+    // Режим без окна для прогонов ИИ против ИИ
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.iter().any(|arg| arg == "--headless") {
+        match systems::headless::HeadlessOptions::from_args(&args) {
+            Ok(options) => {
+                if let Err(err) = systems::headless::run_headless(&options) {
+                    eprintln!("❌ {}", err);
+                    std::process::exit(1);
+                }
+            }
+            Err(err) => {
+                eprintln!("❌ {}", err);
+                eprintln!("{}", systems::headless::USAGE);
+                std::process::exit(2);
+            }
+        }
+        return;
+    }
+
     App::new()
         .add_plugins(DefaultPlugins.set(WindowPlugin {
             primary_window: Some(Window {
@@ -63,11 +83,15 @@ fn main() {
                 .disable::<DebugPickingPlugin>(),
         )
         .init_resource::<ClickCircle>()
-        .init_resource::<SelectedEntity>()
+        .init_resource::<Selection>()
         .init_resource::<CameraSettings>()
         .init_resource::<CameraMovementState>()
+        // Назначения клавиш из config/key_bindings.toml
+        .add_plugins(input::key_bindings::KeyBindingsPlugin)
         .init_resource::<ProcessedClicks>()
-        .init_resource::<systems::AIBehavior>()
+        .init_resource::<systems::AIPlayers>()
+        .init_resource::<systems::ai_scheduler::AIScheduler>()
+        .init_resource::<utils::GameRng>()
         .init_resource::<systems::TurnState>()
         .init_resource::<FpsLimiter>()
         .init_resource::<ui::money_ui::AIMoney>()
@@ -83,7 +107,11 @@ fn main() {
         .init_resource::<ui::notification_system::NotificationState>()
         .insert_resource(DisplayQuality::Medium)
         .insert_resource(Volume(7))
+        .insert_resource(systems::AIDifficulty::Medium)
+        .insert_resource(systems::AIStrategy::Balanced)
         .init_state::<GameState>()
+        .add_plugins(menu::common::match_lifecycle_plugin)
+        .add_event::<systems::AIPurchaseEvent>()
         .add_systems(Startup, setup_ui_camera)
         .add_systems(Update, fps_limiter_system)
         .add_systems(
@@ -94,10 +122,21 @@ fn main() {
             Update,
             handle_escape_key.run_if(in_state(GameState::Game).or_else(in_state(GameState::Paused))),
         )
+        // Сетка проходимости и маршруты в обход зданий - до движения юнитов
+        .add_plugins(systems::pathfinding::PathfindingPlugin)
+        .configure_sets(Update, systems::pathfinding::PathfindingSet.run_if(in_state(GameState::Game)))
+        // Пространственный хэш юнитов для расталкивания и обхода соседей
+        .add_plugins(systems::steering::SteeringPlugin)
+        .configure_sets(
+            Update,
+            systems::steering::SteeringSet
+                .after(systems::pathfinding::PathfindingSet)
+                .run_if(in_state(GameState::Game)),
+        )
         .add_systems(
             Update,
             (
-                process_movement_orders,
+                process_movement_orders.after(systems::steering::SteeringSet),
                 systems::movement::cleanup_tank_movement_audio,
                 ui::health_bars::draw_health_bars,
                 aircraft_movement,
@@ -106,6 +145,32 @@ fn main() {
             )
                 .run_if(in_state(GameState::Game)),
         )
+        // Окопы: прокладка линией, стройка, гарнизон по правому клику, выход по новому приказу, выброс из разрушенного окопа
+        .init_resource::<input::selection::MouseWorldPosition>()
+        .init_resource::<game::TrenchCost>()
+        .init_resource::<game::trench::TrenchDrag>()
+        .add_systems(
+            Update,
+            (
+                (
+                    input::selection::update_mouse_world_position,
+                    game::trench::toggle_trench_placement,
+                    game::trench::place_trench_line.after(handle_ground_clicks),
+                    game::trench::draw_trench_placement_preview,
+                )
+                    .chain(),
+                game::trench::update_trench_construction,
+                game::trench::draw_trench_construction_progress,
+                (
+                    game::trench::order_trench_garrison,
+                    game::trench::enter_trenches,
+                    game::trench::leave_trenches,
+                    game::trench::update_trench_garrisons,
+                )
+                    .chain(),
+            )
+                .run_if(in_state(GameState::Game)),
+        )
         .add_systems(
             Update,
             (debug_all_clicks, handle_enemy_clicks, raycast_unit_selection, deselect_on_right_click).run_if(in_state(GameState::Game)),
@@ -114,6 +179,32 @@ fn main() {
             Update,
             handle_placement_clicks.run_if(in_state(GameState::Game)),
         )
+        // Хоткеи стройплощадок зданий: цены из таблицы баланса, поэтому ждут ее загрузки
+        .add_systems(
+            Update,
+            (
+                game::spawn_forest_farm_on_keystroke,
+                game::spawn_mine_on_keystroke,
+                game::spawn_steel_factory_on_keystroke,
+                game::spawn_petrochemical_plant_on_keystroke,
+            )
+                .run_if(in_state(GameState::Game).and_then(resource_exists::<BalanceLoaded<ui::cost_table::CostTable>>)),
+        )
+        // Выделение рамкой и группы 1..9; рамка проверяется до приказа движения
+        .init_resource::<input::group_selection::BoxSelection>()
+        .init_resource::<input::group_selection::ControlGroups>()
+        .init_resource::<systems::formations::FormationSettings>()
+        .add_systems(
+            Update,
+            (
+                input::group_selection::box_select_system.before(handle_ground_clicks),
+                input::group_selection::draw_selection_box,
+                input::group_selection::control_group_hotkeys,
+                systems::formations::formation_hotkeys,
+            )
+                .run_if(in_state(GameState::Game)),
+        )
+        .add_systems(OnMatchEnd, input::group_selection::reset_group_selection)
         .add_systems(
             Update,
             handle_ground_clicks.run_if(in_state(GameState::Game)),
@@ -122,6 +213,20 @@ fn main() {
             Update,
             handle_attacks.run_if(in_state(GameState::Game)),
         )
+        // Стойки и атакующий марш: юниты игрока сами выбирают цели в радиусе; турели башен стреляют сами
+        .init_resource::<systems::stances::AttackMoveArmed>()
+        .add_systems(
+            Update,
+            (
+                systems::stances::unit_order_hotkeys,
+                systems::stances::attack_move_system,
+                systems::stances::player_auto_fire_system,
+                // Тот же GameRng, поэтому строго после огня юнитов
+                tower::tower_turret_fire_system.after(systems::stances::player_auto_fire_system),
+                systems::stances::record_attackers.after(systems::projectiles::ProjectileSet),
+            )
+                .run_if(in_state(GameState::Game)),
+        )
         .add_systems(
             Update,
             (
@@ -134,20 +239,45 @@ fn main() {
                 game::scene_colliders::handle_child_hover,
             ).run_if(in_state(GameState::Game)),
         )
+        // RTS-камера: ввод меняет желаемое положение, apply_camera_rig плавно двигает камеру
+        .init_resource::<input::camera::CameraRig>()
         .add_systems(
             Update,
             (
-                camera_zoom_system,
-                camera_right_button_movement,
-                camera_follow_selected,
+                (
+                    camera_zoom_system,
+                    camera_keyboard_pan,
+                    camera_edge_scroll,
+                    camera_rotation,
+                    camera_follow_toggle,
+                    camera_right_button_movement,
+                    camera_follow_selected,
+                ),
+                apply_camera_rig,
             )
+                .chain()
                 .run_if(in_state(GameState::Game)),
         )
+        .add_systems(OnEnter(GameState::Menu), input::camera::reset_camera_rig)
         .add_systems(
             Update,
             (
-                tower::repair_tower,
                 tower::update_tower_health_status,
+                tower::draw_tower_repair_progress,
+            )
+                .run_if(in_state(GameState::Game)),
+        )
+        // Инженеры: стройплощадки, рытье окопов и ремонт зданий и башен
+        .add_systems(
+            Update,
+            (
+                (
+                    game::construction::order_engineers,
+                    game::construction::engineer_work,
+                    game::construction::finish_construction_sites,
+                )
+                    .chain(),
+                game::construction::draw_construction_site_progress,
             )
                 .run_if(in_state(GameState::Game)),
         )
@@ -169,28 +299,55 @@ fn main() {
         )
         .add_systems(
             Update,
-            systems::ai_opponent::ai_purchase_system.run_if(in_state(GameState::Game)),
+            systems::ai_scheduler::tick_ai_scheduler.run_if(in_state(GameState::Game)),
         )
+        // Мыслители ИИ работают только во время игры (на паузе и в меню они замирают)
+        .add_plugins(systems::ai_opponent::AIBrainPlugin)
+        // Сетка целей для поиска противника в радиусе
+        .add_plugins(systems::spatial_index::TargetIndexPlugin)
+        // Туман войны: обзор сторон, слой тумана на земле и скрытие невидимых врагов
+        .add_plugins(systems::fog_of_war::FogOfWarPlugin)
+        .add_systems(OnMatchStart, systems::fog_of_war::spawn_fog_overlay)
+        .add_systems(
+            Update,
+            (systems::fog_of_war::hide_fogged_enemies, systems::fog_of_war::update_fog_overlay)
+                .run_if(in_state(GameState::Game)),
+        )
+        .configure_sets(
+            PreUpdate,
+            (BigBrainSet::Scorers, BigBrainSet::Thinkers, BigBrainSet::Actions).run_if(in_state(GameState::Game)),
+        )
+        // Снаряды летят только во время игры; эффекты догорают и на паузе
+        .add_plugins(systems::projectiles::ProjectilePlugin)
+        .configure_sets(Update, systems::projectiles::ProjectileSet.run_if(in_state(GameState::Game)))
+        .add_systems(Startup, utils::setup_combat_effects)
         .add_systems(
             Update,
             (
-                systems::ai_opponent::ai_movement_system,
-                systems::ai_opponent::ai_combat_system,
-            ).run_if(in_state(GameState::Game)),
+                utils::spawn_projectile_effects,
+                utils::spawn_impact_effects,
+                utils::despawn_finished_effects,
+            ),
         )
         .add_systems(
-            OnEnter(GameState::Game),
-            systems::ai_economy::ai_initial_resources_system,
+            OnMatchStart,
+            (
+                systems::ai_opponent::apply_ai_settings,
+                systems::ai_economy::ai_initial_resources_system,
+            ).chain(),
         )
-        .add_systems(OnExit(GameState::Game), (reset_placement_state, reset_game_state, cleanup_ui_camera))
+        .add_systems(OnMatchEnd, (reset_placement_state, reset_game_state, cleanup_ui_camera))
         .add_systems(OnEnter(GameState::Game), menu::main_menu::cleanup_menu_cameras)
         .add_plugins((
             splash_plugin,
             menu_plugin,
             game_plugin::game_plugin,
+            utils::BalancePlugin::<game::units::catalog::UnitCatalog>::default(),
+            utils::BalancePlugin::<game::units::armor::DamageMatrix>::default(),
             ui::money_ui::MoneyUiPlugin,
             ui::ui_plugin,
             systems::enemy_visual_markers::EnemyVisualMarkersPlugin,
+            systems::save_load::SaveLoadPlugin,
         ))
         .run();
 }
@@ -213,6 +370,7 @@ fn reset_placement_state(mut placement_state: ResMut<PlacementState>) {
     placement_state.active = false;
     placement_state.shape_type = None;
     placement_state.unit_type_index = None;
+    placement_state.cost = None;
 }
 
 fn reset_game_state(
@@ -228,7 +386,9 @@ fn reset_game_state(
     mut ai_oil: ResMut<ui::money_ui::AIOil>,
     mut turn_state: ResMut<systems::turn_system::TurnState>,
     mut victory_state: ResMut<systems::victory_system::VictoryState>,
-    mut selected_entity: ResMut<SelectedEntity>,
+    mut selection: ResMut<Selection>,
+    mut ai_scheduler: ResMut<systems::ai_scheduler::AIScheduler>,
+    mut fog_of_war: ResMut<systems::fog_of_war::FogOfWar>,
 ) {
     // Reset player resources to starting values
     money.0 = 100.0;
@@ -253,9 +413,16 @@ fn reset_game_state(
     victory_state.game_ended = false;
     victory_state.victory_timer = None;
     victory_state.defeat_timer = None;
+    victory_state.towers_spawned = false;
+
+    // Reset AI decision timers
+    ai_scheduler.reset();
     
     // Clear selected entity
-    selected_entity.0 = None;
+    selection.clear();
+
+    // Карта снова не разведана
+    *fog_of_war = systems::fog_of_war::FogOfWar::default();
     
     println!("🔄 Game state reset for new game");
 }
@@ -1,3 +1,4 @@
+use bevy::ecs::schedule::ScheduleLabel;
 use bevy::prelude::*;
 
 pub const NORMAL_BUTTON: Color = Color::rgb(0.15, 0.15, 0.15);
@@ -18,12 +19,49 @@ pub enum GameState {
     Paused,
 }
 
+/// Начало матча: вход в игру из меню. Возврат из паузы мир не пересоздает
+#[derive(ScheduleLabel, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct OnMatchStart;
+
+/// Конец матча: уход в меню из игры или из паузы
+#[derive(ScheduleLabel, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct OnMatchEnd;
+
+fn run_match_start(world: &mut World) {
+    world.try_run_schedule(OnMatchStart).ok();
+}
+
+fn run_match_end(world: &mut World) {
+    world.try_run_schedule(OnMatchEnd).ok();
+}
+
+fn pause_virtual_time(mut time: ResMut<Time<Virtual>>) {
+    time.pause();
+}
+
+fn resume_virtual_time(mut time: ResMut<Time<Virtual>>) {
+    time.unpause();
+}
+
+/// The match lives across the pause menu: game systems stop via `in_state(GameState::Game)`
+/// and virtual time is frozen, so projectiles, AI and timers resume exactly where they were.
+pub fn match_lifecycle_plugin(app: &mut App) {
+    app.init_schedule(OnMatchStart)
+        .init_schedule(OnMatchEnd)
+        .add_systems(OnTransition { from: GameState::Menu, to: GameState::Game }, run_match_start)
+        .add_systems(OnTransition { from: GameState::Game, to: GameState::Menu }, run_match_end)
+        .add_systems(OnTransition { from: GameState::Paused, to: GameState::Menu }, run_match_end)
+        .add_systems(OnEnter(GameState::Paused), pause_virtual_time)
+        .add_systems(OnExit(GameState::Paused), resume_virtual_time);
+}
+
 #[derive(Clone, Copy, Default, Eq, PartialEq, Debug, Hash, States)]
 pub enum MenuState {
     Main,
     Settings,
     SettingsDisplay,
     SettingsSound,
+    SettingsControls,
     #[default]
     Disabled,
 }
@@ -47,10 +85,13 @@ pub enum MenuButtonAction {
     Settings,
     SettingsDisplay,
     SettingsSound,
+    SettingsControls,
     BackToMainMenu,
     BackToSettings,
     Quit,
     Resume,
+    SaveGame,
+    LoadGame,
     BackToMenu,
 }
 
@@ -71,9 +112,12 @@ pub fn button_system(
     }
 }
 
+/// The selected button of one settings group
+type SelectedOptionOf<T> = (With<SelectedOption>, With<T>);
+
 pub fn setting_button<T: Resource + Component + PartialEq + Copy>(
     interaction_query: Query<(&Interaction, &T, Entity), (Changed<Interaction>, With<Button>)>,
-    mut selected_query: Query<(Entity, &mut BackgroundColor), With<SelectedOption>>,
+    mut selected_query: Query<(Entity, &mut BackgroundColor), SelectedOptionOf<T>>,
     mut commands: Commands,
     mut setting: ResMut<T>,
 ) {
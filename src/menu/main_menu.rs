@@ -1,11 +1,12 @@
 use super::common::*;
 use bevy::{app::AppExit, prelude::*};
+use crate::systems::ai_opponent::{AIDifficulty, AIStrategy};
 
 // Faction selection components
 #[derive(Component)]
 pub struct FactionSelection;
 
-#[derive(Component, Clone, Copy, Debug, PartialEq)]
+#[derive(Component, Clone, Copy, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
 pub enum Faction {
     Entente,
     CentralPowers,
@@ -379,6 +380,8 @@ pub fn animate_menu_transition(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
     faction_query: Query<Entity, With<FactionSelection>>,
+    ai_difficulty: Res<AIDifficulty>,
+    ai_strategy: Res<AIStrategy>,
 ) {
     // Update the timer
     timer.timer.tick(time.delta());
@@ -424,7 +427,7 @@ pub fn animate_menu_transition(
                 // Check if faction selection UI is already spawned
                 if faction_query.iter().count() == 0 {
                     // Spawn faction selection UI
-                    spawn_faction_selection(&mut commands, &asset_server);
+                    spawn_faction_selection(&mut commands, &asset_server, *ai_difficulty, *ai_strategy);
                 }
 
                 // Don't transition to game state yet - wait for faction selection
@@ -469,11 +472,14 @@ pub fn menu_action(
                 MenuButtonAction::SettingsSound => {
                     menu_state.set(MenuState::SettingsSound);
                 }
+                MenuButtonAction::SettingsControls => {
+                    menu_state.set(MenuState::SettingsControls);
+                }
                 MenuButtonAction::BackToMainMenu => menu_state.set(MenuState::Main),
                 MenuButtonAction::BackToSettings => {
                     menu_state.set(MenuState::Settings);
                 }
-                MenuButtonAction::Resume => {
+                MenuButtonAction::Resume | MenuButtonAction::SaveGame | MenuButtonAction::LoadGame => {
                     // This action is handled in the pause menu
                 }
                 MenuButtonAction::BackToMenu => {
@@ -485,7 +491,12 @@ pub fn menu_action(
 }
 
 // Function to spawn the faction selection UI
-pub fn spawn_faction_selection(commands: &mut Commands, asset_server: &Res<AssetServer>) {
+pub fn spawn_faction_selection(
+    commands: &mut Commands,
+    asset_server: &Res<AssetServer>,
+    ai_difficulty: AIDifficulty,
+    ai_strategy: AIStrategy,
+) {
     let entente_image = asset_server.load("pic/Entente.png");
     let central_powers_image = asset_server.load("pic/CentralPowers.png");
 
@@ -616,11 +627,99 @@ pub fn spawn_faction_selection(commands: &mut Commands, asset_server: &Res<Asset
                         ));
                     });
                 });
+
+            // Настройки ИИ противника (выбираются до выбора коалиции)
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        position_type: PositionType::Absolute,
+                        bottom: Val::Px(20.0),
+                        flex_direction: FlexDirection::Column,
+                        align_items: AlignItems::Center,
+                        padding: UiRect::all(Val::Px(10.0)),
+                        ..default()
+                    },
+                    background_color: Color::rgba(0.0, 0.0, 0.0, 0.6).into(),
+                    ..default()
+                })
+                .with_children(|parent| {
+                    spawn_ai_option_row(
+                        parent,
+                        asset_server,
+                        "Enemy difficulty",
+                        &[AIDifficulty::Easy, AIDifficulty::Medium, AIDifficulty::Hard],
+                        ai_difficulty,
+                    );
+                    spawn_ai_option_row(
+                        parent,
+                        asset_server,
+                        "Enemy strategy",
+                        &[AIStrategy::Rusher, AIStrategy::Defender, AIStrategy::Economic, AIStrategy::Balanced],
+                        ai_strategy,
+                    );
+                });
         });
 
     // No longer spawning the world map here - it's already spawned in main_menu_setup
 }
 
+/// Row of option buttons for one AI setting; the current value starts selected
+fn spawn_ai_option_row<T: Component + Copy + PartialEq + std::fmt::Debug>(
+    parent: &mut ChildBuilder,
+    asset_server: &Res<AssetServer>,
+    label: &str,
+    options: &[T],
+    current: T,
+) {
+    let text_style = TextStyle {
+        font: asset_server.load("fonts/GrenzeGotisch-Light.ttf"),
+        font_size: 24.0,
+        color: TEXT_COLOR,
+    };
+
+    parent
+        .spawn(NodeBundle {
+            style: Style {
+                flex_direction: FlexDirection::Row,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            ..default()
+        })
+        .with_children(|parent| {
+            parent.spawn(
+                TextBundle::from_section(label, text_style.clone()).with_style(Style {
+                    width: Val::Px(180.0),
+                    ..default()
+                }),
+            );
+
+            for option in options {
+                let mut entity_commands = parent.spawn((
+                    ButtonBundle {
+                        style: Style {
+                            width: Val::Px(130.0),
+                            height: Val::Px(40.0),
+                            margin: UiRect::all(Val::Px(5.0)),
+                            justify_content: JustifyContent::Center,
+                            align_items: AlignItems::Center,
+                            ..default()
+                        },
+                        background_color: NORMAL_BUTTON.into(),
+                        ..default()
+                    },
+                    *option,
+                ));
+                entity_commands.with_children(|parent| {
+                    parent.spawn(TextBundle::from_section(format!("{:?}", option), text_style.clone()));
+                });
+                if *option == current {
+                    entity_commands.insert(SelectedOption);
+                }
+            }
+        });
+}
+
 // System for faction hover - now adds green outline when hovering over faction images
 pub fn faction_hover_system(
     mut interaction_query: Query<
@@ -725,7 +824,13 @@ pub fn main_menu_plugin(app: &mut App) {
         )
         .add_systems(
             Update,
-            (faction_hover_system, faction_selection_system, twitter_button_system).run_if(in_state(MenuState::Main)),
+            (
+                faction_hover_system,
+                faction_selection_system,
+                twitter_button_system,
+                setting_button::<AIDifficulty>,
+                setting_button::<AIStrategy>,
+            ).run_if(in_state(MenuState::Main)),
         )
         .add_systems(OnExit(MenuState::Main), despawn_main_menu);
 }
\ No newline at end of file
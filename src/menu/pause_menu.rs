@@ -1,5 +1,6 @@
 use super::common::*;
 use bevy::{app::AppExit, prelude::*};
+use crate::systems::save_load::{read_save_game, write_save_game, PendingLoad, SaveGameSource, SAVE_GAME_PATH};
 
 #[derive(Component)]
 pub struct OnPauseMenuScreen;
@@ -98,6 +99,25 @@ pub fn pause_menu_setup(mut commands: Commands, asset_server: Res<AssetServer>)
                             ));
                         });
 
+                    // Save and Load buttons
+                    for (action, label) in [(MenuButtonAction::SaveGame, "Save Game"), (MenuButtonAction::LoadGame, "Load Game")] {
+                        parent
+                            .spawn((
+                                ButtonBundle {
+                                    style: button_style.clone(),
+                                    background_color: NORMAL_BUTTON.into(),
+                                    ..default()
+                                },
+                                action,
+                            ))
+                            .with_children(|parent| {
+                                parent.spawn(TextBundle::from_section(
+                                    label,
+                                    button_text_style.clone(),
+                                ));
+                            });
+                    }
+
                     // Back to Main Menu button
                     parent
                         .spawn((
@@ -140,9 +160,11 @@ pub fn pause_menu_action(
         (&Interaction, &MenuButtonAction),
         (Changed<Interaction>, With<Button>),
     >,
+    mut commands: Commands,
     mut app_exit_events: EventWriter<AppExit>,
     mut game_state: ResMut<NextState<GameState>>,
     mut menu_state: ResMut<NextState<MenuState>>,
+    source: SaveGameSource,
 ) {
     for (interaction, menu_button_action) in &interaction_query {
         if *interaction == Interaction::Pressed {
@@ -150,6 +172,23 @@ pub fn pause_menu_action(
                 MenuButtonAction::Resume => {
                     game_state.set(GameState::Game);
                 }
+                MenuButtonAction::SaveGame => {
+                    // На паузе матч остается в мире, сохраняем его как есть
+                    match write_save_game(&source.capture(), SAVE_GAME_PATH) {
+                        Ok(()) => info!("💾 Game saved to {}", SAVE_GAME_PATH),
+                        Err(err) => error!("Save failed: {}", err),
+                    }
+                }
+                MenuButtonAction::LoadGame => {
+                    match read_save_game(SAVE_GAME_PATH) {
+                        Ok(save) => {
+                            info!("📂 Loading game from {}", SAVE_GAME_PATH);
+                            commands.insert_resource(PendingLoad(save));
+                            game_state.set(GameState::Game);
+                        }
+                        Err(err) => error!("Load failed: {}", err),
+                    }
+                }
                 MenuButtonAction::BackToMenu => {
                     println!("DEBUG: Pause menu BackToMenu pressed");
                     game_state.set(GameState::Menu);
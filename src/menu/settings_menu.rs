@@ -1,5 +1,6 @@
 use bevy::prelude::*;
 use super::common::*;
+use crate::input::key_bindings::{InputAction, KeyBinding, KeyBindings, KEY_BINDINGS_PATH};
 
 const CRIMSON: Color = Color::rgb(0.86, 0.08, 0.24);
 
@@ -12,6 +13,30 @@ pub struct OnDisplaySettingsMenuScreen;
 #[derive(Component)]
 pub struct OnSoundSettingsMenuScreen;
 
+#[derive(Component)]
+pub struct OnControlsSettingsMenuScreen;
+
+/// Button that rebinds an action: click it, then press the new key
+#[derive(Component)]
+pub struct RebindButton(pub InputAction);
+
+/// Text of a rebind button
+#[derive(Component)]
+pub struct BindingText(pub InputAction);
+
+/// Line under the bindings that lists the conflicts
+#[derive(Component)]
+pub struct KeyConflictsText;
+
+#[derive(Component)]
+pub struct ResetKeyBindingsButton;
+
+/// Action waiting for its new key
+#[derive(Resource, Default)]
+pub struct PendingRebind(pub Option<InputAction>);
+
+const CONFLICT_COLOR: Color = Color::rgb(1.0, 0.8, 0.2);
+
 pub fn settings_menu_setup(mut commands: Commands, asset_server: Res<AssetServer>) {
     let button_style = Style {
         width: Val::Px(200.0),
@@ -99,6 +124,21 @@ pub fn settings_menu_setup(mut commands: Commands, asset_server: Res<AssetServer
                 ));
             });
 
+            parent.spawn((
+                ButtonBundle {
+                    style: button_style.clone(),
+                    background_color: NORMAL_BUTTON.into(),
+                    ..default()
+                },
+                MenuButtonAction::SettingsControls,
+            ))
+            .with_children(|parent| {
+                parent.spawn(TextBundle::from_section(
+                    "Controls",
+                    button_text_style.clone(),
+                ));
+            });
+
             parent.spawn((
                 ButtonBundle {
                     style: button_style,
@@ -324,8 +364,253 @@ pub fn sound_settings_menu_setup(mut commands: Commands, volume: Res<Volume>, as
     });
 }
 
+pub fn controls_settings_menu_setup(mut commands: Commands, key_bindings: Res<KeyBindings>, asset_server: Res<AssetServer>) {
+    let button_style = Style {
+        width: Val::Px(200.0),
+        height: Val::Px(50.0),
+        margin: UiRect::all(Val::Px(10.0)),
+        justify_content: JustifyContent::Center,
+        align_items: AlignItems::Center,
+        ..default()
+    };
+
+    let button_text_style = TextStyle {
+        font: asset_server.load("fonts/GrenzeGotisch-Light.ttf"),
+        font_size: 33.0,
+        color: TEXT_COLOR,
+    };
+
+    let binding_text_style = TextStyle {
+        font_size: 20.0,
+        ..button_text_style.clone()
+    };
+
+    commands.spawn((
+        NodeBundle {
+            style: Style {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+            ..default()
+        },
+        OnControlsSettingsMenuScreen,
+    ))
+    .with_children(|parent| {
+        parent.spawn((
+            NodeBundle {
+                style: Style {
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    padding: UiRect::all(Val::Px(10.0)),
+                    ..default()
+                },
+                background_color: CRIMSON.into(),
+                ..default()
+            },
+        ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section("Controls", button_text_style.clone()));
+
+            // Два столбца: действие и кнопка с его клавишей
+            parent.spawn(NodeBundle {
+                style: Style {
+                    column_gap: Val::Px(30.0),
+                    ..default()
+                },
+                ..default()
+            })
+            .with_children(|parent| {
+                let half = InputAction::ALL.len().div_ceil(2);
+                for column in InputAction::ALL.chunks(half) {
+                    parent.spawn(NodeBundle {
+                        style: Style {
+                            flex_direction: FlexDirection::Column,
+                            ..default()
+                        },
+                        ..default()
+                    })
+                    .with_children(|parent| {
+                        for action in column {
+                            parent.spawn(NodeBundle {
+                                style: Style {
+                                    align_items: AlignItems::Center,
+                                    margin: UiRect::vertical(Val::Px(2.0)),
+                                    ..default()
+                                },
+                                ..default()
+                            })
+                            .with_children(|parent| {
+                                parent.spawn(
+                                    TextBundle::from_section(action.label(), binding_text_style.clone())
+                                        .with_style(Style {
+                                            width: Val::Px(220.0),
+                                            ..default()
+                                        }),
+                                );
+                                parent.spawn((
+                                    ButtonBundle {
+                                        style: Style {
+                                            width: Val::Px(120.0),
+                                            height: Val::Px(28.0),
+                                            justify_content: JustifyContent::Center,
+                                            align_items: AlignItems::Center,
+                                            ..default()
+                                        },
+                                        background_color: NORMAL_BUTTON.into(),
+                                        ..default()
+                                    },
+                                    RebindButton(*action),
+                                ))
+                                .with_children(|parent| {
+                                    parent.spawn((
+                                        TextBundle::from_section(
+                                            key_bindings.get(*action).label(),
+                                            binding_text_style.clone(),
+                                        ),
+                                        BindingText(*action),
+                                    ));
+                                });
+                            });
+                        }
+                    });
+                }
+            });
+
+            parent.spawn((
+                TextBundle::from_section("", TextStyle { color: CONFLICT_COLOR, ..binding_text_style.clone() })
+                    .with_style(Style {
+                        margin: UiRect::top(Val::Px(10.0)),
+                        ..default()
+                    }),
+                KeyConflictsText,
+            ));
+
+            parent.spawn(NodeBundle::default())
+            .with_children(|parent| {
+                parent.spawn((
+                    ButtonBundle {
+                        style: button_style.clone(),
+                        background_color: NORMAL_BUTTON.into(),
+                        ..default()
+                    },
+                    ResetKeyBindingsButton,
+                ))
+                .with_children(|parent| {
+                    parent.spawn(TextBundle::from_section("Defaults", button_text_style.clone()));
+                });
+
+                parent.spawn((
+                    ButtonBundle {
+                        style: button_style,
+                        background_color: NORMAL_BUTTON.into(),
+                        ..default()
+                    },
+                    MenuButtonAction::BackToSettings,
+                ))
+                .with_children(|parent| {
+                    parent.spawn(TextBundle::from_section("Back", button_text_style));
+                });
+            });
+        });
+    });
+}
+
+fn save_key_bindings(key_bindings: &KeyBindings) {
+    match key_bindings.save(KEY_BINDINGS_PATH) {
+        Ok(()) => info!("⌨️ Key bindings saved to {}", KEY_BINDINGS_PATH),
+        Err(err) => error!("{}", err),
+    }
+}
+
+/// Click on a binding waits for the new key; "Defaults" restores every binding
+pub fn controls_buttons(
+    rebind_buttons: Query<(&Interaction, &RebindButton), Changed<Interaction>>,
+    reset_buttons: Query<&Interaction, (Changed<Interaction>, With<ResetKeyBindingsButton>)>,
+    mut pending: ResMut<PendingRebind>,
+    mut key_bindings: ResMut<KeyBindings>,
+) {
+    for (interaction, button) in rebind_buttons.iter() {
+        if *interaction == Interaction::Pressed {
+            pending.0 = Some(button.0);
+        }
+    }
+    if reset_buttons.iter().any(|interaction| *interaction == Interaction::Pressed) {
+        pending.0 = None;
+        *key_bindings = KeyBindings::default();
+        save_key_bindings(&key_bindings);
+    }
+}
+
+/// The first key pressed after clicking a binding becomes its new key (Ctrl is kept as a modifier).
+/// Escape cancels.
+pub fn capture_rebind_key(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut pending: ResMut<PendingRebind>,
+    mut key_bindings: ResMut<KeyBindings>,
+) {
+    let Some(action) = pending.0 else {
+        return;
+    };
+    if keys.just_pressed(KeyCode::Escape) {
+        pending.0 = None;
+        return;
+    }
+    let Some(key) = keys.get_just_pressed().copied().find(|key| KeyBinding::is_bindable(*key)) else {
+        return;
+    };
+
+    let ctrl = keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]);
+    let binding = if ctrl { KeyBinding::ctrl(key) } else { KeyBinding::new(key) };
+    key_bindings.set(action, binding);
+    pending.0 = None;
+    info!("⌨️ {} -> {}", action.label(), binding.label());
+    key_bindings.log_conflicts();
+    save_key_bindings(&key_bindings);
+}
+
+/// Refreshes binding labels, highlights conflicting ones and lists the conflicts
+pub fn update_controls_texts(
+    key_bindings: Res<KeyBindings>,
+    pending: Res<PendingRebind>,
+    mut binding_texts: Query<(&mut Text, &BindingText), Without<KeyConflictsText>>,
+    mut conflicts_text: Query<&mut Text, With<KeyConflictsText>>,
+    opened: Query<(), Added<KeyConflictsText>>,
+) {
+    // Страница только что открыта или назначения поменялись
+    if opened.is_empty() && !key_bindings.is_changed() && !pending.is_changed() {
+        return;
+    }
+
+    for (mut text, binding_text) in binding_texts.iter_mut() {
+        let action = binding_text.0;
+        let section = &mut text.sections[0];
+        if pending.0 == Some(action) {
+            section.value = "press a key...".to_string();
+            section.style.color = TEXT_COLOR;
+        } else {
+            section.value = key_bindings.get(action).label();
+            section.style.color = if key_bindings.is_conflicting(action) { CONFLICT_COLOR } else { TEXT_COLOR };
+        }
+    }
+
+    if let Ok(mut text) = conflicts_text.get_single_mut() {
+        text.sections[0].value = key_bindings.conflicts().iter()
+            .map(|(first, second)| format!("{}: {} / {}", key_bindings.get(*first).label(), first.label(), second.label()))
+            .collect::<Vec<_>>()
+            .join("\n");
+    }
+}
+
+fn cancel_pending_rebind(mut pending: ResMut<PendingRebind>) {
+    pending.0 = None;
+}
+
 pub fn settings_menu_plugin(app: &mut App) {
     app
+        .init_resource::<PendingRebind>()
         .add_systems(OnEnter(MenuState::Settings), settings_menu_setup)
         .add_systems(OnEnter(MenuState::SettingsDisplay), display_settings_menu_setup)
         .add_systems(
@@ -336,5 +621,13 @@ pub fn settings_menu_plugin(app: &mut App) {
         .add_systems(
             Update,
             setting_button::<Volume>.run_if(in_state(MenuState::SettingsSound))
+        )
+        .add_systems(OnEnter(MenuState::SettingsControls), controls_settings_menu_setup)
+        .add_systems(OnExit(MenuState::SettingsControls), cancel_pending_rebind)
+        .add_systems(
+            Update,
+            (controls_buttons, capture_rebind_key, update_controls_texts)
+                .chain()
+                .run_if(in_state(MenuState::SettingsControls))
         );
 } 
\ No newline at end of file
@@ -1,29 +1,46 @@
 use bevy::prelude::*;
+use bevy::ecs::system::SystemParam;
 use bevy_rapier3d::prelude::*;
 use bevy_mod_picking::prelude::*;
+use big_brain::prelude::*;
 use crate::menu::main_menu::Faction;
-use crate::game::{Enemy, Health, CanShoot, ShapeType, MovementOrder, Tank, Selectable};
+use crate::game::{BuildOrder, ConstructionSite, Enemy, EnemyTower, Engineer, Health, CanShoot, MovementOrder, ShapeType, Tank, Selectable};
+use crate::game::construction::{engineer_stand, spawn_construction_site};
 
-use crate::ui::money_ui::{AIMoney, AIWood, AIIron, AISteel, AIOil, PurchasableItem, can_afford_item_ai, deduct_resources_ai};
+use crate::game::units::spawn_unit;
+use crate::game::units::catalog::{UnitCatalog, UnitClass};
+use crate::game::units::{weapon_stats, AIFaction, MilitaryUnit, PlayerFaction};
+use crate::game::units::armor::{ArmorClass, DamageMatrix, DamageType};
+use crate::ui::cost_table::CostTable;
+use crate::ui::money_ui::{Money, Wood, Iron, Steel, Oil, AIMoney, AIWood, AIIron, AISteel, AIOil, PurchasableItem, ResourceCost};
 use crate::systems::turn_system::{TurnState, PlayerTurn};
+use crate::systems::tower::{repair_tower, tower_repair_cost, TowerRepair, TowerRepairResult};
+use crate::systems::projectiles::{fire_projectile, ProjectileKind, Shot, TargetClass};
+use crate::systems::ai_scheduler::{open_ai_order_window, AIDecision, AIOrderWindow, AIScheduler};
+use crate::systems::fog_of_war::FogOfWar;
+use crate::systems::spatial_index::{IndexedTarget, TargetIndex};
+use crate::utils::{BalanceLoaded, GameRng};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
 
-#[derive(Resource, Debug)]
+#[derive(Debug)]
 pub struct AIBehavior {
     pub difficulty: AIDifficulty,
     pub strategy: AIStrategy,
     pub weights: AIWeights,
-    pub last_decision_time: f32,
 }
 
-#[derive(Debug, Clone, Copy)]
+/// Сложность ИИ; выбирается в главном меню перед матчем
+#[derive(Resource, Component, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum AIDifficulty {
     Easy,
     Medium,
     Hard,
 }
 
-#[derive(Debug, Clone, Copy)]
+/// Стратегия ИИ; выбирается в главном меню перед матчем
+#[derive(Resource, Component, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum AIStrategy {
     Rusher,     // Быстрые атаки пехотой и танками
     Defender,   // Строительство укреплений и башен
@@ -40,17 +57,109 @@ pub struct AIWeights {
 
 impl Default for AIBehavior {
     fn default() -> Self {
+        Self::new(AIDifficulty::Medium, AIStrategy::Balanced)
+    }
+}
+
+impl AIBehavior {
+    pub fn new(difficulty: AIDifficulty, strategy: AIStrategy) -> Self {
         Self {
-            difficulty: AIDifficulty::Medium,
-            strategy: AIStrategy::Balanced,
-            weights: AIWeights {
-                aggression: 0.5,
-                economy: 0.4,
-                defense: 0.3,
-            },
-            last_decision_time: 0.0,
+            difficulty,
+            strategy,
+            weights: strategy.get_weights(),
+        }
+    }
+}
+
+impl AIDifficulty {
+    /// Множитель дохода и стартовых ресурсов
+    pub fn resource_multiplier(self) -> f32 {
+        match self {
+            AIDifficulty::Easy => 0.75,
+            AIDifficulty::Medium => 1.0,
+            AIDifficulty::Hard => 1.3,
+        }
+    }
+
+    /// Сколько юнитов каждого класса ИИ держит одновременно
+    pub fn unit_cap(self) -> usize {
+        match self {
+            AIDifficulty::Easy => 2,
+            AIDifficulty::Medium => 3,
+            AIDifficulty::Hard => 5,
+        }
+    }
+
+    /// Сколько инженеров ИИ держит, пока у него есть стройки
+    pub fn engineer_cap(self) -> usize {
+        match self {
+            AIDifficulty::Easy => 1,
+            AIDifficulty::Medium => 2,
+            AIDifficulty::Hard => 3,
+        }
+    }
+
+    pub fn farm_cap(self) -> usize {
+        match self {
+            AIDifficulty::Easy => 1,
+            AIDifficulty::Medium => 2,
+            AIDifficulty::Hard => 3,
+        }
+    }
+
+    /// Лимит шахт, сталелитейных и нефтехимических заводов
+    pub fn building_cap(self) -> usize {
+        match self {
+            AIDifficulty::Easy | AIDifficulty::Medium => 1,
+            AIDifficulty::Hard => 2,
+        }
+    }
+
+    /// Лимит сложности для конкретного предмета
+    pub fn cap(self, item: PurchasableItem) -> usize {
+        match item {
+            PurchasableItem::Tank | PurchasableItem::Infantry | PurchasableItem::Airplane => self.unit_cap(),
+            PurchasableItem::Farm => self.farm_cap(),
+            PurchasableItem::Mine | PurchasableItem::SteelFactory | PurchasableItem::PetrochemicalPlant => self.building_cap(),
+        }
+    }
+
+    /// Вероятность попадания выстрела
+    pub fn hit_chance(self) -> f32 {
+        match self {
+            AIDifficulty::Easy => 0.6,
+            AIDifficulty::Medium => 0.8,
+            AIDifficulty::Hard => 0.95,
         }
     }
+
+    /// На средней и высокой сложности ИИ выбирает цели только среди тех, о ком знает сквозь туман войны
+    pub fn respects_fog_of_war(self) -> bool {
+        self != AIDifficulty::Easy
+    }
+
+    /// Разбор имени сложности из командной строки (без учета регистра)
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "easy" => Some(AIDifficulty::Easy),
+            "medium" => Some(AIDifficulty::Medium),
+            "hard" => Some(AIDifficulty::Hard),
+            _ => None,
+        }
+    }
+}
+
+impl AIWeights {
+    /// Как далеко юниты уходят за целью: агрессивный ИИ идет через всю карту,
+    /// оборонительный встречает противника у своих позиций
+    pub fn engage_range(&self) -> f32 {
+        20.0 + self.aggression * 100.0
+    }
+
+    /// Доля новых юнитов, которые остаются охранять базу
+    pub fn home_guard_share(&self) -> f32 {
+        self.defense * AI_HOME_GUARD_SHARE
+    }
 }
 
 impl AIStrategy {
@@ -78,548 +187,826 @@ impl AIStrategy {
             },
         }
     }
+
+    /// Разбор имени стратегии из командной строки (без учета регистра)
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "rusher" => Some(AIStrategy::Rusher),
+            "defender" => Some(AIStrategy::Defender),
+            "economic" => Some(AIStrategy::Economic),
+            "balanced" => Some(AIStrategy::Balanced),
+            _ => None,
+        }
+    }
 }
 
-/// Упрощенная система покупок ИИ - только базовый функционал
-pub fn ai_purchase_system(
-    mut commands: Commands,
-    asset_server: Res<AssetServer>,
-    turn_state: Res<TurnState>,
-    mut ai_money: ResMut<AIMoney>,
-    mut ai_wood: ResMut<AIWood>,
-    mut ai_iron: ResMut<AIIron>,
-    mut ai_steel: ResMut<AISteel>,
-    mut ai_oil: ResMut<AIOil>,
-    time: Res<Time>,
-    ai_faction: Res<crate::game::units::AIFaction>,
-    // Объединенный запрос для всех AI юнитов
-    ai_units: Query<(
-        Option<&crate::game::Tank>,
-        Option<&crate::game::ShapeType>,
-        Option<&crate::game::Aircraft>,
-        Option<&crate::game::ForestFarm>,
-        Option<&crate::game::Mine>,
-        Option<&crate::game::SteelFactory>,
-        Option<&crate::game::PetrochemicalPlant>,
-    ), With<Enemy>>,
-) {
-    // ИИ покупает только в ход ИИ
-    if turn_state.current_player != PlayerTurn::AI {
-        return;
+/// Сторона, за которую играет ИИ. В обычной игре ИИ управляет только `Enemy`,
+/// в headless-режиме ИИ играет за обе стороны.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AISide {
+    Player,
+    Enemy,
+}
+
+impl AISide {
+    /// Сторона сущности по маркеру `Enemy`
+    pub fn of(is_enemy: bool) -> Self {
+        if is_enemy { AISide::Enemy } else { AISide::Player }
     }
 
-    // Простой таймер - раз в 3 секунды
-    static mut LAST_PURCHASE_TIME: f32 = 0.0;
-    let current_time = time.elapsed_seconds();
-    
-    unsafe {
-        if current_time - LAST_PURCHASE_TIME < 3.0 {
-            return;
-        }
-        LAST_PURCHASE_TIME = current_time;
-    }
-    
-    // Подсчет юнитов с лимитами используя объединенный Query
-    let mut ai_tank_count = 0;
-    let mut ai_infantry_count = 0;
-    let mut ai_aircraft_count = 0;
-    let mut ai_farm_count = 0;
-    let mut ai_mine_count = 0;
-    let mut ai_steel_factory_count = 0;
-    let mut ai_petrochemical_plant_count = 0;
-    
-    for (tank, infantry, aircraft, farm, mine, steel_factory, petrochemical_plant) in ai_units.iter() {
-        if tank.is_some() { ai_tank_count += 1; }
-        if infantry.is_some() { ai_infantry_count += 1; }
-        if aircraft.is_some() { ai_aircraft_count += 1; }
-        if farm.is_some() { ai_farm_count += 1; }
-        if mine.is_some() { ai_mine_count += 1; }
-        if steel_factory.is_some() { ai_steel_factory_count += 1; }
-        if petrochemical_plant.is_some() { ai_petrochemical_plant_count += 1; }
-    }
-    
-    // Проверяем лимиты для каждого типа юнитов
-    let tank_limit_reached = ai_tank_count >= 3;
-    let infantry_limit_reached = ai_infantry_count >= 3;
-    let aircraft_limit_reached = ai_aircraft_count >= 3;
-    
-    let farm_limit_reached = ai_farm_count >= 2; // Разрешаем ИИ строить до 2 ферм
-    let mine_limit_reached = ai_mine_count >= 1;
-    let steel_factory_limit_reached = ai_steel_factory_count >= 1;
-    let petrochemical_plant_limit_reached = ai_petrochemical_plant_count >= 1;
-
-    // Определяем приоритеты покупок с учетом лимитов
-    let mut purchase_priorities = vec![];
-    
-    // ЗДАНИЯ ИМЕЮТ ВЫСШИЙ ПРИОРИТЕТ (только если не достигнут лимит и можем позволить)
-    if !farm_limit_reached && can_afford_item_ai(PurchasableItem::Farm, &ai_money, &ai_wood, &ai_iron, &ai_steel, &ai_oil) {
-        purchase_priorities.push((PurchasableItem::Farm, 11.0)); // Фермы имеют высший приоритет для экономики
-    }
-    if !mine_limit_reached && can_afford_item_ai(PurchasableItem::Mine, &ai_money, &ai_wood, &ai_iron, &ai_steel, &ai_oil) {
-        purchase_priorities.push((PurchasableItem::Mine, 10.0));
-    }
-    if !steel_factory_limit_reached && can_afford_item_ai(PurchasableItem::SteelFactory, &ai_money, &ai_wood, &ai_iron, &ai_steel, &ai_oil) {
-        purchase_priorities.push((PurchasableItem::SteelFactory, 9.0));
-    }
-    if !petrochemical_plant_limit_reached && can_afford_item_ai(PurchasableItem::PetrochemicalPlant, &ai_money, &ai_wood, &ai_iron, &ai_steel, &ai_oil) {
-        purchase_priorities.push((PurchasableItem::PetrochemicalPlant, 8.0));
-    }
-    
-    // Добавляем юниты только если не достигнут лимит и можем позволить
-    if !infantry_limit_reached && can_afford_item_ai(PurchasableItem::Infantry, &ai_money, &ai_wood, &ai_iron, &ai_steel, &ai_oil) {
-        purchase_priorities.push((PurchasableItem::Infantry, 3.0));
-    }
-    if !tank_limit_reached && can_afford_item_ai(PurchasableItem::Tank, &ai_money, &ai_wood, &ai_iron, &ai_steel, &ai_oil) {
-        purchase_priorities.push((PurchasableItem::Tank, 2.0));
-    }
-    if !aircraft_limit_reached && can_afford_item_ai(PurchasableItem::Airplane, &ai_money, &ai_wood, &ai_iron, &ai_steel, &ai_oil) {
-        purchase_priorities.push((PurchasableItem::Airplane, 1.0));
-    }
-
-    // Сортируем по приоритету
-    purchase_priorities.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
-
-    // Пытаемся купить самый приоритетный доступный предмет
-    for (item, priority) in purchase_priorities.iter() {
-        if priority < &0.3 {
-            break; // Слишком низкий приоритет
-        }
-
-        if can_afford_item_ai(*item, &ai_money, &ai_wood, &ai_iron, &ai_steel, &ai_oil) {
-            // Списываем все ресурсы
-            deduct_resources_ai(*item, &mut ai_money, &mut ai_wood, &mut ai_iron, &mut ai_steel, &mut ai_oil);
-            
-            // Создаем юнит
-            simple_spawn_ai_unit(*item, &mut commands, &asset_server, &time, &ai_faction);
-            
-            info!("AI purchased {:?} with priority {:.2}. Counts: Infantry {}/3, Tanks {}/3, Aircraft {}/3, Farms {}/2, Mines {}/1, Factories {}/1, Plants {}/1", 
-                  item, priority, 
-                  ai_infantry_count + if *item == PurchasableItem::Infantry { 1 } else { 0 },
-                  ai_tank_count + if *item == PurchasableItem::Tank { 1 } else { 0 },
-                  ai_aircraft_count + if *item == PurchasableItem::Airplane { 1 } else { 0 },
-                  ai_farm_count + if *item == PurchasableItem::Farm { 1 } else { 0 },
-                  ai_mine_count + if *item == PurchasableItem::Mine { 1 } else { 0 },
-                  ai_steel_factory_count + if *item == PurchasableItem::SteelFactory { 1 } else { 0 },
-                  ai_petrochemical_plant_count + if *item == PurchasableItem::PetrochemicalPlant { 1 } else { 0 });
-            break; // Покупаем только один предмет за раз
+    /// Сторона противника
+    pub fn opponent(self) -> Self {
+        match self {
+            AISide::Player => AISide::Enemy,
+            AISide::Enemy => AISide::Player,
+        }
+    }
+
+    /// Чей ход соответствует этой стороне
+    pub fn turn(self) -> PlayerTurn {
+        match self {
+            AISide::Player => PlayerTurn::Human,
+            AISide::Enemy => PlayerTurn::AI,
+        }
+    }
+
+    /// Принадлежит ли сущность этой стороне (`is_enemy` - есть Enemy или EnemyTower)
+    pub fn owns(self, is_enemy: bool) -> bool {
+        match self {
+            AISide::Player => !is_enemy,
+            AISide::Enemy => is_enemy,
+        }
+    }
+
+    pub fn faction(self, player_faction: &PlayerFaction, ai_faction: &AIFaction) -> Faction {
+        match self {
+            AISide::Player => player_faction.0,
+            AISide::Enemy => ai_faction.get_opposite(),
         }
     }
 
+    /// Позиции ИИ заданы для правой стороны карты, для игрока они отражаются
+    pub fn mirror(self, position: Vec3) -> Vec3 {
+        match self {
+            AISide::Player => Vec3::new(-position.x, position.y, -position.z),
+            AISide::Enemy => position,
+        }
+    }
 }
 
-/// Простая функция создания юнитов ИИ
-fn simple_spawn_ai_unit(
-    item: PurchasableItem,
-    commands: &mut Commands,
-    asset_server: &AssetServer,
-    time: &Res<Time>,
-    ai_faction: &Res<crate::game::units::AIFaction>,
+/// One AI-controlled side and its behaviour settings
+#[derive(Debug)]
+pub struct AIPlayer {
+    pub side: AISide,
+    pub behavior: AIBehavior,
+}
+
+impl AIPlayer {
+    pub fn new(side: AISide, behavior: AIBehavior) -> Self {
+        Self { side, behavior }
+    }
+}
+
+/// Все стороны под управлением ИИ
+#[derive(Resource, Debug)]
+pub struct AIPlayers(pub Vec<AIPlayer>);
+
+impl AIPlayers {
+    pub fn get(&self, side: AISide) -> Option<&AIPlayer> {
+        self.0.iter().find(|player| player.side == side)
+    }
+
+    /// Юниты стороны ИИ двигаются только в ее ход; юниты живого игрока - всегда
+    pub fn may_move(&self, is_enemy: bool, turn_state: &TurnState) -> bool {
+        let side = AISide::of(is_enemy);
+        self.get(side).is_none() || turn_state.current_player == side.turn()
+    }
+}
+
+impl Default for AIPlayers {
+    fn default() -> Self {
+        Self(vec![AIPlayer::new(AISide::Enemy, AIBehavior::default())])
+    }
+}
+
+/// Переносит выбранные в меню сложность и стратегию на ИИ противника
+pub fn apply_ai_settings(
+    difficulty: Res<AIDifficulty>,
+    strategy: Res<AIStrategy>,
+    mut ai_players: ResMut<AIPlayers>,
 ) {
-    // Определяем позицию для спавна (правая сторона карты для ИИ)
-    let seed = time.elapsed_seconds_f64().fract() as f32;
-    let x = 15.0 + (seed * 50.0).sin() * 5.0;
-    let z = (seed * 75.0).cos() * 8.0;
-    let spawn_pos = Vec3::new(x, 0.0, z);
+    for ai_player in ai_players.0.iter_mut().filter(|player| player.side == AISide::Enemy) {
+        ai_player.behavior = AIBehavior::new(*difficulty, *strategy);
+        info!("🤖 AI opponent: {:?} difficulty, {:?} strategy", difficulty, strategy);
+    }
+}
 
+/// Sent every time an AI side buys something
+#[derive(Event, Debug, Clone, Copy)]
+pub struct AIPurchaseEvent {
+    pub side: AISide,
+    pub item: PurchasableItem,
+}
+
+/// Mutable view of one side's five resources
+pub struct Wallet<'a> {
+    pub money: &'a mut f32,
+    pub wood: &'a mut f32,
+    pub iron: &'a mut f32,
+    pub steel: &'a mut f32,
+    pub oil: &'a mut f32,
+}
+
+impl Wallet<'_> {
+    pub fn deduct(&mut self, cost: &ResourceCost) {
+        *self.money -= cost.money;
+        *self.wood -= cost.wood;
+        *self.iron -= cost.iron;
+        *self.steel -= cost.steel;
+        *self.oil -= cost.oil;
+    }
+
+    /// Текущие запасы в виде `ResourceCost`
+    pub fn amounts(&self) -> ResourceCost {
+        ResourceCost {
+            money: *self.money,
+            wood: *self.wood,
+            iron: *self.iron,
+            steel: *self.steel,
+            oil: *self.oil,
+        }
+    }
+}
+
+/// Resources of both sides, so AI systems can work for either of them
+#[derive(SystemParam)]
+pub struct SideResources<'w> {
+    money: ResMut<'w, Money>,
+    wood: ResMut<'w, Wood>,
+    iron: ResMut<'w, Iron>,
+    steel: ResMut<'w, Steel>,
+    oil: ResMut<'w, Oil>,
+    ai_money: ResMut<'w, AIMoney>,
+    ai_wood: ResMut<'w, AIWood>,
+    ai_iron: ResMut<'w, AIIron>,
+    ai_steel: ResMut<'w, AISteel>,
+    ai_oil: ResMut<'w, AIOil>,
+}
+
+impl SideResources<'_> {
+    pub fn wallet(&mut self, side: AISide) -> Wallet<'_> {
+        match side {
+            AISide::Player => Wallet {
+                money: &mut self.money.0,
+                wood: &mut self.wood.0,
+                iron: &mut self.iron.0,
+                steel: &mut self.steel.0,
+                oil: &mut self.oil.0,
+            },
+            AISide::Enemy => Wallet {
+                money: &mut self.ai_money.0,
+                wood: &mut self.ai_wood.0,
+                iron: &mut self.ai_iron.0,
+                steel: &mut self.ai_steel.0,
+                oil: &mut self.ai_oil.0,
+            },
+        }
+    }
+}
+
+/// Базовый приоритет покупки с учетом весов стратегии; те же пропорции, что и
+/// у старого списка приоритетов (фермы 11, шахты 10, ... авиация 1), но в диапазоне 0..1
+fn purchase_score(item: PurchasableItem, weights: &AIWeights) -> f32 {
     match item {
-        PurchasableItem::Tank => {
-            let faction = ai_faction.get_opposite();
-            use rand::Rng;
-            let mut rng = rand::thread_rng();
-            let tank_type_index = rng.gen_range(0..3);
-            
-            let (model_path, scale) = match faction {
-                Faction::Entente => {
-                    match tank_type_index {
-                        0 => ("models/entente/tanks/tsar_tank.glb#Scene0", 0.1), // tsar_tank возвращаем исходный размер
-                        1 => ("models/entente/tanks/mark1.glb#Scene0", 0.08), // mark1 возвращаем исходный размер
-                        _ => ("models/entente/tanks/renault_ft17.glb#Scene0", 0.4), // renault остается нормальным
-                    }
-                },
-                Faction::CentralPowers => {
-                    match tank_type_index {
-                        0 => ("models/central_powers/tanks/panzerwagen.glb#Scene0", 0.08), // panzerwagen уменьшен в 5 раз
-                        1 => ("models/central_powers/tanks/a7v.glb#Scene0", 0.08), // a7v уменьшен в 5 раз
-                        _ => ("models/central_powers/tanks/steam_wheel_tank.glb#Scene0", 0.08), // steam_wheel остается как был
-                    }
-                },
-            };
-            let tank_entity = commands.spawn((
-                SceneBundle {
-                    scene: asset_server.load(model_path),
-                    transform: Transform::from_translation(spawn_pos)
-                        .with_scale(Vec3::splat(scale)),
-                    ..default()
-                },
-                ShapeType::Cube,
-                Enemy,
-                Tank,
-                Selectable,
-                Health { current: 100.0, max: 100.0 },
-                CanShoot {
-                    cooldown: 1.2,
-                    last_shot: time.elapsed_seconds(),
-                    range: 10.0,
-                    damage: 12.0,
-                },
-                RigidBody::Dynamic,
-                Collider::cuboid(5.0, 5.0, 5.0), // Очень большой коллайдер для AI танков
-                Sensor, // Невидимый коллайдер для кликов
-                LockedAxes::ROTATION_LOCKED | LockedAxes::TRANSLATION_LOCKED_Y,
-                Restitution::coefficient(0.0),
-                Friction::coefficient(0.8),
-                PickableBundle::default(),
-                Name::new("AI Tank"),
-            )).id();
-
-            // Добавляем видимый клик-коллайдер для отладки - пока отложим
-        }
-        PurchasableItem::Infantry => {
-            let faction = ai_faction.get_opposite();
-            use rand::Rng;
-            let mut rng = rand::thread_rng();
-            let infantry_type_index = rng.gen_range(0..3);
-            
-            let model_path = match faction {
-                Faction::Entente => {
-                    match infantry_type_index {
-                        0 => "models/infantry/russian_soldier.glb#Scene0",
-                        1 => "models/infantry/british_soldier.glb#Scene0",
-                        _ => "models/infantry/french_soldier.glb#Scene0",
-                    }
-                },
-                Faction::CentralPowers => {
-                    match infantry_type_index {
-                        0 => "models/infantry/german_soldier.glb#Scene0",
-                        1 => "models/infantry/turkish_soldier.glb#Scene0",
-                        _ => "models/infantry/austrian_soldier.glb#Scene0",
-                    }
-                },
-            };
-            
-            commands.spawn((
-                SceneBundle {
-                    scene: asset_server.load(model_path),
-                    transform: Transform::from_translation(spawn_pos)
-                        .with_scale(Vec3::splat(1.0)),
-                    ..default()
-                },
-                ShapeType::Infantry,
-                Enemy,
-                Selectable,
-                Health { current: 60.0, max: 60.0 },
-                CanShoot {
-                    cooldown: 0.9,
-                    last_shot: time.elapsed_seconds(),
-                    range: 12.0,
-                    damage: 8.0,
-                },
-                RigidBody::Dynamic,
-                Collider::ball(3.0), // Очень большой коллайдер для AI пехоты
-                Sensor, // Невидимый коллайдер для кликов
-                LockedAxes::ROTATION_LOCKED | LockedAxes::TRANSLATION_LOCKED_Y,
-                Restitution::coefficient(0.0),
-                Friction::coefficient(0.8),
-                bevy_mod_picking::prelude::PickableBundle::default(),
-                Name::new("AI Infantry"),
-            ));
+        PurchasableItem::Farm => 1.0 * weights.economy,
+        PurchasableItem::Mine => 0.91 * weights.economy,
+        PurchasableItem::SteelFactory => 0.82 * weights.economy,
+        PurchasableItem::PetrochemicalPlant => 0.73 * weights.economy,
+        // Пехота нужна и для атаки, и для обороны
+        PurchasableItem::Infantry => 0.27 * weights.aggression.max(weights.defense),
+        PurchasableItem::Tank => 0.18 * weights.aggression,
+        PurchasableItem::Airplane => 0.09 * weights.aggression,
+    }
+}
+
+const ECONOMY_ITEMS: [PurchasableItem; 4] = [
+    PurchasableItem::Farm,
+    PurchasableItem::Mine,
+    PurchasableItem::SteelFactory,
+    PurchasableItem::PetrochemicalPlant,
+];
+
+/// Радиус вокруг башни, в котором вражеский юнит считается угрозой
+const TOWER_THREAT_RADIUS: f32 = 15.0;
+/// Ниже этого веса обороны ИИ не тратит ресурсы на ремонт башен
+const AI_REPAIR_MIN_DEFENSE: f32 = 0.5;
+/// Сколько прочности ИИ возвращает башне за одно решение
+const AI_REPAIR_STEP: f32 = 50.0;
+/// Доля купленных юнитов, оставляемых охранять базу, при весе обороны 1.0
+const AI_HOME_GUARD_SHARE: f32 = 0.5;
+/// Охрана базы атакует только врагов в этом радиусе от своего поста
+const AI_HOME_GUARD_RADIUS: f32 = 30.0;
+/// Оценка покупки инженера: выше любого здания, иначе купленные стройки так и стоят
+const AI_ENGINEER_SCORE: f32 = 0.9;
+
+/// Актор мыслителя, который решает, что покупать одной стороне
+#[derive(Component, Debug)]
+pub struct AICommander {
+    pub side: AISide,
+}
+
+/// AI unit kept back to defend its base: it never chases targets far from its post
+#[derive(Component, Debug, Clone, Copy)]
+pub struct HomeGuard {
+    pub post: Vec3,
+}
+
+/// What a commander knows about its side; refreshed every frame before the scorers run
+#[derive(Component, Debug, Default, Clone)]
+pub struct SideSurvey {
+    pub tanks: usize,
+    pub infantry: usize,
+    pub aircraft: usize,
+    pub farms: usize,
+    pub mines: usize,
+    pub steel_factories: usize,
+    pub petrochemical_plants: usize,
+    pub engineers: usize,
+    /// Недостроенные здания; они уже учтены в счетчиках зданий выше
+    pub construction_sites: usize,
+    /// Сколько своих башен атакуют вражеские юниты
+    pub threatened_towers: usize,
+    /// Сколько своих башен повреждено и еще может получить ремонт в этот ход
+    pub damaged_towers: usize,
+    pub stock: ResourceCost,
+}
+
+impl SideSurvey {
+    pub fn count(&self, item: PurchasableItem) -> usize {
+        match item {
+            PurchasableItem::Tank => self.tanks,
+            PurchasableItem::Infantry => self.infantry,
+            PurchasableItem::Airplane => self.aircraft,
+            PurchasableItem::Farm => self.farms,
+            PurchasableItem::Mine => self.mines,
+            PurchasableItem::SteelFactory => self.steel_factories,
+            PurchasableItem::PetrochemicalPlant => self.petrochemical_plants,
         }
-        PurchasableItem::Airplane => {
-            let faction = ai_faction.get_opposite();
-            use rand::Rng;
-            let mut rng = rand::thread_rng();
-            let aircraft_type_index = rng.gen_range(0..3);
-            
-            let model_path = match faction {
-                Faction::Entente => {
-                    match aircraft_type_index {
-                        0 => "models/entente/airplanes/sopwith_camel.glb#Scene0",
-                        1 => "models/entente/airplanes/breguet_14.glb#Scene0",
-                        _ => "models/entente/airplanes/ilya_muromets.glb#Scene0",
-                    }
-                },
-                Faction::CentralPowers => {
-                    match aircraft_type_index {
-                        0 => "models/central_powers/airplanes/fokker.glb#Scene0",
-                        1 => "models/central_powers/airplanes/albatros.glb#Scene0",
-                        _ => "models/central_powers/airplanes/red_baron.glb#Scene0",
-                    }
-                },
-            };
-            commands.spawn((
-                SceneBundle {
-                    scene: asset_server.load(model_path),
-                    transform: Transform::from_translation(spawn_pos + Vec3::new(0.0, 10.0, 0.0))
-                        .with_scale(Vec3::splat(0.6)),
-                    ..default()
-                },
-                ShapeType::Airplane,
-                Enemy,
-                crate::game::Aircraft { height: 10.0, speed: 5.0 },
-                MovementOrder(Vec3::ZERO),
-                Health { current: 75.0, max: 75.0 },
-                CanShoot {
-                    cooldown: 0.6,
-                    last_shot: time.elapsed_seconds(),
-                    range: 20.0,
-                    damage: 15.0,
-                },
-                RigidBody::Fixed,
-                Collider::cuboid(7.0, 4.0, 8.0), // Очень большой коллайдер для AI самолетов
-                Sensor, // Невидимый коллайдер для кликов
-                LockedAxes::all(),
-                PickableBundle::default(),
-                Name::new("AI Aircraft"),
-            ));
+    }
+}
+
+/// Unit catalog and prices the commander checks its purchases against
+#[derive(SystemParam)]
+pub struct AIShop<'w> {
+    catalog: Res<'w, UnitCatalog>,
+    costs: Res<'w, CostTable>,
+    catalog_loaded: Option<Res<'w, BalanceLoaded<UnitCatalog>>>,
+    costs_loaded: Option<Res<'w, BalanceLoaded<CostTable>>>,
+    player_faction: Res<'w, PlayerFaction>,
+    ai_faction: Res<'w, AIFaction>,
+}
+
+impl AIShop<'_> {
+    /// Пока файлы баланса не загружены, все цены нулевые - покупать нельзя
+    pub fn is_open(&self) -> bool {
+        self.catalog_loaded.is_some() && self.costs_loaded.is_some()
+    }
+
+    pub fn faction(&self, side: AISide) -> Faction {
+        side.faction(&self.player_faction, &self.ai_faction)
+    }
+
+    /// Модели класса, которые сторона может оплатить из `stock`: боевые или только инженеры
+    pub fn affordable_variants(&self, side: AISide, class: UnitClass, stock: &ResourceCost, engineer: bool) -> Vec<usize> {
+        self.catalog.units(self.faction(side), class)
+            .iter()
+            .enumerate()
+            .filter(|(_, definition)| definition.engineer == engineer && stock.covers(&definition.cost))
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    /// Цена предмета; для юнитов - цена конкретной модели из каталога
+    pub fn cost(&self, side: AISide, item: PurchasableItem, unit_index: Option<usize>) -> Option<ResourceCost> {
+        match unit_class_for_item(item) {
+            Some(class) => self.catalog.get(self.faction(side), class, unit_index?).map(|definition| definition.cost),
+            None => Some(self.costs.get(item)),
         }
-        PurchasableItem::Farm => {
-            commands.spawn((
-                SceneBundle {
-                    scene: asset_server.load("models/farm/forest.glb#Scene0"),
-                    transform: Transform::from_translation(spawn_pos)
-                        .with_scale(Vec3::splat(0.3)),
-                    ..default()
-                },
-                crate::game::ForestFarm,
-                crate::game::FarmIncomeRate(2.0), // Доход от фермы
-                crate::game::FarmActive(true),
-                crate::game::Enemy,
-                Health { current: 80.0, max: 80.0 },
-                RigidBody::Fixed,
-                LockedAxes::all(),
-                Collider::cuboid(1.5, 1.0, 1.5),
-                PickableBundle::default(),
-                Name::new("AI Farm"),
-            ));
+    }
+
+    /// Можно ли купить предмет: лимит сложности не достигнут и хватает ресурсов
+    pub fn can_buy(&self, side: AISide, item: PurchasableItem, survey: &SideSurvey, difficulty: AIDifficulty, stock: &ResourceCost) -> bool {
+        if survey.count(item) >= difficulty.cap(item) {
+            return false;
         }
-        PurchasableItem::Mine => {
+        match unit_class_for_item(item) {
+            Some(class) => !self.affordable_variants(side, class, stock, false).is_empty(),
+            None => stock.covers(&self.costs.get(item)),
+        }
+    }
+
+    /// Инженер нужен, только пока есть стройки, и не больше лимита сложности
+    pub fn can_buy_engineer(&self, side: AISide, survey: &SideSurvey, difficulty: AIDifficulty, stock: &ResourceCost) -> bool {
+        survey.construction_sites > 0
+            && survey.engineers < difficulty.engineer_cap()
+            && !self.affordable_variants(side, UnitClass::Infantry, stock, true).is_empty()
+    }
+}
+
+/// Экономика слабая: есть здание, которое еще можно построить
+#[derive(Clone, Component, Debug, ScorerBuilder)]
+pub struct EconomyWeak;
+
+/// Вражеские юниты подошли к своим башням
+#[derive(Clone, Component, Debug, ScorerBuilder)]
+pub struct TowerUnderThreat;
+
+/// Своя башня повреждена, а оборона для стратегии важна
+#[derive(Clone, Component, Debug, ScorerBuilder)]
+pub struct TowerDamaged;
+
+/// Есть стройки, а инженеров на них не хватает
+#[derive(Clone, Component, Debug, ScorerBuilder)]
+pub struct NeedsEngineer;
+
+/// Хватает ресурсов на юнит и его лимит не достигнут
+#[derive(Clone, Component, Debug, ScorerBuilder)]
+pub struct CanAfford {
+    pub item: PurchasableItem,
+}
+
+/// Покупает самое нужное экономическое здание
+#[derive(Clone, Component, Debug, ActionBuilder)]
+pub struct BuyEconomy;
+
+/// Покупает юнит выбранного типа
+#[derive(Clone, Component, Debug, ActionBuilder)]
+pub struct BuyUnit {
+    pub item: PurchasableItem,
+}
+
+/// Покупает инженера, чтобы достроить свои здания
+#[derive(Clone, Component, Debug, ActionBuilder)]
+pub struct BuyEngineer;
+
+/// Чинит самую поврежденную свою башню
+#[derive(Clone, Component, Debug, ActionBuilder)]
+pub struct RepairTowers;
+
+type PurchaseActionFilter = Or<(With<BuyUnit>, With<BuyEconomy>, With<BuyEngineer>)>;
+type PurchaseAction<'a> = (&'a Actor, &'a mut ActionState, Option<&'a BuyUnit>, Has<BuyEngineer>);
+
+/// Что из покупаемого есть у сущности и чья она
+type OwnedAsset = (
+    Has<Tank>,
+    Has<crate::game::units::infantry::Infantry>,
+    Has<crate::game::Aircraft>,
+    Has<crate::game::ForestFarm>,
+    Has<crate::game::Mine>,
+    Has<crate::game::SteelFactory>,
+    Has<crate::game::PetrochemicalPlant>,
+    Has<Engineer>,
+    Option<&'static ConstructionSite>,
+    Has<Enemy>,
+);
+
+/// Мыслитель командира: побеждает предмет с наибольшей оценкой
+fn commander_thinker() -> ThinkerBuilder {
+    Thinker::build()
+        .label("AI Commander")
+        .picker(Highest)
+        .when(EconomyWeak, BuyEconomy)
+        .when(NeedsEngineer, BuyEngineer)
+        .when(TowerUnderThreat, BuyUnit { item: PurchasableItem::Infantry })
+        .when(TowerDamaged, RepairTowers)
+        .when(CanAfford { item: PurchasableItem::Infantry }, BuyUnit { item: PurchasableItem::Infantry })
+        .when(CanAfford { item: PurchasableItem::Tank }, BuyUnit { item: PurchasableItem::Tank })
+        .when(CanAfford { item: PurchasableItem::Airplane }, BuyUnit { item: PurchasableItem::Airplane })
+}
+
+/// Keeps exactly one commander entity per AI-controlled side
+pub fn sync_ai_commanders(
+    mut commands: Commands,
+    ai_players: Res<AIPlayers>,
+    commanders: Query<(Entity, &AICommander)>,
+) {
+    for (entity, commander) in commanders.iter() {
+        if ai_players.get(commander.side).is_none() {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+    for ai_player in ai_players.0.iter() {
+        if !commanders.iter().any(|(_, commander)| commander.side == ai_player.side) {
             commands.spawn((
-                SceneBundle {
-                    scene: asset_server.load("models/farm/mine.glb#Scene0"),
-                    transform: Transform::from_translation(spawn_pos)
-                        .with_scale(Vec3::splat(0.3)),
-                    ..default()
-                },
-                crate::game::Mine,
-                crate::game::MineIronRate(2.0),
-                crate::game::FarmActive(true),
-                crate::game::Enemy,
-                Health { current: 100.0, max: 100.0 },
-                RigidBody::Fixed,
-        LockedAxes::all(),
-                Collider::cuboid(1.0, 0.5, 1.0),
-                PickableBundle::default(),
-                Name::new("AI Mine - FIXED"),
+                AICommander { side: ai_player.side },
+                SideSurvey::default(),
+                commander_thinker(),
+                Name::new(format!("AI Commander ({:?})", ai_player.side)),
             ));
+            info!("🧠 AI commander spawned for {:?}", ai_player.side);
+        }
+    }
+}
+
+/// Своя живая поврежденная башня, которой еще можно вернуть прочность в этот ход
+fn needs_repair(side: AISide, health: &Health, repair: &TowerRepair, is_enemy_tower: bool, turn_state: &TurnState) -> bool {
+    side.owns(is_enemy_tower)
+        && health.current > 0.0
+        && health.current < health.max
+        && repair.remaining(turn_state) > 0.0
+}
+
+/// Подсчитывает юниты, здания, запасы и угрозы башням для каждого командира
+pub fn survey_ai_sides(
+    mut resources: SideResources,
+    turn_state: Res<TurnState>,
+    mut commanders: Query<(&AICommander, &mut SideSurvey)>,
+    owned: Query<OwnedAsset>,
+    towers: Query<(&Transform, &Health, Has<EnemyTower>), With<crate::game::Tower>>,
+    repairs: Query<(&Health, &TowerRepair, Has<EnemyTower>), With<crate::game::Tower>>,
+    attackers: Query<(&Transform, Has<Enemy>), AttackerFilter>,
+) {
+    for (commander, mut survey) in commanders.iter_mut() {
+        let side = commander.side;
+        let mut next = SideSurvey {
+            stock: resources.wallet(side).amounts(),
+            ..default()
+        };
+
+        for (tank, infantry, aircraft, farm, mine, steel_factory, petrochemical_plant, engineer, site, is_enemy) in owned.iter() {
+            if !side.owns(is_enemy) {
+                continue;
+            }
+            // Стройка занимает место будущего здания в лимите
+            let building = site.map(|site| site.building);
+            if tank { next.tanks += 1; }
+            if engineer { next.engineers += 1; } else if infantry { next.infantry += 1; }
+            if aircraft { next.aircraft += 1; }
+            if farm || building == Some(ShapeType::Farm) { next.farms += 1; }
+            if mine || building == Some(ShapeType::Mine) { next.mines += 1; }
+            if steel_factory || building == Some(ShapeType::SteelFactory) { next.steel_factories += 1; }
+            if petrochemical_plant || building == Some(ShapeType::PetrochemicalPlant) { next.petrochemical_plants += 1; }
+            if site.is_some() { next.construction_sites += 1; }
+        }
+
+        next.threatened_towers = towers.iter()
+            .filter(|(_, health, is_enemy_tower)| side.owns(*is_enemy_tower) && health.current > 0.0)
+            .filter(|(tower, _, _)| {
+                attackers.iter().any(|(attacker, is_enemy)| {
+                    !side.owns(is_enemy) && attacker.translation.distance(tower.translation) <= TOWER_THREAT_RADIUS
+                })
+            })
+            .count();
+        // Башни, исчерпавшие лимит ремонта за ход, не считаются: чинить их все равно нельзя
+        next.damaged_towers = repairs.iter()
+            .filter(|(health, repair, is_enemy_tower)| needs_repair(side, health, repair, *is_enemy_tower, &turn_state))
+            .count();
+
+        *survey = next;
+    }
+}
+
+pub fn economy_weak_scorer_system(
+    ai_players: Res<AIPlayers>,
+    shop: AIShop,
+    commanders: Query<(&AICommander, &SideSurvey)>,
+    mut scorers: Query<(&Actor, &mut Score), With<EconomyWeak>>,
+) {
+    for (Actor(actor), mut score) in scorers.iter_mut() {
+        let value = commanders.get(*actor).ok()
+            .and_then(|(commander, survey)| {
+                let behavior = &ai_players.get(commander.side)?.behavior;
+                ECONOMY_ITEMS.iter()
+                    .filter(|item| shop.can_buy(commander.side, **item, survey, behavior.difficulty, &survey.stock))
+                    .map(|item| purchase_score(*item, &behavior.weights))
+                    .reduce(f32::max)
+            })
+            .unwrap_or(0.0);
+        score.set(value);
+    }
+}
+
+/// Стройкам без рабочих рук нужен инженер; без него здание никогда не заработает
+pub fn needs_engineer_scorer_system(
+    ai_players: Res<AIPlayers>,
+    shop: AIShop,
+    commanders: Query<(&AICommander, &SideSurvey)>,
+    mut scorers: Query<(&Actor, &mut Score), With<NeedsEngineer>>,
+) {
+    for (Actor(actor), mut score) in scorers.iter_mut() {
+        let value = commanders.get(*actor).ok()
+            .and_then(|(commander, survey)| {
+                let behavior = &ai_players.get(commander.side)?.behavior;
+                shop.can_buy_engineer(commander.side, survey, behavior.difficulty, &survey.stock)
+                    .then_some(AI_ENGINEER_SCORE)
+            })
+            .unwrap_or(0.0);
+        score.set(value);
+    }
+}
+
+pub fn tower_under_threat_scorer_system(
+    ai_players: Res<AIPlayers>,
+    shop: AIShop,
+    commanders: Query<(&AICommander, &SideSurvey)>,
+    mut scorers: Query<(&Actor, &mut Score), With<TowerUnderThreat>>,
+) {
+    for (Actor(actor), mut score) in scorers.iter_mut() {
+        let value = commanders.get(*actor).ok()
+            .and_then(|(commander, survey)| {
+                let behavior = &ai_players.get(commander.side)?.behavior;
+                let can_defend = survey.threatened_towers > 0
+                    && shop.can_buy(commander.side, PurchasableItem::Infantry, survey, behavior.difficulty, &survey.stock);
+                // Оборонительная стратегия реагирует на угрозу сильнее
+                can_defend.then_some(0.5 + 0.5 * behavior.weights.defense)
+            })
+            .unwrap_or(0.0);
+        score.set(value);
+    }
+}
+
+pub fn tower_damaged_scorer_system(
+    ai_players: Res<AIPlayers>,
+    commanders: Query<(&AICommander, &SideSurvey)>,
+    mut scorers: Query<(&Actor, &mut Score), With<TowerDamaged>>,
+) {
+    for (Actor(actor), mut score) in scorers.iter_mut() {
+        let value = commanders.get(*actor).ok()
+            .and_then(|(commander, survey)| {
+                let weights = &ai_players.get(commander.side)?.behavior.weights;
+                let should_repair = survey.damaged_towers > 0
+                    && weights.defense >= AI_REPAIR_MIN_DEFENSE
+                    && survey.stock.covers(&tower_repair_cost(AI_REPAIR_STEP));
+                should_repair.then_some(weights.defense)
+            })
+            .unwrap_or(0.0);
+        score.set(value);
+    }
+}
+
+pub fn can_afford_scorer_system(
+    ai_players: Res<AIPlayers>,
+    shop: AIShop,
+    commanders: Query<(&AICommander, &SideSurvey)>,
+    mut scorers: Query<(&Actor, &mut Score, &CanAfford)>,
+) {
+    for (Actor(actor), mut score, can_afford) in scorers.iter_mut() {
+        let value = commanders.get(*actor).ok()
+            .and_then(|(commander, survey)| {
+                let behavior = &ai_players.get(commander.side)?.behavior;
+                shop.can_buy(commander.side, can_afford.item, survey, behavior.difficulty, &survey.stock)
+                    .then(|| purchase_score(can_afford.item, &behavior.weights))
+            })
+            .unwrap_or(0.0);
+        score.set(value);
+    }
+}
+
+/// Выполняет покупки командиров: только в свой ход и не чаще, чем позволяет расписание
+#[allow(clippy::too_many_arguments)]
+pub fn ai_purchase_action_system(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    turn_state: Res<TurnState>,
+    mut resources: SideResources,
+    time: Res<Time>,
+    shop: AIShop,
+    ai_players: Res<AIPlayers>,
+    mut scheduler: ResMut<AIScheduler>,
+    mut rng: ResMut<GameRng>,
+    mut purchases: EventWriter<AIPurchaseEvent>,
+    commanders: Query<(&AICommander, &SideSurvey)>,
+    mut actions: Query<PurchaseAction, PurchaseActionFilter>,
+) {
+    for (Actor(actor), mut state, buy_unit, buy_engineer) in actions.iter_mut() {
+        match *state {
+            ActionState::Requested => *state = ActionState::Executing,
+            ActionState::Executing => {}
+            ActionState::Cancelled => {
+                *state = ActionState::Failure;
+                continue;
+            }
+            _ => continue,
         }
-        PurchasableItem::SteelFactory => {
-            commands.spawn((
-                SceneBundle {
-                    scene: asset_server.load("models/farm/factory.glb#Scene0"),
-                    transform: Transform::from_translation(spawn_pos)
-                        .with_scale(Vec3::splat(0.25)),
-                    ..default()
-                },
-                crate::game::SteelFactory,
-                crate::game::FarmActive(true),
-                crate::game::Enemy,
-                Health { current: 120.0, max: 120.0 },
-                RigidBody::Fixed,
-        LockedAxes::all(),
-                Collider::cuboid(1.0, 0.5, 1.0),
-                PickableBundle::default(),
-                Name::new("AI Steel Factory - FIXED"),
-            ));
+
+        let Ok((commander, survey)) = commanders.get(*actor) else {
+            continue;
+        };
+        let side = commander.side;
+        let Some(ai_player) = ai_players.get(side) else {
+            continue;
+        };
+        if turn_state.current_player != side.turn() || !shop.is_open() || !scheduler.try_decide(side, AIDecision::Purchase) {
+            continue;
         }
-        PurchasableItem::PetrochemicalPlant => {
-            commands.spawn((
-                SceneBundle {
-                    scene: asset_server.load("models/farm/oil_pump.glb#Scene0"),
-                    transform: Transform::from_translation(spawn_pos)
-                        .with_scale(Vec3::splat(0.3)),
-                    ..default()
-                },
-                crate::game::PetrochemicalPlant,
-                crate::game::FarmActive(true),
-                crate::game::Enemy,
-                Health { current: 110.0, max: 110.0 },
-                RigidBody::Fixed,
-        LockedAxes::all(),
-                Collider::cuboid(1.0, 0.5, 1.0),
-                PickableBundle::default(),
-                Name::new("AI Petrochemical Plant - FIXED"),
-            ));
+
+        let behavior = &ai_player.behavior;
+        let mut wallet = resources.wallet(side);
+        let stock = wallet.amounts();
+
+        // Здание выбирается заново: за время ожидания могли появиться деньги на более нужное
+        let item = match buy_unit {
+            _ if buy_engineer => Some(PurchasableItem::Infantry)
+                .filter(|_| shop.can_buy_engineer(side, survey, behavior.difficulty, &stock)),
+            Some(buy_unit) => Some(buy_unit.item)
+                .filter(|item| shop.can_buy(side, *item, survey, behavior.difficulty, &stock)),
+            None => ECONOMY_ITEMS.iter()
+                .copied()
+                .filter(|item| shop.can_buy(side, *item, survey, behavior.difficulty, &stock))
+                .max_by(|a, b| purchase_score(*a, &behavior.weights).total_cmp(&purchase_score(*b, &behavior.weights))),
+        };
+        let Some(item) = item else {
+            *state = ActionState::Failure;
+            continue;
+        };
+
+        // Модель юнита выбирается случайно среди тех, что по карману
+        let unit_index = unit_class_for_item(item).map(|class| {
+            let variants = shop.affordable_variants(side, class, &stock, buy_engineer);
+            variants[rng.gen_range(0..variants.len())]
+        });
+        let Some(cost) = shop.cost(side, item, unit_index) else {
+            *state = ActionState::Failure;
+            continue;
+        };
+
+        wallet.deduct(&cost);
+        simple_spawn_ai_unit(item, unit_index, &mut commands, &asset_server, &mut meshes, &mut materials, &time, side, shop.faction(side), &shop.catalog, &behavior.weights, &mut rng);
+        purchases.send(AIPurchaseEvent { side, item });
+
+        if buy_engineer {
+            info!("AI ({:?}, {:?}) purchased an engineer for {} construction site(s). Engineers before purchase: {}/{}",
+                  side, behavior.strategy, survey.construction_sites, survey.engineers, behavior.difficulty.engineer_cap());
+        } else {
+            info!("AI ({:?}, {:?}) purchased {:?} with score {:.2}. Owned before purchase: {}/{}",
+                  side, behavior.strategy, item, purchase_score(item, &behavior.weights),
+                  survey.count(item), behavior.difficulty.cap(item));
         }
+        *state = ActionState::Success;
     }
 }
 
-fn make_purchase_ai(
-    item: PurchasableItem,
-    commands: &mut Commands,
-    _meshes: &mut ResMut<Assets<Mesh>>,
-    _materials: &mut ResMut<Assets<StandardMaterial>>,
-    ai_money: &mut ResMut<AIMoney>,
-    ai_wood: &mut ResMut<AIWood>,
-    ai_iron: &mut ResMut<AIIron>,
-    ai_steel: &mut ResMut<AISteel>,
-    ai_oil: &mut ResMut<AIOil>,
-    time: &Res<Time>,
-    asset_server: &Res<AssetServer>,
-    ai_faction: &Res<crate::game::units::AIFaction>,
+/// Ремонт башен командиром: в свой ход, по расписанию покупок и в пределах лимита за ход
+#[allow(clippy::too_many_arguments)]
+pub fn ai_repair_action_system(
+    turn_state: Res<TurnState>,
+    time: Res<Time>,
+    mut resources: SideResources,
+    ai_players: Res<AIPlayers>,
+    mut scheduler: ResMut<AIScheduler>,
+    commanders: Query<&AICommander>,
+    mut towers: Query<(Entity, &mut Health, &mut TowerRepair, Has<EnemyTower>), With<crate::game::Tower>>,
+    mut actions: Query<(&Actor, &mut ActionState), With<RepairTowers>>,
 ) {
-    // Списываем ресурсы ИИ
-    deduct_resources_ai(item, ai_money, ai_wood, ai_iron, ai_steel, ai_oil);
+    for (Actor(actor), mut state) in actions.iter_mut() {
+        match *state {
+            ActionState::Requested => *state = ActionState::Executing,
+            ActionState::Executing => {}
+            ActionState::Cancelled => {
+                *state = ActionState::Failure;
+                continue;
+            }
+            _ => continue,
+        }
+
+        let Ok(commander) = commanders.get(*actor) else {
+            continue;
+        };
+        let side = commander.side;
+        let Some(ai_player) = ai_players.get(side) else {
+            continue;
+        };
+        if turn_state.current_player != side.turn() {
+            continue;
+        }
+
+        // Сначала самая поврежденная башня; при равенстве - по id, чтобы матч повторялся
+        let target = towers.iter()
+            .filter(|(_, health, repair, is_enemy_tower)| needs_repair(side, health, repair, *is_enemy_tower, &turn_state))
+            .min_by(|a, b| {
+                (a.1.current / a.1.max).total_cmp(&(b.1.current / b.1.max)).then(a.0.cmp(&b.0))
+            })
+            .map(|(entity, ..)| entity);
+        let mut wallet = resources.wallet(side);
+        // Лимит за ход и оплату проверяем до расписания, чтобы не тратить на заведомо неудачный ремонт окно решения
+        if target.is_none() || !wallet.amounts().covers(&tower_repair_cost(AI_REPAIR_STEP)) {
+            *state = ActionState::Failure;
+            continue;
+        }
+        if !scheduler.try_decide(side, AIDecision::Purchase) {
+            continue;
+        }
+        let Some((tower, mut health, mut repair, _)) = target.and_then(|entity| towers.get_mut(entity).ok()) else {
+            *state = ActionState::Failure;
+            continue;
+        };
+
+        let result = repair_tower(&mut health, &mut repair, &mut wallet, AI_REPAIR_STEP, &turn_state, time.elapsed_seconds());
+        info!("🔧 AI ({:?}, {:?}) repairs tower {:?}: {:?}, now {:.0}/{:.0}",
+              side, ai_player.behavior.strategy, tower, result, health.current, health.max);
+        *state = match result {
+            TowerRepairResult::Repaired(_) => ActionState::Success,
+            _ => ActionState::Failure,
+        };
+    }
+}
 
-    // Создаем объект
-    spawn_ai_unit(item, commands, _meshes, _materials, time, asset_server, ai_faction);
+/// Класс юнита из каталога для покупаемого предмета (None для зданий)
+fn unit_class_for_item(item: PurchasableItem) -> Option<UnitClass> {
+    match item {
+        PurchasableItem::Infantry => Some(UnitClass::Infantry),
+        PurchasableItem::Tank => Some(UnitClass::Tank),
+        PurchasableItem::Airplane => Some(UnitClass::Aircraft),
+        _ => None,
+    }
 }
 
-fn spawn_ai_unit(
+/// Простая функция создания юнитов ИИ
+#[allow(clippy::too_many_arguments)]
+fn simple_spawn_ai_unit(
     item: PurchasableItem,
+    unit_index: Option<usize>,
     commands: &mut Commands,
-    _meshes: &mut ResMut<Assets<Mesh>>,
-    _materials: &mut ResMut<Assets<StandardMaterial>>,
+    asset_server: &AssetServer,
+    meshes: &mut Assets<Mesh>,
+    materials: &mut Assets<StandardMaterial>,
     time: &Res<Time>,
-    asset_server: &Res<AssetServer>,
-    ai_faction: &Res<crate::game::units::AIFaction>,
+    side: AISide,
+    faction: Faction,
+    unit_catalog: &UnitCatalog,
+    weights: &AIWeights,
+    rng: &mut GameRng,
 ) {
-    // Определяем позицию для спавна (правая сторона карты для ИИ)
-    let seed = time.elapsed_seconds_f64().fract() as f32;
-    let x = 15.0 + (seed * 50.0).sin() * 5.0;
-    let z = (seed * 75.0).cos() * 8.0;
-    let spawn_pos = Vec3::new(x, 0.0, z);
+    // Определяем позицию для спавна (правая сторона карты для ИИ, левая для игрока)
+    let x = 15.0 + rng.gen_range(-5.0..5.0);
+    let z = rng.gen_range(-8.0..8.0);
+    let spawn_pos = side.mirror(Vec3::new(x, 0.0, z));
 
     match item {
-        PurchasableItem::Tank => {
-            let faction = ai_faction.get_opposite();
-            let model_path = match faction {
-                Faction::Entente => "models/entente/tanks/mark1.glb#Scene0",
-                Faction::CentralPowers => "models/central_powers/tanks/a7v.glb#Scene0",
+        PurchasableItem::Tank | PurchasableItem::Infantry | PurchasableItem::Airplane => {
+            let Some(class) = unit_class_for_item(item) else {
+                return;
             };
-            commands.spawn((
-                SceneBundle {
-                    scene: asset_server.load(model_path),
-                    transform: Transform::from_translation(spawn_pos + Vec3::new(0.0, 0.0, 0.0))
-                        .with_scale(Vec3::splat(0.4)),
-                    ..default()
-                },
-                ShapeType::Cube,
-                Enemy,
-                Tank,
-                Selectable,
-                Health { current: 100.0, max: 100.0 },
-                CanShoot {
-                    cooldown: 1.2,
-                    last_shot: time.elapsed_seconds(),
-                    range: 10.0,
-                    damage: 12.0,
-                },
-                RigidBody::Dynamic,
-                Collider::cuboid(0.5, 0.5, 0.5), // Коллайдер танка ИИ
-                LockedAxes::ROTATION_LOCKED | LockedAxes::TRANSLATION_LOCKED_Y, // Заблокируем вращение и движение по Y
-                Restitution::coefficient(0.0), // Без отскока
-                Friction::coefficient(0.8), // Трение
-                PickableBundle::default(),
-                Name::new("AI Tank"),
-            ));
-        }
-        PurchasableItem::Infantry => {
-            let faction = ai_faction.get_opposite();
-            use rand::Rng;
-            let mut rng = rand::thread_rng();
-            let infantry_type_index = rng.gen_range(0..3);
-            
-            let model_path = match faction {
-                Faction::Entente => {
-                    match infantry_type_index {
-                        0 => "models/infantry/russian_soldier.glb#Scene0",
-                        1 => "models/infantry/british_soldier.glb#Scene0",
-                        _ => "models/infantry/french_soldier.glb#Scene0",
-                    }
-                },
-                Faction::CentralPowers => {
-                    match infantry_type_index {
-                        0 => "models/infantry/german_soldier.glb#Scene0",
-                        1 => "models/infantry/turkish_soldier.glb#Scene0",
-                        _ => "models/infantry/austrian_soldier.glb#Scene0",
-                    }
-                },
+            let index = unit_index.unwrap_or(0);
+            let Some(definition) = unit_catalog.get(faction, class, index) else {
+                return;
             };
-            
-            commands.spawn((
-                SceneBundle {
-                    scene: asset_server.load(model_path),
-                    transform: Transform::from_translation(spawn_pos + Vec3::new(0.0, 0.0, 0.0))
-                        .with_scale(Vec3::splat(0.8)),
-                    ..default()
-                },
-                ShapeType::Infantry,
-                Enemy,
-                Selectable,
-                Health { current: 60.0, max: 60.0 },
-                CanShoot {
-                    cooldown: 0.9,
-                    last_shot: time.elapsed_seconds(),
-                    range: 12.0,
-                    damage: 8.0,
-                },
-                RigidBody::Dynamic,
-                Collider::ball(3.0), // Очень большой коллайдер для AI пехоты
-                Sensor, // Невидимый коллайдер для кликов
-                LockedAxes::ROTATION_LOCKED | LockedAxes::TRANSLATION_LOCKED_Y, // Заблокируем вращение и движение по Y
-                Restitution::coefficient(0.0), // Без отскока
-                Friction::coefficient(0.8), // Трение
-                bevy_mod_picking::prelude::PickableBundle::default(),
-                Name::new("AI Infantry"),
-            ));
-        }
-        PurchasableItem::Airplane => {
-            let faction = ai_faction.get_opposite();
-            use rand::Rng;
-            let mut rng = rand::thread_rng();
-            let aircraft_type_index = rng.gen_range(0..3);
-            
-            let model_path = match faction {
-                Faction::Entente => {
-                    match aircraft_type_index {
-                        0 => "models/entente/airplanes/sopwith_camel.glb#Scene0",
-                        1 => "models/entente/airplanes/breguet_14.glb#Scene0",
-                        _ => "models/entente/airplanes/ilya_muromets.glb#Scene0",
+            let can_shoot = CanShoot {
+                last_shot: time.elapsed_seconds(),
+                ..definition.can_shoot()
+            };
+            let name = match (side, class) {
+                (AISide::Enemy, UnitClass::Infantry) if definition.engineer => "AI Engineer",
+                (AISide::Player, UnitClass::Infantry) if definition.engineer => "Player Engineer",
+                (AISide::Enemy, UnitClass::Tank) => "AI Tank",
+                (AISide::Enemy, UnitClass::Infantry) => "AI Infantry",
+                (AISide::Enemy, UnitClass::Aircraft) => "AI Aircraft",
+                (AISide::Player, UnitClass::Tank) => "Player Tank",
+                (AISide::Player, UnitClass::Infantry) => "Player Infantry",
+                (AISide::Player, UnitClass::Aircraft) => "Player Aircraft",
+            };
+
+            if let Some(entity) = spawn_unit(commands, asset_server, unit_catalog, faction, class, index, spawn_pos) {
+                commands.entity(entity).insert((can_shoot, Name::new(name)));
+                // Оборонительный ИИ оставляет часть армии у своих башен; инженеры заняты стройками
+                if !definition.engineer && rng.gen::<f32>() < weights.home_guard_share() {
+                    commands.entity(entity).insert(HomeGuard { post: spawn_pos });
+                }
+                match side {
+                    AISide::Enemy => {
+                        commands.entity(entity).insert(Enemy);
+                        if class == UnitClass::Tank {
+                            commands.entity(entity).insert(Selectable);
+                        }
                     }
-                },
-                Faction::CentralPowers => {
-                    match aircraft_type_index {
-                        0 => "models/central_powers/airplanes/fokker.glb#Scene0",
-                        1 => "models/central_powers/airplanes/albatros.glb#Scene0",
-                        _ => "models/central_powers/airplanes/red_baron.glb#Scene0",
+                    AISide::Player => {
+                        commands.entity(entity).insert(Selectable);
                     }
-                },
-            };
-            commands.spawn((
-                SceneBundle {
-                    scene: asset_server.load(model_path),
-                    transform: Transform::from_translation(spawn_pos + Vec3::new(0.0, 10.0, 0.0))
-                        .with_scale(Vec3::splat(0.6)),
-                    ..default()
-                },
-                ShapeType::Airplane,
-                Enemy,
-                crate::game::Aircraft { height: 10.0, speed: 5.0 },
-                MovementOrder(Vec3::ZERO),
-                Health { current: 75.0, max: 75.0 },
-                CanShoot {
-                    cooldown: 0.6,
-                    last_shot: time.elapsed_seconds(),
-                    range: 20.0,
-                    damage: 15.0,
-                },
-                RigidBody::Fixed, // Самолеты теперь фиксированы в воздухе
-                Collider::cuboid(5.0, 2.5, 6.0), // Большой коллайдер для AI самолетов
-                LockedAxes::all(), // Блокируем все движения
-                PickableBundle::default(),
-                Name::new("AI Aircraft"),
-            ));
+                }
+            }
+        }
+        _ => {
+            // Здание ИИ тоже начинается со стройплощадки; достраивают его инженеры ИИ
+            let site = spawn_construction_site(commands, meshes, materials, item.shape_type(), spawn_pos);
+            if side == AISide::Enemy {
+                commands.entity(site).insert(Enemy).remove::<Selectable>();
+            }
         }
+    }
+}
+
+/// Создает здание ИИ (None для юнитов)
+pub fn spawn_ai_building(
+    commands: &mut Commands,
+    asset_server: &AssetServer,
+    item: PurchasableItem,
+    side: AISide,
+    spawn_pos: Vec3,
+) -> Option<Entity> {
+    let entity = match item {
         PurchasableItem::Farm => {
             commands.spawn((
                 SceneBundle {
@@ -631,14 +1018,13 @@ fn spawn_ai_unit(
                 crate::game::ForestFarm,
                 crate::game::FarmIncomeRate(2.0), // Доход от фермы
                 crate::game::FarmActive(true),
-                crate::game::Enemy,
                 Health { current: 80.0, max: 80.0 },
                 RigidBody::Fixed,
                 LockedAxes::all(),
                 Collider::cuboid(1.5, 1.0, 1.5),
                 PickableBundle::default(),
                 Name::new("AI Farm"),
-            ));
+            )).id()
         }
         PurchasableItem::Mine => {
             commands.spawn((
@@ -651,14 +1037,13 @@ fn spawn_ai_unit(
                 crate::game::Mine,
                 crate::game::MineIronRate(2.0),
                 crate::game::FarmActive(true),
-                crate::game::Enemy,
                 Health { current: 100.0, max: 100.0 },
                 RigidBody::Fixed,
         LockedAxes::all(),
                 Collider::cuboid(1.0, 0.5, 1.0),
                 PickableBundle::default(),
                 Name::new("AI Mine - FIXED"),
-            ));
+            )).id()
         }
         PurchasableItem::SteelFactory => {
             commands.spawn((
@@ -670,14 +1055,13 @@ fn spawn_ai_unit(
                 },
                 crate::game::SteelFactory,
                 crate::game::FarmActive(true),
-                crate::game::Enemy,
                 Health { current: 120.0, max: 120.0 },
                 RigidBody::Fixed,
         LockedAxes::all(),
                 Collider::cuboid(1.0, 0.5, 1.0),
                 PickableBundle::default(),
                 Name::new("AI Steel Factory - FIXED"),
-            ));
+            )).id()
         }
         PurchasableItem::PetrochemicalPlant => {
             commands.spawn((
@@ -689,274 +1073,561 @@ fn spawn_ai_unit(
                 },
                 crate::game::PetrochemicalPlant,
                 crate::game::FarmActive(true),
-                crate::game::Enemy,
                 Health { current: 110.0, max: 110.0 },
                 RigidBody::Fixed,
         LockedAxes::all(),
                 Collider::cuboid(1.0, 0.5, 1.0),
                 PickableBundle::default(),
                 Name::new("AI Petrochemical Plant - FIXED"),
-            ));
+            )).id()
+        }
+        PurchasableItem::Tank | PurchasableItem::Infantry | PurchasableItem::Airplane => return None,
+    };
+    if side == AISide::Enemy {
+        commands.entity(entity).insert(Enemy);
+    }
+    Some(entity)
+}
+
+/// Фильтр подвижных юнитов (без зданий и башен)
+type MobileUnitFilter = (
+    With<CanShoot>,
+    Without<crate::game::Tower>,
+    Without<crate::game::ForestFarm>,
+    Without<crate::game::Mine>,
+    Without<crate::game::SteelFactory>,
+    Without<crate::game::PetrochemicalPlant>,
+);
+
+/// Подвижные юниты, которым еще не выдан мыслитель; инженерами управляет `ai_engineer_orders`
+type UnitWithoutThinkerFilter = (MobileUnitFilter, Without<Engineer>, Without<ThinkerBuilder>);
+
+/// Юниты, которые можно атаковать
+type AttackerFilter = (MobileUnitFilter, With<Health>);
+
+/// Цель выстрела: класс цели для вероятности попадания
+type ShotTarget = (
+    Entity,
+    &'static Transform,
+    Has<crate::game::units::infantry::Infantry>,
+    Has<Tank>,
+    Has<crate::game::Aircraft>,
+);
+
+/// Юнит, ищущий цель для наступления (охрана базы ищет у своего поста)
+type SightedUnit = (
+    &'static Transform,
+    Has<MovementOrder>,
+    Has<Enemy>,
+    Option<&'static HomeGuard>,
+);
+
+/// Наступающий юнит: радиус выстрела, текущий приказ и пост охраны
+type AdvancingUnit = (
+    &'static Transform,
+    &'static CanShoot,
+    Option<&'static MovementOrder>,
+    Has<Enemy>,
+    Option<&'static HomeGuard>,
+);
+
+/// Стреляющий юнит: оружие, сторона и класс для типа снаряда
+type Shooter = (
+    &'static Transform,
+    &'static mut CanShoot,
+    Option<&'static MilitaryUnit>,
+    Has<Enemy>,
+    Has<Tank>,
+    Has<crate::game::Aircraft>,
+    Has<crate::game::units::infantry::Infantry>,
+);
+
+/// Противник в радиусе выстрела юнита
+#[derive(Clone, Component, Debug, ScorerBuilder)]
+pub struct EnemyInRange;
+
+/// Юнит уже идет к цели или видит противника в пределах `engage_range` стратегии
+#[derive(Clone, Component, Debug, ScorerBuilder)]
+pub struct EnemyInSight;
+
+/// Стреляет по ближайшей цели в радиусе
+#[derive(Clone, Component, Debug, ActionBuilder)]
+pub struct AttackTarget;
+
+/// Выбирает цель и идет к ней
+#[derive(Clone, Component, Debug, ActionBuilder)]
+pub struct Advance;
+
+/// Мыслитель юнита: стрелять, если есть по кому, иначе наступать
+fn unit_thinker() -> ThinkerBuilder {
+    Thinker::build()
+        .label("AI Unit")
+        .picker(FirstToScore { threshold: 0.5 })
+        .when(EnemyInRange, AttackTarget)
+        .when(EnemyInSight, Advance)
+}
+
+/// Gives every mobile unit of an AI-controlled side its own thinker
+pub fn attach_ai_unit_thinkers(
+    mut commands: Commands,
+    ai_players: Res<AIPlayers>,
+    units: Query<(Entity, Has<Enemy>), UnitWithoutThinkerFilter>,
+) {
+    for (entity, is_enemy) in units.iter() {
+        if ai_players.get(AISide::of(is_enemy)).is_some() {
+            commands.entity(entity).insert(unit_thinker());
         }
     }
 }
 
-/// Система активных действий ИИ - движение и атака во время хода ИИ
-pub fn ai_movement_system(
+/// Какие цели сторона видит: без тумана - все, с туманом - только известные ей
+fn visible_to<'a>(side: AISide, ai_players: &AIPlayers, fog: &'a FogOfWar) -> impl Fn(&IndexedTarget) -> bool + 'a {
+    let respects_fog = ai_players.get(side).is_some_and(|ai_player| ai_player.behavior.difficulty.respects_fog_of_war());
+    move |target| !respects_fog || fog.knows(side, target.position, target.kind)
+}
+
+/// Куда наступать юниту: ближайший враг в пределах `engage_range`.
+/// Охрана базы ищет врагов только вокруг своего поста и возвращается на него, если целей нет
+fn advance_destination(
+    position: Vec3,
+    guard: Option<&HomeGuard>,
+    engage_range: f32,
+    side: AISide,
+    target_index: &TargetIndex,
+    visible: impl Fn(&IndexedTarget) -> bool,
+) -> Option<Vec3> {
+    let Some(guard) = guard else {
+        return target_index.nearest(position, engage_range, side.opponent(), visible).map(|target| target.position);
+    };
+    target_index.nearest(guard.post, AI_HOME_GUARD_RADIUS, side.opponent(), visible)
+        .map(|target| target.position)
+        .or_else(|| (position.xz().distance(guard.post.xz()) > AI_HOME_GUARD_RADIUS * 0.5).then_some(guard.post))
+}
+
+pub fn enemy_in_range_scorer_system(
+    ai_players: Res<AIPlayers>,
+    target_index: Res<TargetIndex>,
+    fog: Res<FogOfWar>,
+    units: Query<(&Transform, &CanShoot, Has<Enemy>)>,
+    mut scorers: Query<(&Actor, &mut Score), With<EnemyInRange>>,
+) {
+    for (Actor(actor), mut score) in scorers.iter_mut() {
+        let in_range = units.get(*actor).is_ok_and(|(transform, can_shoot, is_enemy)| {
+            let side = AISide::of(is_enemy);
+            target_index.within_radius(transform.translation, can_shoot.range, side.opponent())
+                .any(visible_to(side, &ai_players, &fog))
+        });
+        score.set(if in_range { 1.0 } else { 0.0 });
+    }
+}
+
+pub fn enemy_in_sight_scorer_system(
+    ai_players: Res<AIPlayers>,
+    target_index: Res<TargetIndex>,
+    fog: Res<FogOfWar>,
+    units: Query<SightedUnit, MobileUnitFilter>,
+    mut scorers: Query<(&Actor, &mut Score), With<EnemyInSight>>,
+) {
+    for (Actor(actor), mut score) in scorers.iter_mut() {
+        let in_sight = units.get(*actor).is_ok_and(|(transform, has_order, is_enemy, guard)| {
+            let side = AISide::of(is_enemy);
+            let Some(ai_player) = ai_players.get(side) else {
+                return false;
+            };
+            let engage_range = ai_player.behavior.weights.engage_range();
+            has_order || advance_destination(transform.translation, guard, engage_range, side, &target_index, visible_to(side, &ai_players, &fog))
+                .is_some()
+        });
+        score.set(if in_sight { 1.0 } else { 0.0 });
+    }
+}
+
+/// Наступление: цель выбирается по расписанию, а двигает юнита общая система приказов
+/// (`process_movement_orders`/`aircraft_movement`) с его собственной скоростью
+#[allow(clippy::too_many_arguments)]
+pub fn advance_action_system(
     mut commands: Commands,
     turn_state: Res<TurnState>,
-    time: Res<Time>,
-    // ИИ юниты
-    mut ai_units: Query<(Entity, &mut Transform, Option<&MovementOrder>), (With<Enemy>, Without<crate::game::Tank>, Without<crate::game::ForestFarm>, Without<crate::game::Mine>, Without<crate::game::SteelFactory>, Without<crate::game::PetrochemicalPlant>)>,
-    mut ai_tanks: Query<(Entity, &mut Transform, Option<&MovementOrder>), (With<Enemy>, With<Tank>)>,
-    // Цели для атаки (все юниты игрока кроме зданий)
-    player_units: Query<&Transform, (With<Health>, Without<Enemy>)>, // Ищем ВСЕ цели игрока включая здания
+    ai_players: Res<AIPlayers>,
+    window: Res<AIOrderWindow>,
+    target_index: Res<TargetIndex>,
+    fog: Res<FogOfWar>,
+    units: Query<AdvancingUnit, MobileUnitFilter>,
+    mut actions: Query<(&Actor, &mut ActionState), With<Advance>>,
 ) {
-    // ИИ действует только в свой ход
-    if turn_state.current_player != PlayerTurn::AI {
-        return;
-    }
-    
-    let delta_time = time.delta_seconds();
-    
-    // Обработка движения ИИ танков
-    for (entity, mut transform, movement_order) in ai_tanks.iter_mut() {
-        if let Some(order) = movement_order {
-            let distance_to_target = Vec3::new(transform.translation.x, 0.0, transform.translation.z)
-                .distance(Vec3::new(order.0.x, 0.0, order.0.z));
-            let attack_range = 10.0; // Дистанция атаки танка
-            
-            // Если еще далеко от цели - двигаемся
-            if distance_to_target > attack_range {
-                let mut direction = order.0 - transform.translation;
-                direction.y = 0.0; // Игнорируем Y координату - движемся только по земле
-                let direction = direction.normalize();
-                let move_speed = 3.0; // Скорость танка
-                let movement = direction * move_speed * delta_time;
-                transform.translation.x += movement.x;
-                transform.translation.z += movement.z;
-                // Y координата остается неизменной (на земле)
-            }
-            // Если цель в радиусе атаки - останавливаемся и убираем приказ на движение
-            else {
-                commands.entity(entity).remove::<MovementOrder>();
-            }
-        } else {
-            // Если нет приказа, найти ближайшую цель для атаки
-            if let Some(target_pos) = find_nearest_target(&transform.translation, &player_units) {
-                commands.entity(entity).insert(MovementOrder(target_pos));
+    for (Actor(actor), mut state) in actions.iter_mut() {
+        match *state {
+            ActionState::Requested => *state = ActionState::Executing,
+            ActionState::Executing => {}
+            ActionState::Cancelled => {
+                *state = ActionState::Failure;
+                continue;
             }
+            _ => continue,
         }
-    }
-    
-    // Обработка движения ИИ пехоты
-    for (entity, mut transform, movement_order) in ai_units.iter_mut() {
+
+        let Ok((transform, can_shoot, movement_order, is_enemy, guard)) = units.get(*actor) else {
+            *state = ActionState::Failure;
+            continue;
+        };
+        let side = AISide::of(is_enemy);
+        let Some(ai_player) = ai_players.get(side) else {
+            continue;
+        };
+
         if let Some(order) = movement_order {
             let distance_to_target = Vec3::new(transform.translation.x, 0.0, transform.translation.z)
                 .distance(Vec3::new(order.0.x, 0.0, order.0.z));
-            let attack_range = 12.0; // Дистанция атаки пехоты
-            
-            // Если еще далеко от цели - двигаемся
-            if distance_to_target > attack_range {
-                let mut direction = order.0 - transform.translation;
-                direction.y = 0.0; // Игнорируем Y координату - движемся только по земле
-                let direction = direction.normalize();
-                let move_speed = 2.0; // Скорость пехоты
-                let movement = direction * move_speed * delta_time;
-                transform.translation.x += movement.x;
-                transform.translation.z += movement.z;
-                // Y координата остается неизменной (на земле)
-            }
-            // Если цель в радиусе атаки - останавливаемся и убираем приказ на движение
-            else {
-                commands.entity(entity).remove::<MovementOrder>();
+
+            // Останавливаемся чуть ближе дальности выстрела, чтобы цель точно была в радиусе
+            if distance_to_target <= can_shoot.range * 0.9 {
+                commands.entity(*actor).remove::<MovementOrder>();
+                *state = ActionState::Success;
             }
-        } else {
-            // Если нет приказа, найти ближайшую цель для атаки
-            if let Some(target_pos) = find_nearest_target(&transform.translation, &player_units) {
-                commands.entity(entity).insert(MovementOrder(target_pos));
+        } else if turn_state.current_player == side.turn() && window.is_open(side, AIDecision::Movement) {
+            let engage_range = ai_player.behavior.weights.engage_range();
+            match advance_destination(transform.translation, guard, engage_range, side, &target_index, visible_to(side, &ai_players, &fog)) {
+                Some(destination) => {
+                    commands.entity(*actor).insert(MovementOrder(destination));
+                }
+                None => *state = ActionState::Failure,
             }
         }
     }
 }
 
-/// Система атак ИИ - с разносом по времени
-pub fn ai_combat_system(
-    turn_state: Res<TurnState>,
+/// Атаки юнитов ИИ - с разносом по времени
+#[allow(clippy::too_many_arguments)]
+pub fn attack_action_system(
+    mut commands: Commands,
     time: Res<Time>,
     asset_server: Res<AssetServer>,
-    mut ai_units: Query<(Entity, &Transform, &mut CanShoot), (With<Enemy>, Without<crate::game::ForestFarm>, Without<crate::game::Mine>, Without<crate::game::SteelFactory>, Without<crate::game::PetrochemicalPlant>)>,
-    mut player_units: Query<(Entity, &Transform, &mut Health), Without<Enemy>>, // Атакуем ВСЕ цели игрока включая здания
-    tank_query: Query<Entity, With<crate::game::Tank>>,
-    aircraft_query: Query<Entity, With<crate::game::Aircraft>>,
-    infantry_query: Query<Entity, With<crate::game::units::infantry::Infantry>>,
-    mut commands: Commands,
+    ai_players: Res<AIPlayers>,
+    window: Res<AIOrderWindow>,
+    damage_matrix: Res<DamageMatrix>,
+    target_index: Res<TargetIndex>,
+    fog: Res<FogOfWar>,
+    mut rng: ResMut<GameRng>,
+    mut shooters: Query<Shooter>,
+    targets: Query<ShotTarget, With<Health>>,
+    mut actions: Query<(Entity, &Actor, &mut ActionState), With<AttackTarget>>,
 ) {
-    // ИИ атакует только в свой ход
-    if turn_state.current_player != PlayerTurn::AI {
-        return;
-    }
-    
     let current_time = time.elapsed_seconds();
-    
-    // Отслеживаем, какие цели уже атакуются в этом кадре
-    let mut targets_being_attacked: HashSet<Entity> = HashSet::new();
-    
-    // Собираем всех AI юнитов, готовых к атаке, и сортируем по расстоянию до ближайшей цели
-    let mut ready_ai_units: Vec<(Entity, Vec3, f32, f32)> = Vec::new(); // Entity, position, range, closest_distance
-    
-    for (ai_entity, ai_transform, mut can_shoot) in ai_units.iter_mut() {
-        // Создаем уникальную задержку для каждого юнита на основе их ID
-        let unit_specific_delay = (ai_entity.index() as f32 * 0.3) % 1.5;
-        let adjusted_cooldown = can_shoot.cooldown + unit_specific_delay;
-        
-        // Проверяем кулдаун с индивидуальной задержкой
-        if current_time - can_shoot.last_shot < adjusted_cooldown {
+
+    // Собираем юнитов, готовых к атаке: действие, юнит, позиция, дальность, расстояние до ближайшей цели
+    let mut ready_units: Vec<(Entity, Entity, Vec3, f32, f32)> = Vec::new();
+
+    for (action, Actor(actor), mut state) in actions.iter_mut() {
+        match *state {
+            ActionState::Requested => *state = ActionState::Executing,
+            ActionState::Executing => {}
+            ActionState::Cancelled => {
+                *state = ActionState::Failure;
+                continue;
+            }
+            _ => continue,
+        }
+
+        let Ok((transform, can_shoot, military_unit, is_enemy, _, _, _)) = shooters.get(*actor) else {
+            *state = ActionState::Failure;
+            continue;
+        };
+        let side = AISide::of(is_enemy);
+        if !window.is_open(side, AIDecision::Attack) {
             continue;
         }
-        
-        // Найдем ближайшую цель для этого юнита
-        let mut closest_distance = f32::INFINITY;
-        for (_, target_transform, _) in player_units.iter() {
-            let distance = ai_transform.translation.distance(target_transform.translation);
-            if distance <= can_shoot.range && distance < closest_distance {
-                closest_distance = distance;
-            }
+
+        // Уникальная задержка для каждого юнита на основе его ID
+        let unit_specific_delay = (actor.index() as f32 * 0.3) % 1.5;
+        let (_, cooldown) = weapon_stats(can_shoot, military_unit);
+        if current_time - can_shoot.last_shot < cooldown + unit_specific_delay {
+            continue;
         }
-        
-        if closest_distance < f32::INFINITY {
-            ready_ai_units.push((ai_entity, ai_transform.translation, can_shoot.range, closest_distance));
-        }
-    }
-    
-    // Сортируем AI юнитов по расстоянию до ближайшей цели (ближайшие атакуют первыми)
-    ready_ai_units.sort_by(|a, b| a.3.partial_cmp(&b.3).unwrap());
-    
-    // Теперь обрабатываем атаки, избегая множественных атак на одну цель
-    for (ready_ai_entity, ai_pos, ai_range, _) in ready_ai_units {
-        // Получаем мутабельную ссылку на CanShoot для этого юнита
-        if let Ok((_, _, mut can_shoot)) = ai_units.get_mut(ready_ai_entity) {
-        let mut target_found = false;
-        
-        // Ищем цели в радиусе, которые еще не атакуются
-        for (target_entity, target_transform, mut target_health) in player_units.iter_mut() {
-            // Пропускаем цели, которые уже атакуются
-            if targets_being_attacked.contains(&target_entity) {
-                continue;
+
+        let closest_distance = target_index
+            .nearest(transform.translation, can_shoot.range, side.opponent(), visible_to(side, &ai_players, &fog))
+            .map(|target| target.position.distance(transform.translation));
+
+        if let Some(closest_distance) = closest_distance {
+            ready_units.push((action, *actor, transform.translation, can_shoot.range, closest_distance));
+        }
+    }
+
+    // Ближайшие к цели атакуют первыми, каждая цель получает не больше одного выстрела за кадр
+    ready_units.sort_by(|a, b| a.4.total_cmp(&b.4));
+    let mut targets_being_attacked: HashSet<Entity> = HashSet::new();
+
+    for (action, shooter, shooter_pos, range, _) in ready_units {
+        let Ok((_, mut can_shoot, military_unit, is_enemy, is_tank, is_aircraft, is_infantry)) = shooters.get_mut(shooter) else {
+            continue;
+        };
+        let (damage, _) = weapon_stats(&can_shoot, military_unit);
+        let side = AISide::of(is_enemy);
+        let Some(ai_player) = ai_players.get(side) else {
+            continue;
+        };
+
+        // Ближайшая цель в радиусе, по которой в этом кадре еще не стреляли
+        let visible = visible_to(side, &ai_players, &fog);
+        let target = target_index
+            .nearest(shooter_pos, range, side.opponent(), |target| visible(target) && !targets_being_attacked.contains(&target.entity))
+            .and_then(|target| targets.get(target.entity).ok());
+        if let Some((target_entity, target_transform, target_infantry, target_tank, target_aircraft)) = target {
+            targets_being_attacked.insert(target_entity);
+
+            let damage = damage_matrix.apply(
+                damage,
+                DamageType::for_shooter(is_tank, is_aircraft),
+                ArmorClass::of(target_infantry, target_tank, target_aircraft),
+            );
+            // Урон нанесет снаряд при попадании; меткость зависит от сложности
+            let hit = fire_projectile(&mut commands, &mut rng, Shot {
+                shooter,
+                from: shooter_pos,
+                target: target_entity,
+                target_pos: target_transform.translation,
+                target_class: TargetClass::of(target_infantry, target_tank, target_aircraft),
+                kind: ProjectileKind::for_shooter(is_tank, is_aircraft),
+                damage,
+                range,
+                from_enemy: is_enemy,
+                accuracy: ai_player.behavior.difficulty.hit_chance(),
+            });
+            can_shoot.last_shot = current_time;
+
+            let audio_source = if is_tank {
+                asset_server.load("audio/tank_shot.mp3")
+            } else if is_aircraft {
+                asset_server.load("audio/aircraft_gun.mp3")
+            } else if is_infantry {
+                asset_server.load("audio/infantry_shot.ogg")
+            } else {
+                asset_server.load("audio/gun.mp3")
+            };
+
+            info!("🔫 AI unit shooting from {:?}", shooter_pos);
+            commands.spawn(AudioBundle {
+                source: audio_source,
+                settings: PlaybackSettings::ONCE,
+            });
+
+            if hit {
+                info!("AI unit ({:?}) fired on target for {} damage", side, damage);
+            } else {
+                info!("AI unit ({:?}) fired off target", side);
             }
-            
-            let distance = ai_pos.distance(target_transform.translation);
-            
-            if distance <= ai_range {
-                // Отмечаем эту цель как атакуемую
-                targets_being_attacked.insert(target_entity);
-                
-                // Атакуем
-                target_health.current -= can_shoot.damage;
-                can_shoot.last_shot = current_time;
-                
-                // Воспроизводим звук стрельбы ИИ
-                let audio_source = if tank_query.get(ready_ai_entity).is_ok() {
-                    asset_server.load("audio/tank_shot.mp3")
-                } else if aircraft_query.get(ready_ai_entity).is_ok() {
-                    asset_server.load("audio/aircraft_gun.mp3")  
-                } else if infantry_query.get(ready_ai_entity).is_ok() {
-                    asset_server.load("audio/infantry_shot.ogg")
-                } else {
-                    asset_server.load("audio/gun.mp3")
-                };
-
-                info!("🔫 AI unit shooting from {:?}", ai_pos);
-                commands.spawn(AudioBundle {
-                    source: audio_source,
-                    settings: PlaybackSettings::ONCE,
-                });
-                
-                info!("AI unit attacked player unit for {} damage!", can_shoot.damage);
-                
-                // Если цель уничтожена
-                if target_health.current <= 0.0 {
-                    if let Some(entity_commands) = commands.get_entity(target_entity) {
-                        entity_commands.despawn_recursive();
-                    }
-                    info!("Player unit destroyed by AI!");
-                }
-                
-                target_found = true;
-                break; // Атакуем только одну цель за раз
+
+            if let Ok((_, _, mut state)) = actions.get_mut(action) {
+                *state = ActionState::Success;
             }
         }
-        
-        // Если юнит не нашел цель, он не атакует в этом кадре
-        if !target_found {
-            // Можно добавить логику поиска цели или движения
-        }
-        } // Закрываем if let Ok
     }
 }
 
-fn find_nearest_target(pos: &Vec3, targets: &Query<&Transform, (With<Health>, Without<Enemy>)>) -> Option<Vec3> {
-    let mut nearest_pos = None;
-    let mut nearest_distance = f32::INFINITY;
-    
-    for target_transform in targets.iter() {
-        let distance = pos.distance(target_transform.translation);
-        if distance < nearest_distance {
-            nearest_distance = distance;
-            nearest_pos = Some(target_transform.translation);
+/// Инженеры без текущей работы
+type IdleEngineerFilter = (With<Engineer>, Without<BuildOrder>);
+
+/// Свободные инженеры ИИ идут на ближайшую свою стройку; строит их `engineer_work`
+pub fn ai_engineer_orders(
+    mut commands: Commands,
+    ai_players: Res<AIPlayers>,
+    engineers: Query<(Entity, &Transform, Has<Enemy>), IdleEngineerFilter>,
+    sites: Query<(Entity, &Transform, &ShapeType, Has<Enemy>), With<ConstructionSite>>,
+) {
+    for (engineer, transform, is_enemy) in engineers.iter() {
+        let side = AISide::of(is_enemy);
+        if ai_players.get(side).is_none() {
+            continue;
         }
+        let position = transform.translation;
+        // Ближайшая стройка; при равенстве - по id, чтобы матч повторялся
+        let site = sites.iter()
+            .filter(|(.., is_enemy_site)| side.owns(*is_enemy_site))
+            .min_by(|a, b| {
+                a.1.translation.distance_squared(position)
+                    .total_cmp(&b.1.translation.distance_squared(position))
+                    .then(a.0.cmp(&b.0))
+            });
+        let Some((target, site_transform, building, _)) = site else {
+            continue;
+        };
+        let stand = engineer_stand(site_transform.translation, *building, position);
+        commands.entity(engineer).insert((MovementOrder(stand), BuildOrder { target, stand }));
+        info!("🔧 AI ({:?}) engineer {:?} sent to build {:?} {:?}", side, engineer, building, target);
     }
-    
-    nearest_pos
 }
 
-fn spawn_ai_farm(
-    commands: &mut Commands,
-    _meshes: &mut ResMut<Assets<Mesh>>,
-    _materials: &mut ResMut<Assets<StandardMaterial>>,
-    asset_server: &Res<AssetServer>,
-    existing_farms: &Query<&Transform, (With<crate::game::ForestFarm>, With<Enemy>)>,
-    time: &Res<Time>,
-) {
-    // Генерируем позицию с достаточным расстоянием от существующих ферм
-    let mut attempts = 0;
-    let farm_position = loop {
-        let seed = time.elapsed_seconds_f64().fract() as f32 + attempts as f32 * 0.1;
-        let x = 15.0 + (seed * 30.0).sin() * 8.0; // От 7 до 23
-        let z = (seed * 45.0).cos() * 10.0; // От -10 до 10
-        let candidate_pos = Vec3::new(x, 0.0, z);
-        
-        // Проверяем минимальное расстояние до существующих ферм (6 единиц)
-        let mut too_close = false;
-        for existing_transform in existing_farms.iter() {
-            if existing_transform.translation.distance(candidate_pos) < 6.0 {
-                too_close = true;
-                break;
-            }
-        }
-        
-        if !too_close || attempts > 10 {
-            break candidate_pos;
-        }
-        attempts += 1;
-    };
-    
-    commands.spawn((
-        SceneBundle {
-            scene: asset_server.load("models/farm/forest.glb#Scene0"),
-            transform: Transform::from_translation(farm_position)
-                .with_scale(Vec3::splat(0.2)),
-            ..default()
-        },
-        crate::game::ForestFarm,
-        crate::game::FarmActive(true), 
-        crate::game::Enemy,
-        RigidBody::Fixed,
-        LockedAxes::all(),
-        Collider::cuboid(1.0, 0.5, 1.0),
-        PickableBundle::default(),
-        Name::new("AI Farm - WITH HP"),
-    ));
-    
-    info!("AI farm spawned as ACTIVE at position: {:?}", farm_position);
+/// Utility AI of the opponent: a commander thinker per side for purchases and a thinker per unit
+pub struct AIBrainPlugin;
+
+/// Пустая система, через которую задается порядок очистки big-brain
+fn big_brain_cleanup_barrier() {}
+
+impl Plugin for AIBrainPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(BigBrainPlugin::new(PreUpdate))
+            // big-brain кладет свою очистку в Last без порядка, а ее команды создают и удаляют
+            // мыслителей: при другом порядке сущности получают другие id и матч с тем же seed расходится
+            .add_systems(
+                Last,
+                (
+                    big_brain_cleanup_barrier
+                        .after(big_brain::thinker::actor_gone_cleanup)
+                        .before(big_brain::thinker::thinker_component_detach_system),
+                    big_brain_cleanup_barrier
+                        .after(big_brain::thinker::thinker_component_detach_system)
+                        .before(big_brain::thinker::thinker_component_attach_system),
+                ),
+            )
+            .init_resource::<AIOrderWindow>()
+            .add_systems(
+                PreUpdate,
+                (
+                    (sync_ai_commanders, attach_ai_unit_thinkers, survey_ai_sides, open_ai_order_window).chain(),
+                    (
+                        economy_weak_scorer_system,
+                        needs_engineer_scorer_system,
+                        tower_under_threat_scorer_system,
+                        tower_damaged_scorer_system,
+                        can_afford_scorer_system,
+                        enemy_in_range_scorer_system,
+                        enemy_in_sight_scorer_system,
+                    ),
+                ).chain().in_set(BigBrainSet::Scorers),
+            )
+            .add_systems(
+                PreUpdate,
+                // Цепочка, чтобы GameRng расходовался в одном и том же порядке
+                (ai_purchase_action_system, ai_repair_action_system, advance_action_system, attack_action_system, ai_engineer_orders)
+                    .chain()
+                    .in_set(BigBrainSet::Actions),
+            );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::ecs::system::RunSystemOnce;
+    use crate::systems::ai_scheduler::tick_ai_scheduler;
+    use crate::utils::read_balance_file;
+
+    /// Мир с балансом из assets/ и одним ИИ противника
+    fn ai_app(strategy: AIStrategy) -> App {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, AssetPlugin::default()))
+            .init_asset::<Mesh>()
+            .init_asset::<StandardMaterial>()
+            .init_asset::<Scene>()
+            .add_event::<AIPurchaseEvent>()
+            .insert_resource(read_balance_file::<UnitCatalog>().expect("unit catalog"))
+            .insert_resource(read_balance_file::<CostTable>().expect("cost table"))
+            .init_resource::<BalanceLoaded<UnitCatalog>>()
+            .init_resource::<BalanceLoaded<CostTable>>()
+            .insert_resource(AIPlayers(vec![AIPlayer::new(AISide::Enemy, AIBehavior::new(AIDifficulty::Medium, strategy))]))
+            .insert_resource(GameRng::seeded(1))
+            .init_resource::<AIScheduler>()
+            .init_resource::<TurnState>()
+            .init_resource::<PlayerFaction>()
+            .init_resource::<AIFaction>()
+            .init_resource::<Money>()
+            .init_resource::<Wood>()
+            .init_resource::<Iron>()
+            .init_resource::<Steel>()
+            .init_resource::<Oil>()
+            .init_resource::<AIMoney>()
+            .init_resource::<AIWood>()
+            .init_resource::<AIIron>()
+            .init_resource::<AISteel>()
+            .init_resource::<AIOil>();
+        app
+    }
+
+    fn rich() -> ResourceCost {
+        ResourceCost { money: 10_000.0, wood: 10_000.0, iron: 10_000.0, steel: 10_000.0, oil: 10_000.0 }
+    }
+
+    fn spawn_commander(app: &mut App, survey: SideSurvey) -> Entity {
+        app.world.spawn((AICommander { side: AISide::Enemy }, survey)).id()
+    }
+
+    fn score_of<M>(app: &mut App, scorer: impl Component, system: impl IntoSystem<(), (), M>) -> f32 {
+        let commander = spawn_commander(app, SideSurvey { stock: rich(), ..default() });
+        let entity = app.world.spawn((Actor(commander), Score::default(), scorer)).id();
+        app.world.run_system_once(system);
+        app.world.get::<Score>(entity).expect("scorer keeps its score").get()
+    }
+
+    #[test]
+    fn economy_scorer_follows_strategy_weights() {
+        let economic = score_of(&mut ai_app(AIStrategy::Economic), EconomyWeak, economy_weak_scorer_system);
+        let rusher = score_of(&mut ai_app(AIStrategy::Rusher), EconomyWeak, economy_weak_scorer_system);
+        assert_eq!(economic, purchase_score(PurchasableItem::Farm, &AIStrategy::Economic.get_weights()));
+        assert!(economic > rusher);
+    }
+
+    #[test]
+    fn economy_scorer_is_zero_without_resources() {
+        let mut app = ai_app(AIStrategy::Economic);
+        let commander = spawn_commander(&mut app, SideSurvey::default());
+        let scorer = app.world.spawn((Actor(commander), Score::default(), EconomyWeak)).id();
+        app.world.run_system_once(economy_weak_scorer_system);
+        assert_eq!(app.world.get::<Score>(scorer).unwrap().get(), 0.0);
+    }
+
+    #[test]
+    fn can_afford_scorer_respects_unit_cap() {
+        let mut app = ai_app(AIStrategy::Rusher);
+        let cap = AIDifficulty::Medium.unit_cap();
+        let commander = spawn_commander(&mut app, SideSurvey { tanks: cap, stock: rich(), ..default() });
+        let tank = app.world.spawn((Actor(commander), Score::default(), CanAfford { item: PurchasableItem::Tank })).id();
+        let infantry = app.world.spawn((Actor(commander), Score::default(), CanAfford { item: PurchasableItem::Infantry })).id();
+        app.world.run_system_once(can_afford_scorer_system);
+        assert_eq!(app.world.get::<Score>(tank).unwrap().get(), 0.0);
+        assert!(app.world.get::<Score>(infantry).unwrap().get() > 0.0);
+    }
+
+    /// Командир ИИ с запросом на покупку пехоты
+    fn request_infantry(app: &mut App) -> Entity {
+        app.world.resource_mut::<AIMoney>().0 = 10_000.0;
+        app.world.resource_mut::<AIWood>().0 = 10_000.0;
+        app.world.resource_mut::<AIIron>().0 = 10_000.0;
+        app.world.resource_mut::<AISteel>().0 = 10_000.0;
+        app.world.resource_mut::<AIOil>().0 = 10_000.0;
+        app.world.run_system_once(tick_ai_scheduler);
+        let commander = spawn_commander(app, SideSurvey { stock: rich(), ..default() });
+        app.world.spawn((Actor(commander), ActionState::Requested, BuyUnit { item: PurchasableItem::Infantry })).id()
+    }
+
+    fn ai_infantry_count(app: &mut App) -> usize {
+        app.world
+            .query_filtered::<(), (With<crate::game::units::infantry::Infantry>, With<Enemy>)>()
+            .iter(&app.world)
+            .count()
+    }
+
+    #[test]
+    fn purchase_action_buys_unit_on_ai_turn() {
+        let mut app = ai_app(AIStrategy::Rusher);
+        app.world.resource_mut::<TurnState>().current_player = PlayerTurn::AI;
+        let action = request_infantry(&mut app);
+
+        app.world.run_system_once(ai_purchase_action_system);
+
+        assert_eq!(app.world.get::<ActionState>(action), Some(&ActionState::Success));
+        assert_eq!(ai_infantry_count(&mut app), 1);
+        assert!(app.world.resource::<AIMoney>().0 < 10_000.0);
+    }
+
+    #[test]
+    fn purchase_action_waits_for_ai_turn() {
+        let mut app = ai_app(AIStrategy::Rusher);
+        let action = request_infantry(&mut app);
+
+        app.world.run_system_once(ai_purchase_action_system);
+
+        assert_eq!(app.world.get::<ActionState>(action), Some(&ActionState::Executing));
+        assert_eq!(ai_infantry_count(&mut app), 0);
+        assert_eq!(app.world.resource::<AIMoney>().0, 10_000.0);
+    }
 }
@@ -0,0 +1,146 @@
+use bevy::prelude::*;
+use crate::systems::ai_opponent::{AIDifficulty, AIPlayers, AISide};
+use crate::systems::turn_system::TurnState;
+
+/// Kind of decision an AI side makes on its own schedule
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AIDecision {
+    Purchase,
+    Movement,
+    Attack,
+}
+
+impl AIDecision {
+    pub const ALL: [AIDecision; 3] = [AIDecision::Purchase, AIDecision::Movement, AIDecision::Attack];
+
+    /// Интервал между решениями в секундах: чем выше сложность, тем быстрее реагирует ИИ
+    pub fn interval(self, difficulty: AIDifficulty) -> f32 {
+        match (self, difficulty) {
+            (AIDecision::Purchase, AIDifficulty::Easy) => 5.0,
+            (AIDecision::Purchase, AIDifficulty::Medium) => 3.0,
+            (AIDecision::Purchase, AIDifficulty::Hard) => 1.5,
+            (AIDecision::Movement, AIDifficulty::Easy) => 1.5,
+            (AIDecision::Movement, AIDifficulty::Medium) => 0.75,
+            (AIDecision::Movement, AIDifficulty::Hard) => 0.25,
+            (AIDecision::Attack, AIDifficulty::Easy) => 0.6,
+            (AIDecision::Attack, AIDifficulty::Medium) => 0.3,
+            (AIDecision::Attack, AIDifficulty::Hard) => 0.1,
+        }
+    }
+
+    fn index(self) -> usize {
+        match self {
+            AIDecision::Purchase => 0,
+            AIDecision::Movement => 1,
+            AIDecision::Attack => 2,
+        }
+    }
+}
+
+/// Decision timers of one AI side
+#[derive(Debug)]
+pub struct DecisionSchedule {
+    pub side: AISide,
+    pub difficulty: AIDifficulty,
+    timers: [Timer; 3],
+}
+
+impl DecisionSchedule {
+    fn new(side: AISide, difficulty: AIDifficulty) -> Self {
+        // Первое решение разрешено сразу, дальше - по интервалу
+        let timer = |decision: AIDecision| {
+            let mut timer = Timer::from_seconds(decision.interval(difficulty), TimerMode::Once);
+            timer.tick(timer.duration());
+            timer
+        };
+        Self {
+            side,
+            difficulty,
+            timers: AIDecision::ALL.map(timer),
+        }
+    }
+
+    pub fn timer(&self, decision: AIDecision) -> &Timer {
+        &self.timers[decision.index()]
+    }
+}
+
+/// Controls how often each AI side may buy, pick movement targets and attack
+#[derive(Resource, Debug, Default)]
+pub struct AIScheduler {
+    pub schedules: Vec<DecisionSchedule>,
+}
+
+impl AIScheduler {
+    /// Returns true and restarts the timer if `side` may make this decision now
+    pub fn try_decide(&mut self, side: AISide, decision: AIDecision) -> bool {
+        let Some(schedule) = self.schedules.iter_mut().find(|schedule| schedule.side == side) else {
+            return false;
+        };
+        let timer = &mut schedule.timers[decision.index()];
+        if !timer.finished() {
+            return false;
+        }
+        timer.reset();
+        true
+    }
+
+    /// Забывает все таймеры; расписания создаются заново на следующем кадре
+    pub fn reset(&mut self) {
+        self.schedules.clear();
+    }
+}
+
+/// Keeps one schedule per AI player and advances its timers
+pub fn tick_ai_scheduler(
+    time: Res<Time>,
+    ai_players: Res<AIPlayers>,
+    mut scheduler: ResMut<AIScheduler>,
+) {
+    // Пересоздаем расписания, если набор игроков или сложность изменились
+    scheduler.schedules.retain(|schedule| {
+        ai_players.0.iter().any(|player| player.side == schedule.side && player.behavior.difficulty == schedule.difficulty)
+    });
+    for player in ai_players.0.iter() {
+        if !scheduler.schedules.iter().any(|schedule| schedule.side == player.side) {
+            scheduler.schedules.push(DecisionSchedule::new(player.side, player.behavior.difficulty));
+        }
+    }
+
+    for schedule in scheduler.schedules.iter_mut() {
+        for timer in schedule.timers.iter_mut() {
+            timer.tick(time.delta());
+        }
+    }
+}
+
+/// Решения юнитов (движение и атака), открытые в текущем кадре.
+/// Таймер общий для всех юнитов стороны, поэтому он сбрасывается здесь один раз за кадр,
+/// а действия юнитов только проверяют окно.
+#[derive(Resource, Debug, Default)]
+pub struct AIOrderWindow {
+    open: Vec<(AISide, AIDecision)>,
+}
+
+impl AIOrderWindow {
+    pub fn is_open(&self, side: AISide, decision: AIDecision) -> bool {
+        self.open.contains(&(side, decision))
+    }
+}
+
+/// Opens the unit order window for the side whose turn it is
+pub fn open_ai_order_window(
+    turn_state: Res<TurnState>,
+    ai_players: Res<AIPlayers>,
+    mut scheduler: ResMut<AIScheduler>,
+    mut window: ResMut<AIOrderWindow>,
+) {
+    window.open.clear();
+    for ai_player in ai_players.0.iter().filter(|player| turn_state.current_player == player.side.turn()) {
+        for decision in [AIDecision::Movement, AIDecision::Attack] {
+            if scheduler.try_decide(ai_player.side, decision) {
+                window.open.push((ai_player.side, decision));
+            }
+        }
+    }
+}
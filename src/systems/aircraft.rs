@@ -1,7 +1,11 @@
 use bevy::prelude::*;
 use bevy_rapier3d::prelude::*;
 use bevy_mod_picking::prelude::*;
-use crate::game::components::{Aircraft, MovementOrder, Selectable, CanShoot};
+use crate::game::components::{Aircraft, MovementOrder, Selectable, CanShoot, Enemy};
+use crate::game::units::MilitaryUnit;
+use crate::systems::ai_opponent::AIPlayers;
+use crate::systems::formations::FormationSpeed;
+use crate::systems::turn_system::TurnState;
 
 /// Marker component for aircraft that are currently playing movement sound
 #[derive(Component)]
@@ -11,14 +15,23 @@ pub struct MovingAircraft;
 #[derive(Component)]
 pub struct AircraftMovementAudio;
 
+type FlyingUnit<'a> = (Entity, &'a mut Transform, &'a Aircraft, &'a MovementOrder, Option<&'a MilitaryUnit>, Option<&'a FormationSpeed>, Has<Enemy>);
+
 pub fn aircraft_movement(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
     time: Res<Time>,
-    mut query: Query<(Entity, &mut Transform, &Aircraft, &MovementOrder)>,
+    turn_state: Res<TurnState>,
+    ai_players: Res<AIPlayers>,
+    mut query: Query<FlyingUnit>,
     moving_aircraft_query: Query<Entity, With<MovingAircraft>>,
 ) {
-    for (entity, mut transform, aircraft, movement_order) in query.iter_mut() {
+    for (entity, mut transform, aircraft, movement_order, military_unit, formation_speed, is_enemy) in query.iter_mut() {
+        // Самолеты ИИ ждут своего хода
+        if !ai_players.may_move(is_enemy, &turn_state) {
+            continue;
+        }
+
         let target_position = movement_order.0;
         let current_position = transform.translation;
         
@@ -34,7 +47,7 @@ pub fn aircraft_movement(
         // Check if we've reached the target (within 1.0 unit distance)
         if direction_xz.length_squared() <= 1.0 {
             // Reached target, remove movement order and stop sound
-            commands.entity(entity).remove::<MovementOrder>();
+            commands.entity(entity).remove::<(MovementOrder, FormationSpeed)>();
             commands.entity(entity).remove::<MovingAircraft>();
             info!("Aircraft {:?} reached target at {:?}", entity, target_position);
             continue;
@@ -60,7 +73,9 @@ pub fn aircraft_movement(
             let normalized_direction = direction_xz.normalize();
             
             // Move the aircraft
-            let movement = normalized_direction * aircraft.speed * time.delta_seconds();
+            let speed = military_unit.map_or(aircraft.speed, |unit| unit.speed);
+            let speed = formation_speed.map_or(speed, |pace| speed.min(pace.0));
+            let movement = normalized_direction * speed * time.delta_seconds();
             transform.translation += movement;
             
             // Keep the aircraft at the specified height
@@ -93,6 +108,16 @@ pub fn spawn_initial_aircraft(
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<StandardMaterial>>,
 ) {
+    spawn_starting_aircraft(&mut commands, &mut meshes, &mut materials, Transform::from_xyz(0.0, 10.0, 0.0));
+}
+
+/// Spawns the player's starting aircraft (a primitive mesh, not a catalog unit)
+pub fn spawn_starting_aircraft(
+    commands: &mut Commands,
+    meshes: &mut Assets<Mesh>,
+    materials: &mut Assets<StandardMaterial>,
+    transform: Transform,
+) -> Entity {
     commands.spawn((
         PbrBundle {
             mesh: meshes.add(Mesh::from(Cuboid::new(2.0, 0.5, 4.0))),
@@ -100,7 +125,7 @@ pub fn spawn_initial_aircraft(
                 base_color: Color::rgb(0.8, 0.8, 0.8),
                 ..default()
             }),
-            transform: Transform::from_xyz(0.0, 10.0, 0.0),
+            transform,
             ..default()
         },
         Aircraft {
@@ -120,5 +145,5 @@ pub fn spawn_initial_aircraft(
         Sensor,
         PickableBundle::default(),
         Name::new("Initial Aircraft"),
-    ));
+    )).id()
 } 
\ No newline at end of file
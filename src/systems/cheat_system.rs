@@ -4,12 +4,15 @@ use crate::systems::turn_system::TurnState;
 use crate::systems::victory_system::{VictoryState, TwitterConfig};
 use crate::game::{EnemyTower, Health, Tower};
 use crate::menu::common::GameState;
+use crate::systems::fog_of_war::FogOfWar;
+use crate::input::key_bindings::{InputAction, KeyBindings};
 
 /// Handle cheat key inputs
 pub fn handle_cheat_keys(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
     keyboard_input: Res<ButtonInput<KeyCode>>,
+    key_bindings: Res<KeyBindings>,
     mut money: ResMut<Money>,
     mut wood: ResMut<Wood>,
     mut iron: ResMut<Iron>,
@@ -21,9 +24,10 @@ pub fn handle_cheat_keys(
     mut player_towers: Query<&mut Health, (With<Tower>, Without<EnemyTower>)>,
     mut enemy_towers: Query<&mut Health, (With<Tower>, With<EnemyTower>)>,
     twitter_config: Res<TwitterConfig>,
+    mut fog: ResMut<FogOfWar>,
 ) {
-    // Cheat: D - Force defeat
-    if keyboard_input.just_pressed(KeyCode::KeyD) {
+    // Cheat: CheatDefeat (Ctrl+D by default) - Force defeat
+    if key_bindings.just_pressed(InputAction::CheatDefeat, &keyboard_input) {
         println!("🔧 CHEAT: Force defeat activated!");
         
         // Destroy all player towers
@@ -38,8 +42,8 @@ pub fn handle_cheat_keys(
         show_defeat_screen_cheat(&mut commands, &asset_server);
     }
 
-    // Cheat: V - Force victory
-    if keyboard_input.just_pressed(KeyCode::KeyV) {
+    // Cheat: CheatVictory (Ctrl+V by default) - Force victory
+    if key_bindings.just_pressed(InputAction::CheatVictory, &keyboard_input) {
         println!("🔧 CHEAT: Force victory activated!");
         
         // Destroy all enemy towers
@@ -54,8 +58,8 @@ pub fn handle_cheat_keys(
         show_victory_screen_cheat(&mut commands, &asset_server);
     }
 
-    // Cheat: M - Add 1000 resources
-    if keyboard_input.just_pressed(KeyCode::KeyM) {
+    // Cheat: CheatResources (Ctrl+M by default) - Add 1000 resources
+    if key_bindings.just_pressed(InputAction::CheatResources, &keyboard_input) {
         println!("🔧 CHEAT: +1000 resources activated!");
         
         money.0 += 1000.0;
@@ -67,8 +71,8 @@ pub fn handle_cheat_keys(
         println!("💰 Added 1000 to all resources!");
     }
 
-    // Cheat: T - Add 10 seconds to player turn
-    if keyboard_input.just_pressed(KeyCode::KeyT) {
+    // Cheat: CheatTurnTime (Ctrl+T by default) - Add 10 seconds to player turn
+    if key_bindings.just_pressed(InputAction::CheatTurnTime, &keyboard_input) {
         println!("🔧 CHEAT: +10 seconds to turn activated!");
         
         turn_state.time_left += 10.0;
@@ -76,8 +80,14 @@ pub fn handle_cheat_keys(
         println!("⏰ Added 10 seconds to current turn! Time left: {:.1}s", turn_state.time_left);
     }
 
-    // Cheat: P - Test Twitter post
-    if keyboard_input.just_pressed(KeyCode::KeyP) {
+    // Cheat: CheatRevealMap (Ctrl+X by default) - Reveal the whole map (fog of war is still applied to targeting)
+    if key_bindings.just_pressed(InputAction::CheatRevealMap, &keyboard_input) {
+        fog.revealed = !fog.revealed;
+        println!("🔧 CHEAT: Map reveal {}", if fog.revealed { "on" } else { "off" });
+    }
+
+    // Cheat: CheatTestTweet (Ctrl+P by default) - Test Twitter post
+    if key_bindings.just_pressed(InputAction::CheatTestTweet, &keyboard_input) {
         println!("🔧 CHEAT: Testing Twitter post!");
         
         if twitter_config.enabled {
@@ -1,17 +1,24 @@
 use bevy::prelude::*;
 use bevy_mod_picking::prelude::*;
 
-use crate::game::{SelectedEntity, Enemy, Health, CanShoot, EnemyTower, ShapeType, Mine, SteelFactory, PetrochemicalPlant, LinkedToEnemy, Tank, Aircraft};
+use crate::game::{Selection, Enemy, Health, CanShoot, EnemyTower, Mine, SteelFactory, PetrochemicalPlant, LinkedToEnemy, Tank, Aircraft, Trench, TrenchConstruction, TrenchGarrison};
 use crate::game::units::infantry::Infantry;
+use crate::game::units::{weapon_stats, MilitaryUnit};
+use crate::game::units::armor::{ArmorClass, DamageMatrix, DamageType};
+use crate::systems::projectiles::{fire_projectile, ProjectileKind, Shot, TargetClass};
+use crate::utils::GameRng;
 use crate::systems::turn_system::{TurnState, PlayerTurn};
+use crate::systems::ai_opponent::AISide;
+use crate::systems::fog_of_war::FogOfWar;
+use crate::systems::spatial_index::{TargetIndex, TargetKind};
 
 
-/// system for processing clicks on attackable objects (enemies or towers) with instant hit
+/// system for processing clicks on attackable objects (enemies or towers): every selected unit in range opens fire
 pub fn handle_attacks(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
     mut click_events: EventReader<Pointer<Click>>,
-    selected_entity: Res<SelectedEntity>,
+    selection: Res<Selection>,
     // Combined query for all enemy entities
     query_enemies: Query<Entity, Or<(
         With<Enemy>,
@@ -23,8 +30,8 @@ pub fn handle_attacks(
     child_query: Query<&crate::game::scene_colliders::ChildOfClickable>,
     collider_query: Query<&LinkedToEnemy>,
     transform_query: Query<&Transform>,
-    can_shoot_query: Query<&CanShoot>,
-    mut health_query: Query<&mut Health>,
+    can_shoot_query: Query<(&CanShoot, Option<&MilitaryUnit>)>,
+    health_query: Query<&Health>,
     // Combined query for unit types
     unit_type_query: Query<(
         Option<&Tank>, 
@@ -33,6 +40,9 @@ pub fn handle_attacks(
     )>,
     time: Res<Time>,
     turn_state: Res<TurnState>,
+    damage_matrix: Res<DamageMatrix>,
+    fog: Res<FogOfWar>,
+    mut rng: ResMut<GameRng>,
 ) {
     // Блокируем все клики во время хода ИИ
     if turn_state.current_player != PlayerTurn::Human {
@@ -82,13 +92,18 @@ pub fn handle_attacks(
         info!("handle_attacks: Click on entity {:?}, is_valid_target: {}", event.target, is_valid_target);
         
         if is_valid_target {
-            info!("handle_attacks: Valid target clicked, selected_entity: {:?}", selected_entity.0);
-            if let Some(shooter_entity) = selected_entity.0 {
-                if let Ok(can_shoot) = can_shoot_query.get(shooter_entity) {
-                    info!("handle_attacks: Shooter has CanShoot component, damage: {}, range: {}", can_shoot.damage, can_shoot.range);
+            info!("handle_attacks: Valid target clicked, selection: {:?}", selection.0);
+            if selection.is_empty() {
+                info!("handle_attacks: No entity selected - cannot attack");
+            }
+            // Приказ атаки получает вся выделенная группа
+            for shooter_entity in selection.iter() {
+                if let Ok((can_shoot, military_unit)) = can_shoot_query.get(shooter_entity) {
+                    let (damage, cooldown) = weapon_stats(can_shoot, military_unit);
+                    info!("handle_attacks: Shooter has CanShoot component, damage: {}, range: {}", damage, can_shoot.range);
                     let current_time = time.elapsed_seconds();
                     
-                    if current_time - can_shoot.last_shot >= can_shoot.cooldown {
+                    if current_time - can_shoot.last_shot >= cooldown {
                         if let (Ok(shooter_transform), Ok(target_transform)) = (
                             transform_query.get(shooter_entity),
                             transform_query.get(target_entity)
@@ -98,15 +113,36 @@ pub fn handle_attacks(
                             let distance = (target_pos - shooter_pos).length();
                             
                             info!("handle_attacks: Distance {} <= range {}: {}", distance, can_shoot.range, distance <= can_shoot.range);
-                            if distance <= can_shoot.range {
-                                // Instant hit - apply damage immediately
-                                if let Ok(mut health) = health_query.get_mut(target_entity) {
-                                    let old_health = health.current;
-                                    health.current -= can_shoot.damage;
-                                    info!("handle_attacks: Damage applied! Health: {} -> {}", old_health, health.current);
+                            // Стрелять можно только по тому, что видно сквозь туман войны
+                            if !fog.sees(AISide::Player, target_pos) {
+                                info!("handle_attacks: Target is hidden by the fog of war");
+                            } else if distance <= can_shoot.range {
+                                // Выстрел - снаряд летит к цели, урон наносится при попадании
+                                if health_query.get(target_entity).is_ok() {
+                                    let (tank_opt, aircraft_opt, infantry_opt) = unit_type_query.get(shooter_entity).unwrap_or((None, None, None));
+                                    let (target_tank, target_aircraft, target_infantry) = unit_type_query.get(target_entity).unwrap_or((None, None, None));
+                                    let kind = ProjectileKind::for_shooter(tank_opt.is_some(), aircraft_opt.is_some());
+                                    // Урон по цели зависит от типа оружия и брони цели
+                                    let damage = damage_matrix.apply(
+                                        damage,
+                                        DamageType::for_shooter(tank_opt.is_some(), aircraft_opt.is_some()),
+                                        ArmorClass::of(target_infantry.is_some(), target_tank.is_some(), target_aircraft.is_some()),
+                                    );
+                                    let hit = fire_projectile(&mut commands, &mut rng, Shot {
+                                        shooter: shooter_entity,
+                                        from: shooter_pos,
+                                        target: target_entity,
+                                        target_pos,
+                                        target_class: TargetClass::of(target_infantry.is_some(), target_tank.is_some(), target_aircraft.is_some()),
+                                        kind,
+                                        damage,
+                                        range: can_shoot.range,
+                                        from_enemy: query_enemies.get(shooter_entity).is_ok(),
+                                        accuracy: 1.0,
+                                    });
+                                    info!("handle_attacks: {:?} fired, {}", kind, if hit { "on target" } else { "off target" });
                                     
                                     // Воспроизводим звук стрельбы
-                                    let (tank_opt, aircraft_opt, infantry_opt) = unit_type_query.get(selected_entity.0.unwrap()).unwrap_or((None, None, None));
                                     let audio_source = if tank_opt.is_some() {
                                         asset_server.load("audio/tank_shot.mp3")
                                     } else if aircraft_opt.is_some() {
@@ -122,14 +158,6 @@ pub fn handle_attacks(
                                         source: audio_source,
                                         settings: PlaybackSettings::ONCE,
                                     });
-                                    
-                                    if health.current <= 0.0 {
-                                        info!("handle_attacks: Enemy destroyed!");
-                                        // Use try_despawn_recursive to avoid panics if entity is already despawned
-                                        if let Some(entity_commands) = commands.get_entity(target_entity) {
-                                            entity_commands.despawn_recursive();
-                                        }
-                                    }
                                 } else {
                                     info!("handle_attacks: Could not get Health component from target");
                                 }
@@ -151,10 +179,8 @@ pub fn handle_attacks(
                         info!("handle_attacks: Weapon on cooldown");
                     }
                 } else {
-                    info!("handle_attacks: Selected entity has no CanShoot component");
+                    info!("handle_attacks: Selected entity {:?} has no CanShoot component", shooter_entity);
                 }
-            } else {
-                info!("handle_attacks: No entity selected - cannot attack");
             }
         }
     }
@@ -162,32 +188,64 @@ pub fn handle_attacks(
 
 
 
-/// System to handle damage from trenches to enemy infantry
+/// Радиус, в котором окоп отбивается от пехоты и сам получает урон от штурма
+const TRENCH_DEFENSE_RADIUS: f32 = 4.0;
+/// Урон в секунду по каждому пехотинцу противника у окопа: пустой окоп и прибавка за каждого бойца внутри
+const TRENCH_BASE_DPS: f32 = 2.0;
+const TRENCH_DPS_PER_OCCUPANT: f32 = 2.0;
+/// Урон в секунду, который каждый штурмующий пехотинец наносит окопу
+const TRENCH_ASSAULT_DPS: f32 = 1.5;
+
+type DefendedTrench<'a> = (Entity, &'a Transform, &'a mut Health, &'a TrenchGarrison);
+type AssaultingInfantry = (With<Enemy>, With<Infantry>, Without<Trench>);
+
+/// Completed trenches fight enemy infantry that comes close: the attackers take damage (more with a garrison
+/// inside), the trench wears down under the assault and collapses at zero health.
+/// Like any other fire, the trench hits back on the player's turn and the assault wears it down on the AI's turn
 pub fn handle_trench_damage(
-    trench_query: Query<&Transform, With<crate::game::Trench>>,
-    mut enemy_query: Query<(&Transform, &mut Health), (With<Enemy>, With<ShapeType>)>,
-    _time: Res<Time>,
+    mut commands: Commands,
+    time: Res<Time>,
+    turn_state: Res<TurnState>,
+    target_index: Res<TargetIndex>,
+    mut trenches: Query<DefendedTrench, (With<Trench>, Without<TrenchConstruction>)>,
+    mut enemy_infantry: Query<&mut Health, AssaultingInfantry>,
 ) {
-    // Basic implementation for future addition of full functionality
-    // In the future there will be:
-    // 1. Checking distance from enemies to trenches
-    // 2. If enemy attacks trench (is nearby), trench deals damage
-    // 3. Implementation of trench health and destruction
+    let delta_time = time.delta_seconds();
+    let defending = turn_state.current_player == PlayerTurn::Human;
 
-    // Note: full functionality will be added later as specified in requirements
-    
-    for trench_transform in trench_query.iter() {
-        let trench_pos = trench_transform.translation;
-        
-        // Check all Infantry type enemies within 3 units radius of trench
-        for (enemy_transform, mut _health) in enemy_query.iter_mut() {
-            let enemy_pos = enemy_transform.translation;
-            let distance = trench_pos.distance(enemy_pos);
-            
-            if distance < 3.0 {
-                // In the future here will be:
-                // health.current -= 1.0 * time.delta_seconds();
+    for (trench, transform, mut trench_health, garrison) in trenches.iter_mut() {
+        let attackers: Vec<Entity> = target_index
+            .within_radius(transform.translation, TRENCH_DEFENSE_RADIUS, AISide::Enemy)
+            .filter(|target| target.kind == TargetKind::Unit && enemy_infantry.get(target.entity).is_ok())
+            .map(|target| target.entity)
+            .collect();
+        if attackers.is_empty() {
+            continue;
+        }
+
+        if !defending {
+            trench_health.current -= TRENCH_ASSAULT_DPS * attackers.len() as f32 * delta_time;
+            if trench_health.current <= 0.0 {
+                commands.entity(trench).despawn_recursive();
+                info!("🪖 Trench {:?} collapsed under the assault", trench);
+            }
+            continue;
+        }
+
+        let damage = (TRENCH_BASE_DPS + TRENCH_DPS_PER_OCCUPANT * garrison.count() as f32) * delta_time;
+        for attacker in attackers.iter() {
+            let Ok(mut health) = enemy_infantry.get_mut(*attacker) else {
+                continue;
+            };
+            // Уже убит соседним окопом в этом кадре
+            if health.current <= 0.0 {
+                continue;
+            }
+            health.current -= damage;
+            if health.current <= 0.0 {
+                commands.entity(*attacker).despawn_recursive();
+                info!("🪖 Enemy infantry {:?} fell assaulting a trench", attacker);
             }
         }
     }
-} 
\ No newline at end of file
+}
@@ -1,5 +1,6 @@
 use bevy::prelude::*;
 use crate::game::components::{Enemy, EnemyTower, Selectable, Tower};
+use crate::systems::fog_of_war::FogHidden;
 
 /// Plugin for managing visual markers
 pub struct EnemyVisualMarkersPlugin;
@@ -16,8 +17,8 @@ impl Plugin for EnemyVisualMarkersPlugin {
 /// System to draw simple circular markers above units and towers
 fn draw_unit_markers(
     mut gizmos: Gizmos,
-    enemy_query: Query<&Transform, (Or<(With<Enemy>, With<EnemyTower>)>, Without<Tower>)>,
-    enemy_tower_query: Query<&Transform, With<EnemyTower>>,
+    enemy_query: Query<&Transform, (Or<(With<Enemy>, With<EnemyTower>)>, Without<Tower>, Without<FogHidden>)>,
+    enemy_tower_query: Query<&Transform, (With<EnemyTower>, Without<FogHidden>)>,
     player_query: Query<&Transform, (With<Selectable>, Without<Enemy>, Without<EnemyTower>)>,
     player_tower_query: Query<&Transform, (With<Tower>, Without<EnemyTower>)>,
 ) {
@@ -0,0 +1,271 @@
+use bevy::prelude::*;
+use bevy::render::render_asset::RenderAssetUsages;
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+use bevy::render::texture::ImageSampler;
+use bevy_mod_picking::prelude::*;
+
+use crate::game::{CanShoot, Enemy, EnemyTower, Health, ShapeType, Tower, Vision};
+use crate::game_plugin::OnGameScreen;
+use crate::systems::ai_opponent::AISide;
+use crate::systems::pathfinding::NAV_GRID_HALF_EXTENT;
+use crate::systems::spatial_index::TargetKind;
+
+/// Размер клетки тумана войны
+pub const FOG_CELL_SIZE: f32 = 2.0;
+/// Высота слоя тумана над землей
+const FOG_OVERLAY_HEIGHT: f32 = 0.05;
+/// Непрозрачность тумана: никогда не видели / видели раньше / видно сейчас
+const UNEXPLORED_ALPHA: u8 = 235;
+const EXPLORED_ALPHA: u8 = 140;
+const VISIBLE_ALPHA: u8 = 0;
+
+/// What a side knows about a spot of the map
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FogState {
+    Unexplored,
+    /// Видели раньше: здания остаются на карте, юниты - нет
+    Explored,
+    Visible,
+}
+
+/// Explored and currently visible cells of one side
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SideVision {
+    explored: Vec<bool>,
+    visible: Vec<bool>,
+}
+
+impl SideVision {
+    fn new(cells: usize) -> Self {
+        Self { explored: vec![false; cells], visible: vec![false; cells] }
+    }
+}
+
+/// Fog of war of both sides, recomputed every frame from the vision radii of their units and buildings
+#[derive(Resource, Clone, Debug)]
+pub struct FogOfWar {
+    pub size: i32,
+    player: SideVision,
+    enemy: SideVision,
+    /// Чит: игрок видит всю карту (туман остается в силе для прицеливания)
+    pub revealed: bool,
+}
+
+impl Default for FogOfWar {
+    fn default() -> Self {
+        let size = (NAV_GRID_HALF_EXTENT * 2.0 / FOG_CELL_SIZE).ceil() as i32;
+        let cells = (size * size) as usize;
+        Self { size, player: SideVision::new(cells), enemy: SideVision::new(cells), revealed: false }
+    }
+}
+
+impl FogOfWar {
+    fn cell_of(&self, position: Vec3) -> IVec2 {
+        let cell = ((Vec2::new(position.x, position.z) + NAV_GRID_HALF_EXTENT) / FOG_CELL_SIZE).floor();
+        cell.as_ivec2().clamp(IVec2::ZERO, IVec2::splat(self.size - 1))
+    }
+
+    fn index(&self, cell: IVec2) -> usize {
+        (cell.y * self.size + cell.x) as usize
+    }
+
+    fn vision(&self, side: AISide) -> &SideVision {
+        match side {
+            AISide::Player => &self.player,
+            AISide::Enemy => &self.enemy,
+        }
+    }
+
+    pub fn state(&self, side: AISide, position: Vec3) -> FogState {
+        let vision = self.vision(side);
+        let index = self.index(self.cell_of(position));
+        if vision.visible[index] {
+            FogState::Visible
+        } else if vision.explored[index] {
+            FogState::Explored
+        } else {
+            FogState::Unexplored
+        }
+    }
+
+    /// Does `side` see this point right now
+    pub fn sees(&self, side: AISide, position: Vec3) -> bool {
+        self.state(side, position) == FogState::Visible
+    }
+
+    /// Does `side` know about this target: units only while seen, buildings once explored,
+    /// towers always - they stand at the known positions of the map
+    pub fn knows(&self, side: AISide, position: Vec3, kind: TargetKind) -> bool {
+        match kind {
+            TargetKind::Unit => self.sees(side, position),
+            TargetKind::Building => self.state(side, position) != FogState::Unexplored,
+            TargetKind::Tower => true,
+        }
+    }
+
+    /// Marks every cell whose center lies within `radius` of `center` as visible and explored
+    fn reveal(&self, vision: &mut SideVision, center: Vec3, radius: f32) {
+        let min = self.cell_of(center - Vec3::new(radius, 0.0, radius));
+        let max = self.cell_of(center + Vec3::new(radius, 0.0, radius));
+        let center = Vec2::new(center.x, center.z);
+        for y in min.y..=max.y {
+            for x in min.x..=max.x {
+                let cell_center = (Vec2::new(x as f32, y as f32) + 0.5) * FOG_CELL_SIZE - NAV_GRID_HALF_EXTENT;
+                if cell_center.distance(center) <= radius {
+                    let index = self.index(IVec2::new(x, y));
+                    vision.visible[index] = true;
+                    vision.explored[index] = true;
+                }
+            }
+        }
+    }
+}
+
+/// Vision radius of entities without a `Vision` component, by their shape
+pub fn default_vision(shape: Option<&ShapeType>) -> f32 {
+    match shape {
+        Some(ShapeType::Tower) => 18.0,
+        Some(ShapeType::Airplane) => 22.0,
+        Some(ShapeType::Infantry) => 12.0,
+        Some(ShapeType::Cube) => 14.0,
+        Some(ShapeType::Trench) => 10.0,
+        Some(ShapeType::Farm | ShapeType::Mine | ShapeType::SteelFactory | ShapeType::PetrochemicalPlant) => 8.0,
+        None => 10.0,
+    }
+}
+
+/// Marker for enemy entities hidden by the player's fog
+#[derive(Component)]
+pub struct FogHidden;
+
+/// Ground overlay that shows the fog as a texture, one texel per fog cell
+#[derive(Component)]
+pub struct FogOverlay(pub Handle<Image>);
+
+type VisionSource<'a> = (&'a Transform, Option<&'a Vision>, Option<&'a ShapeType>, Has<Enemy>, Has<EnemyTower>);
+type FoggedEnemy<'a> = (Entity, &'a Transform, &'a mut Visibility, Has<CanShoot>, Has<Tower>, Has<FogHidden>);
+type EnemyFilter = Or<(With<Enemy>, With<EnemyTower>)>;
+
+/// Recomputes what each side sees; the resource is marked changed only when the picture changes
+pub fn update_fog_of_war(
+    mut fog: ResMut<FogOfWar>,
+    sources: Query<VisionSource, With<Health>>,
+) {
+    let mut player = fog.player.clone();
+    let mut enemy = fog.enemy.clone();
+    player.visible.fill(false);
+    enemy.visible.fill(false);
+
+    for (transform, vision, shape, is_enemy, is_enemy_tower) in sources.iter() {
+        let radius = vision.map_or(default_vision(shape), |vision| vision.0);
+        let side_vision = match AISide::of(is_enemy || is_enemy_tower) {
+            AISide::Player => &mut player,
+            AISide::Enemy => &mut enemy,
+        };
+        fog.reveal(side_vision, transform.translation, radius);
+    }
+
+    if player != fog.player || enemy != fog.enemy {
+        fog.player = player;
+        fog.enemy = enemy;
+    }
+}
+
+/// Hides enemy units outside the player's sight; enemy buildings stay once their spot was explored
+/// and towers are always on the map
+pub fn hide_fogged_enemies(
+    mut commands: Commands,
+    fog: Res<FogOfWar>,
+    mut enemies: Query<FoggedEnemy, EnemyFilter>,
+) {
+    for (entity, transform, mut visibility, armed, is_tower, hidden) in enemies.iter_mut() {
+        let shown = fog.revealed || fog.knows(AISide::Player, transform.translation, TargetKind::of(armed, is_tower));
+
+        if shown && hidden {
+            *visibility = Visibility::Inherited;
+            commands.entity(entity).remove::<FogHidden>();
+        } else if !shown && !hidden {
+            *visibility = Visibility::Hidden;
+            commands.entity(entity).insert(FogHidden);
+        }
+    }
+}
+
+/// Spawns the fog layer right above the ground plane
+pub fn spawn_fog_overlay(
+    mut commands: Commands,
+    fog: Res<FogOfWar>,
+    mut images: ResMut<Assets<Image>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let size = Extent3d { width: fog.size as u32, height: fog.size as u32, depth_or_array_layers: 1 };
+    let mut image = Image::new_fill(
+        size,
+        TextureDimension::D2,
+        &[0, 0, 0, UNEXPLORED_ALPHA],
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::default(),
+    );
+    // Линейная фильтрация размывает границу видимости
+    image.sampler = ImageSampler::linear();
+    let image = images.add(image);
+
+    let extent = NAV_GRID_HALF_EXTENT * 2.0;
+    commands.spawn((
+        PbrBundle {
+            mesh: meshes.add(Plane3d::default().mesh().size(extent, extent)),
+            material: materials.add(StandardMaterial {
+                base_color_texture: Some(image.clone()),
+                alpha_mode: AlphaMode::Blend,
+                unlit: true,
+                ..default()
+            }),
+            transform: Transform::from_xyz(0.0, FOG_OVERLAY_HEIGHT, 0.0),
+            ..default()
+        },
+        // Туман не должен перехватывать клики по земле
+        Pickable::IGNORE,
+        FogOverlay(image),
+        OnGameScreen,
+    ));
+    info!("🌫️ Fog of war overlay spawned ({}x{} cells)", fog.size, fog.size);
+}
+
+/// Copies the player's fog into the overlay texture whenever it changes
+pub fn update_fog_overlay(
+    fog: Res<FogOfWar>,
+    overlays: Query<Ref<FogOverlay>>,
+    mut images: ResMut<Assets<Image>>,
+) {
+    for overlay in overlays.iter() {
+        // Новый слой (например, после паузы) рисуется сразу, иначе - только при изменении тумана
+        if !fog.is_changed() && !overlay.is_added() {
+            continue;
+        }
+        let Some(image) = images.get_mut(&overlay.0) else {
+            continue;
+        };
+        let vision = fog.vision(AISide::Player);
+        for (index, pixel) in image.data.chunks_exact_mut(4).enumerate() {
+            pixel[3] = if fog.revealed || vision.visible[index] {
+                VISIBLE_ALPHA
+            } else if vision.explored[index] {
+                EXPLORED_ALPHA
+            } else {
+                UNEXPLORED_ALPHA
+            };
+        }
+    }
+}
+
+/// Registers the fog of war itself; it is updated before the AI picks targets.
+/// The overlay and hiding of enemies are added by the game app only, headless matches need no rendering.
+pub struct FogOfWarPlugin;
+
+impl Plugin for FogOfWarPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<FogOfWar>()
+            .add_systems(PreUpdate, update_fog_of_war.before(big_brain::prelude::BigBrainSet::Scorers));
+    }
+}
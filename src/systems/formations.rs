@@ -0,0 +1,203 @@
+use bevy::prelude::*;
+
+use crate::game::{Aircraft, Tank};
+use crate::game::units::infantry::Infantry;
+use crate::game::units::{move_speed, MilitaryUnit};
+use crate::systems::turn_system::{PlayerTurn, TurnState};
+use crate::input::key_bindings::{InputAction, KeyBindings};
+
+/// Расстояние между соседними местами в строю
+pub const FORMATION_SPACING: f32 = 2.0;
+/// Сколько юнитов помещается в одну шеренгу смешанного строя
+const MIXED_RANK_WIDTH: usize = 5;
+
+/// Layout used to spread a group order around the clicked point
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum FormationKind {
+    #[default]
+    Line,
+    Column,
+    Wedge,
+    /// Пехота впереди, танки сзади
+    Mixed,
+}
+
+impl FormationKind {
+    pub fn next(self) -> Self {
+        match self {
+            FormationKind::Line => FormationKind::Column,
+            FormationKind::Column => FormationKind::Wedge,
+            FormationKind::Wedge => FormationKind::Mixed,
+            FormationKind::Mixed => FormationKind::Line,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            FormationKind::Line => "Line",
+            FormationKind::Column => "Column",
+            FormationKind::Wedge => "Wedge",
+            FormationKind::Mixed => "Mixed",
+        }
+    }
+}
+
+/// Formation for group orders: N cycles the layout, U toggles "move together"
+#[derive(Resource, Default)]
+pub struct FormationSettings {
+    pub kind: FormationKind,
+    pub move_together: bool,
+}
+
+/// Speed cap of a unit marching with its group - the pace of the slowest member
+#[derive(Component, Clone, Copy)]
+pub struct FormationSpeed(pub f32);
+
+/// Place of a unit in the mixed formation, front to back
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum FormationRole {
+    Infantry,
+    Support,
+    Tank,
+}
+
+/// One unit taking part in a group order
+#[derive(Clone, Copy, Debug)]
+pub struct FormationMember {
+    pub entity: Entity,
+    pub position: Vec3,
+    pub role: FormationRole,
+    pub speed: f32,
+}
+
+/// Units that line up in formations
+pub type FormationMemberFilter = Or<(With<Infantry>, With<Tank>, With<Aircraft>)>;
+pub type FormationMemberData<'a> = (
+    Entity,
+    &'a Transform,
+    Option<&'a MilitaryUnit>,
+    Option<&'a Aircraft>,
+    Has<Infantry>,
+    Has<Tank>,
+);
+
+impl FormationMember {
+    pub fn from_query(
+        (entity, transform, military_unit, aircraft, is_infantry, is_tank): (Entity, &Transform, Option<&MilitaryUnit>, Option<&Aircraft>, bool, bool),
+    ) -> Self {
+        let role = if is_infantry {
+            FormationRole::Infantry
+        } else if is_tank {
+            FormationRole::Tank
+        } else {
+            FormationRole::Support
+        };
+        // Самолеты летают со своей скоростью, если юнит не из каталога
+        let speed = match aircraft {
+            Some(aircraft) => military_unit.map_or(aircraft.speed, |unit| unit.speed),
+            None => move_speed(military_unit),
+        };
+        Self { entity, position: transform.translation, role, speed }
+    }
+}
+
+/// Pace of the slowest member of the group
+pub fn group_pace(members: &[FormationMember]) -> Option<f32> {
+    members.iter().map(|member| member.speed).min_by(f32::total_cmp)
+}
+
+/// Spreads the group around `target`: a destination for every member.
+/// The formation faces from the group's center towards the target.
+pub fn plan_formation(kind: FormationKind, target: Vec3, members: &[FormationMember]) -> Vec<(Entity, Vec3)> {
+    if members.is_empty() {
+        return Vec::new();
+    }
+
+    let center = members.iter().map(|member| member.position).sum::<Vec3>() / members.len() as f32;
+    let to_target = Vec3::new(target.x - center.x, 0.0, target.z - center.z);
+    let forward = if to_target.length_squared() > 0.01 { to_target.normalize() } else { Vec3::NEG_Z };
+    let right = Vec3::new(-forward.z, 0.0, forward.x);
+    let to_world = |offset: Vec2| target + (right * offset.x - forward * offset.y) * FORMATION_SPACING;
+
+    if kind != FormationKind::Mixed {
+        let slots: Vec<Vec3> = formation_offsets(kind, members.len()).into_iter().map(to_world).collect();
+        return assign_slots(members, &slots);
+    }
+
+    // Смешанный строй: каждая роль занимает свои шеренги, пехота первой
+    let mut sorted = members.to_vec();
+    sorted.sort_by_key(|member| member.role);
+    let mut plan = Vec::with_capacity(members.len());
+    let mut first_rank = 0;
+    for group in sorted.chunk_by(|a, b| a.role == b.role) {
+        let slots: Vec<Vec3> = rank_offsets(group.len(), MIXED_RANK_WIDTH, first_rank).into_iter().map(to_world).collect();
+        plan.extend(assign_slots(group, &slots));
+        first_rank += group.len().div_ceil(MIXED_RANK_WIDTH);
+    }
+    plan
+}
+
+/// Slot offsets in formation space: x - to the right, y - back from the target, in spacings
+fn formation_offsets(kind: FormationKind, count: usize) -> Vec<Vec2> {
+    match kind {
+        FormationKind::Line | FormationKind::Mixed => rank_offsets(count, count, 0),
+        FormationKind::Column => rank_offsets(count, 1, 0),
+        // Клин: ведущий в точке приказа, остальные расходятся назад по обе стороны
+        FormationKind::Wedge => (0..count)
+            .map(|index| {
+                let rank = index.div_ceil(2) as f32;
+                let side = if index % 2 == 1 { -1.0 } else { 1.0 };
+                Vec2::new(side * rank, rank)
+            })
+            .collect(),
+    }
+}
+
+/// `count` slots in ranks of `width`, each rank centered, starting at `first_rank`
+fn rank_offsets(count: usize, width: usize, first_rank: usize) -> Vec<Vec2> {
+    let width = width.max(1);
+    (0..count)
+        .map(|index| {
+            let rank = index / width;
+            let in_rank = width.min(count - rank * width);
+            let column = (index % width) as f32 - (in_rank as f32 - 1.0) / 2.0;
+            Vec2::new(column, (first_rank + rank) as f32)
+        })
+        .collect()
+}
+
+/// Front slots go first, each to the nearest unit that has no slot yet
+fn assign_slots(members: &[FormationMember], slots: &[Vec3]) -> Vec<(Entity, Vec3)> {
+    let mut free: Vec<&FormationMember> = members.iter().collect();
+    slots.iter()
+        .map(|slot| {
+            let nearest = free.iter()
+                .enumerate()
+                .min_by(|(_, a), (_, b)| a.position.distance_squared(*slot).total_cmp(&b.position.distance_squared(*slot)))
+                .map(|(index, _)| index)
+                .unwrap_or_default();
+            (free.remove(nearest).entity, *slot)
+        })
+        .collect()
+}
+
+/// N - next formation layout, U - toggle "move together"
+pub fn formation_hotkeys(
+    keys: Res<ButtonInput<KeyCode>>,
+    key_bindings: Res<KeyBindings>,
+    turn_state: Res<TurnState>,
+    mut settings: ResMut<FormationSettings>,
+) {
+    if turn_state.current_player != PlayerTurn::Human {
+        return;
+    }
+
+    if key_bindings.just_pressed(InputAction::NextFormation, &keys) {
+        settings.kind = settings.kind.next();
+        info!("🪖 Formation: {}", settings.kind.label());
+    }
+    if key_bindings.just_pressed(InputAction::ToggleMoveTogether, &keys) {
+        settings.move_together = !settings.move_together;
+        info!("🪖 Move together: {}", if settings.move_together { "on" } else { "off" });
+    }
+}
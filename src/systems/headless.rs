@@ -0,0 +1,343 @@
+use bevy::prelude::*;
+use bevy::ecs::schedule::{ExecutorKind, ScheduleLabel};
+use bevy::scene::SceneSpawner;
+use bevy::time::TimeUpdateStrategy;
+use bevy_rapier3d::prelude::{NoUserData, RapierPhysicsPlugin};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use crate::game::Health;
+use crate::game::construction::{engineer_work, finish_construction_sites};
+use crate::game::setup::{spawn_faction_towers, PLAYER_TOWER_POSITIONS, ENEMY_TOWER_POSITIONS};
+use crate::game::units::{PlayerFaction, AIFaction};
+use crate::game::units::catalog::UnitCatalog;
+use crate::game::units::armor::DamageMatrix;
+use crate::systems::ai_economy::{ai_initial_resources_system, ai_resource_generation_system};
+use crate::systems::ai_opponent::{
+    AIBehavior, AIBrainPlugin, AIDifficulty, AIPlayer, AIPlayers, AIPurchaseEvent, AISide, AIStrategy, SideResources,
+};
+use crate::systems::ai_scheduler::{tick_ai_scheduler, AIScheduler};
+use crate::systems::aircraft::aircraft_movement;
+use crate::systems::movement::process_movement_orders;
+use crate::systems::projectiles::ProjectilePlugin;
+use crate::systems::pathfinding::{PathfindingPlugin, PathfindingSet};
+use crate::systems::steering::{SteeringPlugin, SteeringSet};
+use crate::systems::tower::tower_turret_fire_system;
+use crate::systems::spatial_index::TargetIndexPlugin;
+use crate::systems::fog_of_war::FogOfWarPlugin;
+use crate::systems::turn_system::{update_turn_system, TurnState};
+use crate::systems::victory_system::{count_towers_alive, EnemyTowerFilter, PlayerTowerFilter, VictoryState};
+use crate::ui::cost_table::CostTable;
+use crate::ui::money_ui::{Money, Wood, Iron, Steel, Oil, AIMoney, AIWood, AIIron, AISteel, AIOil, ResourceCost};
+use crate::utils::{read_balance_file, BalanceLoaded, GameRng};
+
+/// Шаг симуляции - 30 кадров в секунду игрового времени
+const SIM_STEP: f32 = 1.0 / 30.0;
+
+pub const USAGE: &str = "usage: the_coalitions_war_of_empires --headless [--matches N] [--seed N] [--max-turns N] \
+[--player-strategy rusher|defender|economic|balanced] [--ai-strategy ...] \
+[--player-difficulty easy|medium|hard] [--ai-difficulty ...] [--output FILE]\n\
+One JSON summary per match is printed to stdout, or appended to FILE as JSON Lines.";
+
+/// Command line options of the headless AI-vs-AI mode
+#[derive(Debug, Clone)]
+pub struct HeadlessOptions {
+    pub matches: u32,
+    pub seed: u64,
+    pub max_turns: u32,
+    pub player_strategy: AIStrategy,
+    pub ai_strategy: AIStrategy,
+    pub player_difficulty: AIDifficulty,
+    pub ai_difficulty: AIDifficulty,
+    pub output: Option<PathBuf>,
+}
+
+impl Default for HeadlessOptions {
+    fn default() -> Self {
+        Self {
+            matches: 1,
+            seed: 0,
+            max_turns: 50,
+            player_strategy: AIStrategy::Balanced,
+            ai_strategy: AIStrategy::Balanced,
+            player_difficulty: AIDifficulty::Medium,
+            ai_difficulty: AIDifficulty::Medium,
+            output: None,
+        }
+    }
+}
+
+impl HeadlessOptions {
+    pub fn from_args(args: &[String]) -> Result<Self, String> {
+        let mut options = Self::default();
+        let mut args = args.iter();
+
+        while let Some(arg) = args.next() {
+            let mut value = || args.next().ok_or_else(|| format!("missing value for {}", arg));
+            match arg.as_str() {
+                "--headless" => {}
+                "--matches" => options.matches = parse_number(arg, value()?)?,
+                "--seed" => options.seed = parse_number(arg, value()?)?,
+                "--max-turns" => options.max_turns = parse_number(arg, value()?)?,
+                "--player-strategy" => options.player_strategy = parse_strategy(value()?)?,
+                "--ai-strategy" => options.ai_strategy = parse_strategy(value()?)?,
+                "--player-difficulty" => options.player_difficulty = parse_difficulty(value()?)?,
+                "--ai-difficulty" => options.ai_difficulty = parse_difficulty(value()?)?,
+                "--output" => options.output = Some(PathBuf::from(value()?)),
+                other => return Err(format!("unknown argument {}", other)),
+            }
+        }
+
+        Ok(options)
+    }
+}
+
+fn parse_number<T: std::str::FromStr>(flag: &str, value: &str) -> Result<T, String> {
+    value.parse().map_err(|_| format!("invalid value for {}: {}", flag, value))
+}
+
+fn parse_strategy(value: &str) -> Result<AIStrategy, String> {
+    AIStrategy::from_name(value).ok_or_else(|| format!("unknown strategy {}", value))
+}
+
+fn parse_difficulty(value: &str) -> Result<AIDifficulty, String> {
+    AIDifficulty::from_name(value).ok_or_else(|| format!("unknown difficulty {}", value))
+}
+
+/// Итоги одного матча
+#[derive(Serialize, Debug)]
+pub struct MatchSummary {
+    pub match_index: u32,
+    pub seed: u64,
+    /// None - ничья по лимиту ходов
+    pub winner: Option<AISide>,
+    pub turns: u32,
+    pub player_strategy: AIStrategy,
+    pub ai_strategy: AIStrategy,
+    pub player_difficulty: AIDifficulty,
+    pub ai_difficulty: AIDifficulty,
+    pub units_bought: UnitsBought,
+    pub resources: Vec<ResourceSample>,
+}
+
+/// Purchases per side, keyed by item name
+#[derive(Serialize, Debug, Default)]
+pub struct UnitsBought {
+    pub player: BTreeMap<String, u32>,
+    pub enemy: BTreeMap<String, u32>,
+}
+
+/// Resources of both sides at the start of a turn
+#[derive(Serialize, Debug)]
+pub struct ResourceSample {
+    pub turn: u32,
+    pub player: ResourceCost,
+    pub enemy: ResourceCost,
+}
+
+/// Накопленная статистика текущего матча
+#[derive(Resource, Default)]
+struct MatchRecord {
+    winner: Option<AISide>,
+    finished: bool,
+    units_bought: UnitsBought,
+    resources: Vec<ResourceSample>,
+    last_sampled_turn: u32,
+}
+
+/// Plays all requested matches and writes one JSON line per match.
+/// Fails before the first match if a balance file or the output file cannot be opened.
+pub fn run_headless(options: &HeadlessOptions) -> Result<(), String> {
+    let catalog = read_balance_file::<UnitCatalog>()?;
+    let costs = read_balance_file::<CostTable>()?;
+    let damage_matrix = read_balance_file::<DamageMatrix>()?;
+
+    let mut output = match &options.output {
+        Some(path) => Some(
+            std::fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)
+                .map_err(|err| format!("could not open {}: {}", path.display(), err))?,
+        ),
+        None => None,
+    };
+
+    for match_index in 0..options.matches {
+        let seed = options.seed.wrapping_add(match_index as u64);
+        let summary = run_match(options, match_index, seed, catalog.clone(), costs.clone(), damage_matrix.clone());
+
+        let line = match serde_json::to_string(&summary) {
+            Ok(line) => line,
+            Err(err) => {
+                eprintln!("❌ Could not serialize match summary: {}", err);
+                continue;
+            }
+        };
+        match output.as_mut() {
+            Some(file) => {
+                if let Err(err) = writeln!(file, "{}", line) {
+                    eprintln!("❌ Could not write match summary: {}", err);
+                }
+            }
+            None => println!("{}", line),
+        }
+    }
+    Ok(())
+}
+
+fn run_match(
+    options: &HeadlessOptions,
+    match_index: u32,
+    seed: u64,
+    catalog: UnitCatalog,
+    costs: CostTable,
+    damage_matrix: DamageMatrix,
+) -> MatchSummary {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .add_plugins(AssetPlugin {
+            watch_for_changes_override: Some(false),
+            ..default()
+        })
+        // Модели и звуки не загружаются, но их хэндлы создаются функциями спавна
+        .init_asset::<Scene>()
+        .init_asset::<bevy::audio::AudioSource>()
+        // Снаряды попадают по коллайдерам rapier, как и в обычной игре
+        .init_asset::<Mesh>()
+        // Стройплощадки зданий ИИ создаются с мешем и материалом
+        .init_asset::<StandardMaterial>()
+        .init_resource::<SceneSpawner>()
+        .add_plugins((TransformPlugin, HierarchyPlugin, RapierPhysicsPlugin::<NoUserData>::default()))
+        .add_plugins((ProjectilePlugin, PathfindingPlugin, SteeringPlugin, TargetIndexPlugin, FogOfWarPlugin))
+        .configure_sets(Update, SteeringSet.after(PathfindingSet))
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f32(SIM_STEP)))
+        .insert_resource(GameRng::seeded(seed))
+        .insert_resource(catalog)
+        .insert_resource(costs)
+        .insert_resource(damage_matrix)
+        // Файлы баланса прочитаны заранее, ждать asset server не нужно
+        .init_resource::<BalanceLoaded<UnitCatalog>>()
+        .init_resource::<BalanceLoaded<CostTable>>()
+        .init_resource::<BalanceLoaded<DamageMatrix>>()
+        .insert_resource(AIPlayers(vec![
+            AIPlayer::new(AISide::Player, AIBehavior::new(options.player_difficulty, options.player_strategy)),
+            AIPlayer::new(AISide::Enemy, AIBehavior::new(options.ai_difficulty, options.ai_strategy)),
+        ]))
+        .init_resource::<TurnState>()
+        .init_resource::<VictoryState>()
+        .init_resource::<PlayerFaction>()
+        .init_resource::<AIFaction>()
+        .init_resource::<Money>()
+        .init_resource::<Wood>()
+        .init_resource::<Iron>()
+        .init_resource::<Steel>()
+        .init_resource::<Oil>()
+        .init_resource::<AIMoney>()
+        .init_resource::<AIWood>()
+        .init_resource::<AIIron>()
+        .init_resource::<AISteel>()
+        .init_resource::<AIOil>()
+        .init_resource::<AIScheduler>()
+        .init_resource::<MatchRecord>()
+        .add_event::<AIPurchaseEvent>()
+        .add_plugins(AIBrainPlugin)
+        .add_systems(Startup, (spawn_headless_towers, ai_initial_resources_system))
+        .add_systems(
+            Update,
+            (
+                update_turn_system,
+                ai_resource_generation_system,
+                tick_ai_scheduler,
+                process_movement_orders,
+                aircraft_movement,
+                engineer_work,
+                finish_construction_sites,
+                tower_turret_fire_system,
+                record_match_system,
+            ).chain().after(SteeringSet),
+        );
+
+    // Однопоточное выполнение, чтобы матч с одним и тем же seed повторялся
+    for label in [Startup.intern(), PreUpdate.intern(), Update.intern(), PostUpdate.intern(), Last.intern()] {
+        app.edit_schedule(label, |schedule| {
+            schedule.set_executor_kind(ExecutorKind::SingleThreaded);
+        });
+    }
+
+    app.finish();
+    app.cleanup();
+
+    loop {
+        app.update();
+        if app.world.resource::<MatchRecord>().finished
+            || app.world.resource::<TurnState>().turn_number > options.max_turns
+        {
+            break;
+        }
+    }
+
+    let turns = app.world.resource::<TurnState>().turn_number.min(options.max_turns);
+    let record = std::mem::take(&mut *app.world.resource_mut::<MatchRecord>());
+    MatchSummary {
+        match_index,
+        seed,
+        winner: record.winner,
+        turns,
+        player_strategy: options.player_strategy,
+        ai_strategy: options.ai_strategy,
+        player_difficulty: options.player_difficulty,
+        ai_difficulty: options.ai_difficulty,
+        units_bought: record.units_bought,
+        resources: record.resources,
+    }
+}
+
+fn spawn_headless_towers(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    player_faction: Res<PlayerFaction>,
+    ai_faction: Res<AIFaction>,
+) {
+    let [left, center, right] = PLAYER_TOWER_POSITIONS;
+    spawn_faction_towers(&mut commands, &asset_server, player_faction.0, left, center, right, false);
+    let [left, center, right] = ENEMY_TOWER_POSITIONS;
+    spawn_faction_towers(&mut commands, &asset_server, ai_faction.0, left, center, right, true);
+}
+
+/// Collects purchases, samples resources once per turn and decides the winner
+fn record_match_system(
+    turn_state: Res<TurnState>,
+    mut resources: SideResources,
+    mut purchases: EventReader<AIPurchaseEvent>,
+    mut record: ResMut<MatchRecord>,
+    mut victory_state: ResMut<VictoryState>,
+    player_towers: Query<&Health, PlayerTowerFilter>,
+    enemy_towers: Query<&Health, EnemyTowerFilter>,
+) {
+    for purchase in purchases.read() {
+        let bought = match purchase.side {
+            AISide::Player => &mut record.units_bought.player,
+            AISide::Enemy => &mut record.units_bought.enemy,
+        };
+        *bought.entry(format!("{:?}", purchase.item)).or_default() += 1;
+    }
+
+    if turn_state.turn_number != record.last_sampled_turn {
+        record.last_sampled_turn = turn_state.turn_number;
+        record.resources.push(ResourceSample {
+            turn: turn_state.turn_number,
+            player: resources.wallet(AISide::Player).amounts(),
+            enemy: resources.wallet(AISide::Enemy).amounts(),
+        });
+    }
+
+    let (player_towers_alive, enemy_towers_alive) = count_towers_alive(&player_towers, &enemy_towers);
+    if let Some(winner) = victory_state.tower_winner(player_towers_alive, enemy_towers_alive) {
+        record.winner = Some(winner);
+        record.finished = true;
+    }
+}
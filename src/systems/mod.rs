@@ -5,10 +5,20 @@ pub mod movement;
 pub mod turn_system;
 pub mod ai_economy;
 pub mod ai_opponent;
+pub mod ai_scheduler;
 pub mod victory_system;
 pub mod cheat_system;
 pub mod enemy_visual_markers;
 pub mod twitter_client;
+pub mod save_load;
+pub mod headless;
+pub mod projectiles;
+pub mod stances;
+pub mod formations;
+pub mod pathfinding;
+pub mod steering;
+pub mod spatial_index;
+pub mod fog_of_war;
 
 pub use aircraft::*;
 #[allow(unused_imports)]
@@ -1,4 +1,10 @@
-use crate::game::MovementOrder;
+use crate::game::{AttackMove, Enemy, MovementOrder};
+use crate::game::units::MilitaryUnit;
+use crate::systems::ai_opponent::AIPlayers;
+use crate::systems::formations::FormationSpeed;
+use crate::systems::pathfinding::{MovementPath, NavGrid};
+use crate::systems::steering::{order_speed, unit_radius, UnitSpatialHash};
+use crate::systems::turn_system::TurnState;
 use bevy::prelude::*;
 
 /// Marker component for tanks that are currently playing movement sound
@@ -9,22 +15,59 @@ pub struct MovingTank;
 #[derive(Component)]
 pub struct TankMovementAudio;
 
-/// processing movement orders
+type MovingUnit<'a> = (
+    Entity,
+    &'a mut Transform,
+    &'a MovementOrder,
+    Option<&'a MilitaryUnit>,
+    Option<&'a FormationSpeed>,
+    Option<&'a mut MovementPath>,
+    Has<Enemy>,
+);
+
+/// processing movement orders (player and AI units alike), with local avoidance of neighbours
+#[allow(clippy::too_many_arguments)]
 pub fn process_movement_orders(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
-    mut query: Query<(Entity, &mut Transform, &MovementOrder), Without<crate::game::components::Aircraft>>,
+    spatial_hash: Res<UnitSpatialHash>,
+    nav_grid: Res<NavGrid>,
+    turn_state: Res<TurnState>,
+    ai_players: Res<AIPlayers>,
+    mut query: Query<MovingUnit, Without<crate::game::components::Aircraft>>,
     tank_query: Query<Entity, With<crate::game::Tank>>,
     moving_tank_query: Query<Entity, With<MovingTank>>,
     time: Res<Time>,
 ) {
-    for (entity, mut transform, movement_order) in query.iter_mut() {
-        let target = movement_order.0;
-        let speed = 2.0;
+    for (entity, mut transform, movement_order, military_unit, formation_speed, mut path, is_enemy) in query.iter_mut() {
+        // Юниты ИИ ждут своего хода
+        if !ai_players.may_move(is_enemy, &turn_state) {
+            continue;
+        }
+        // Идем по маршруту в обход зданий; последняя точка маршрута - сама цель приказа
+        let waypoint = path.as_ref().and_then(|path| path.waypoint_for(movement_order));
+        let target = waypoint.unwrap_or(movement_order.0);
+        let intermediate = waypoint.is_some_and(|waypoint| waypoint != movement_order.0);
+        // Каждый юнит движется со своей скоростью из каталога, в строю - не быстрее самого медленного
+        let speed = order_speed(military_unit, formation_speed);
         let direction = target - transform.translation;
+        let radius = unit_radius(tank_query.get(entity).is_ok());
+
+        // Цель заняла стоящая там другая часть - останавливаемся рядом, а не толкаемся
+        let goal_taken = !intermediate
+            && Vec3::new(direction.x, 0.0, direction.z).length() < radius * 2.0 + 1.0
+            && spatial_hash.goal_taken(entity, target, radius);
 
-        if direction.length_squared() > 0.01 {
+        if direction.length_squared() > 0.01 && !goal_taken {
             let movement_this_frame = direction.normalize() * speed * time.delta_seconds();
+            // Обходим соседей, но не заходим в клетки, занятые зданиями
+            let steered = spatial_hash.steer(entity, transform.translation, direction.normalize() * speed, radius)
+                * time.delta_seconds();
+            let steered_step = if nav_grid.is_blocked(nav_grid.cell_of(transform.translation + steered)) {
+                movement_this_frame
+            } else {
+                steered
+            };
             let xz_direction = Vec3::new(direction.x, 0.0, direction.z).normalize();
 
             if xz_direction.length_squared() > 0.001 {
@@ -46,20 +89,35 @@ pub fn process_movement_orders(
                 info!("🚗 Tank movement sound started");
             }
 
-            if movement_this_frame.length_squared() >= direction.length_squared() {
+            if movement_this_frame.length_squared() >= direction.length_squared() && intermediate {
+                transform.translation = target;
+                if let Some(path) = path.as_mut() {
+                    path.advance();
+                }
+            } else if movement_this_frame.length_squared() >= direction.length_squared() {
                 transform.translation = target;
-                commands.entity(entity).remove::<MovementOrder>();
+                commands.entity(entity).remove::<(MovementOrder, FormationSpeed, MovementPath)>();
                 // Убираем компонент движения танка когда достигли цели
                 if tank_query.get(entity).is_ok() {
                     commands.entity(entity).remove::<MovingTank>();
                 }
                 info!("process_movement_orders: Object {:?} reached goal", entity);
             } else {
-                transform.translation += movement_this_frame;
+                transform.translation += steered_step;
             }
-        } else {
+        } else if intermediate {
             transform.translation = target;
-            commands.entity(entity).remove::<MovementOrder>();
+            if let Some(path) = path.as_mut() {
+                path.advance();
+            }
+        } else {
+            if goal_taken {
+                // Рядом с занятой точкой атакующий марш тоже закончен, иначе он снова выдаст тот же приказ
+                commands.entity(entity).remove::<AttackMove>();
+            } else {
+                transform.translation = target;
+            }
+            commands.entity(entity).remove::<(MovementOrder, FormationSpeed, MovementPath)>();
             // Убираем компонент движения танка когда достигли цели (для случая близкой цели)
             if tank_query.get(entity).is_ok() {
                 commands.entity(entity).remove::<MovingTank>();
@@ -0,0 +1,311 @@
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+use bevy::prelude::*;
+
+use crate::game::{Aircraft, MovementOrder, ShapeType};
+
+/// Половина стороны игрового поля (плоскость земли 120x120 из setup)
+pub const NAV_GRID_HALF_EXTENT: f32 = 60.0;
+/// Размер клетки сетки проходимости
+pub const NAV_CELL_SIZE: f32 = 1.0;
+/// Запас вокруг зданий, чтобы юниты не задевали углы
+const UNIT_CLEARANCE: f32 = 0.5;
+/// Радиус поиска свободной клетки рядом с занятой целью (в клетках)
+const FREE_CELL_SEARCH_RADIUS: i32 = 12;
+
+/// Systems that keep the occupancy grid current and turn orders into paths
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PathfindingSet;
+
+/// Occupancy grid of the battlefield: cells covered by buildings, towers and trenches are blocked
+#[derive(Resource)]
+pub struct NavGrid {
+    pub size: i32,
+    blocked: Vec<bool>,
+}
+
+impl Default for NavGrid {
+    fn default() -> Self {
+        let size = (NAV_GRID_HALF_EXTENT * 2.0 / NAV_CELL_SIZE).ceil() as i32;
+        Self { size, blocked: vec![false; (size * size) as usize] }
+    }
+}
+
+type Cell = IVec2;
+
+impl NavGrid {
+    pub fn cell_of(&self, position: Vec3) -> Cell {
+        let cell = ((Vec2::new(position.x, position.z) + NAV_GRID_HALF_EXTENT) / NAV_CELL_SIZE).floor();
+        cell.as_ivec2().clamp(IVec2::ZERO, IVec2::splat(self.size - 1))
+    }
+
+    pub fn cell_center(&self, cell: Cell, height: f32) -> Vec3 {
+        let center = (cell.as_vec2() + 0.5) * NAV_CELL_SIZE - NAV_GRID_HALF_EXTENT;
+        Vec3::new(center.x, height, center.y)
+    }
+
+    fn in_bounds(&self, cell: Cell) -> bool {
+        cell.x >= 0 && cell.y >= 0 && cell.x < self.size && cell.y < self.size
+    }
+
+    fn index(&self, cell: Cell) -> usize {
+        (cell.y * self.size + cell.x) as usize
+    }
+
+    pub fn is_blocked(&self, cell: Cell) -> bool {
+        !self.in_bounds(cell) || self.blocked[self.index(cell)]
+    }
+
+    /// Marks every cell whose center lies inside the (rotated) footprint
+    fn block_footprint(&self, blocked: &mut [bool], transform: &Transform, half_extents: Vec2) {
+        let half_extents = half_extents + UNIT_CLEARANCE;
+        let radius = half_extents.length();
+        let min = self.cell_of(transform.translation - Vec3::new(radius, 0.0, radius));
+        let max = self.cell_of(transform.translation + Vec3::new(radius, 0.0, radius));
+        let inverse_rotation = transform.rotation.inverse();
+
+        for y in min.y..=max.y {
+            for x in min.x..=max.x {
+                let cell = IVec2::new(x, y);
+                let local = inverse_rotation * (self.cell_center(cell, transform.translation.y) - transform.translation);
+                if local.x.abs() <= half_extents.x && local.z.abs() <= half_extents.y {
+                    blocked[self.index(cell)] = true;
+                }
+            }
+        }
+    }
+
+    /// Straight walk from `from` to `to` crosses no blocked cell (except the two end cells)
+    fn line_of_sight(&self, from: Vec3, to: Vec3) -> bool {
+        let (from_cell, to_cell) = (self.cell_of(from), self.cell_of(to));
+        let distance = Vec2::new(to.x - from.x, to.z - from.z).length();
+        let steps = (distance / (NAV_CELL_SIZE * 0.25)).ceil().max(1.0) as i32;
+        (1..steps).all(|step| {
+            let cell = self.cell_of(from.lerp(to, step as f32 / steps as f32));
+            cell == from_cell || cell == to_cell || !self.is_blocked(cell)
+        })
+    }
+
+    /// Nearest free cell to a blocked one (the goal is inside a building - we walk up to it)
+    fn nearest_free_cell(&self, cell: Cell, towards: Cell) -> Option<Cell> {
+        (1..=FREE_CELL_SEARCH_RADIUS).find_map(|radius| {
+            (-radius..=radius)
+                .flat_map(|dy| (-radius..=radius).map(move |dx| IVec2::new(dx, dy)))
+                .filter(|offset| offset.x.abs() == radius || offset.y.abs() == radius)
+                .map(|offset| cell + offset)
+                .filter(|candidate| !self.is_blocked(*candidate))
+                .min_by_key(|candidate| (*candidate - towards).length_squared())
+        })
+    }
+
+    /// A* over the grid (8 directions, no corner cutting) followed by line-of-sight smoothing.
+    /// Returns the waypoints after `from`, ending exactly at `to`; None if there is no way.
+    pub fn find_path(&self, from: Vec3, to: Vec3) -> Option<Vec<Vec3>> {
+        let start = self.cell_of(from);
+        let goal_cell = self.cell_of(to);
+        if self.line_of_sight(from, to) {
+            return Some(vec![to]);
+        }
+
+        // Цель внутри здания: ищем путь до ближайшей свободной клетки, а последний шаг делаем напрямую
+        let goal = if self.is_blocked(goal_cell) {
+            self.nearest_free_cell(goal_cell, start)?
+        } else {
+            goal_cell
+        };
+
+        let cells = self.a_star(start, goal)?;
+        // Промежуточные точки на высоте юнита, к высоте цели он выходит на последнем отрезке
+        let mut points: Vec<Vec3> = cells.into_iter().map(|cell| self.cell_center(cell, from.y)).collect();
+        points.push(to);
+        Some(self.smooth(from, &points))
+    }
+
+    fn a_star(&self, start: Cell, goal: Cell) -> Option<Vec<Cell>> {
+        let cell_count = (self.size * self.size) as usize;
+        let mut cost = vec![f32::INFINITY; cell_count];
+        let mut came_from: Vec<Option<Cell>> = vec![None; cell_count];
+        let mut open = BinaryHeap::new();
+
+        cost[self.index(start)] = 0.0;
+        open.push(OpenCell { cell: start, estimate: octile(start, goal) });
+
+        while let Some(OpenCell { cell, .. }) = open.pop() {
+            if cell == goal {
+                let mut path = vec![goal];
+                let mut current = goal;
+                while let Some(previous) = came_from[self.index(current)] {
+                    path.push(previous);
+                    current = previous;
+                }
+                path.pop();
+                path.reverse();
+                return Some(path);
+            }
+
+            // Из занятой клетки (юнит стоит вплотную к зданию) разрешаем выйти куда угодно
+            let leaving_blocked = self.is_blocked(cell);
+            for (dx, dy) in [(1, 0), (-1, 0), (0, 1), (0, -1), (1, 1), (1, -1), (-1, 1), (-1, -1)] {
+                let next = cell + IVec2::new(dx, dy);
+                if !self.in_bounds(next) || (!leaving_blocked && self.is_blocked(next)) {
+                    continue;
+                }
+                let diagonal = dx != 0 && dy != 0;
+                if diagonal && !leaving_blocked
+                    && (self.is_blocked(cell + IVec2::new(dx, 0)) || self.is_blocked(cell + IVec2::new(0, dy)))
+                {
+                    continue;
+                }
+
+                let next_cost = cost[self.index(cell)] + if diagonal { std::f32::consts::SQRT_2 } else { 1.0 };
+                if next_cost < cost[self.index(next)] {
+                    let index = self.index(next);
+                    cost[index] = next_cost;
+                    came_from[index] = Some(cell);
+                    open.push(OpenCell { cell: next, estimate: next_cost + octile(next, goal) });
+                }
+            }
+        }
+        None
+    }
+
+    /// Drops every waypoint that can be skipped in a straight line
+    fn smooth(&self, from: Vec3, points: &[Vec3]) -> Vec<Vec3> {
+        let mut smoothed = Vec::new();
+        let mut anchor = from;
+        let mut index = 0;
+        while index < points.len() {
+            let mut farthest = index;
+            while farthest + 1 < points.len() && self.line_of_sight(anchor, points[farthest + 1]) {
+                farthest += 1;
+            }
+            anchor = points[farthest];
+            smoothed.push(anchor);
+            index = farthest + 1;
+        }
+        smoothed
+    }
+}
+
+fn octile(from: Cell, to: Cell) -> f32 {
+    let delta = (to - from).abs();
+    let (straight, diagonal) = (delta.x.max(delta.y), delta.x.min(delta.y));
+    (straight - diagonal) as f32 + diagonal as f32 * std::f32::consts::SQRT_2
+}
+
+/// Open list entry: the heap pops the smallest estimate first, ties go to the smaller cell for repeatable paths
+#[derive(PartialEq)]
+struct OpenCell {
+    cell: Cell,
+    estimate: f32,
+}
+
+impl Eq for OpenCell {}
+
+impl Ord for OpenCell {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.estimate.total_cmp(&self.estimate)
+            .then_with(|| (other.cell.y, other.cell.x).cmp(&(self.cell.y, self.cell.x)))
+    }
+}
+
+impl PartialOrd for OpenCell {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Route of a ground unit to its `MovementOrder`: the movement systems walk the waypoints in turn
+#[derive(Component, Clone, Debug)]
+pub struct MovementPath {
+    /// Приказ, для которого построен маршрут
+    pub destination: Vec3,
+    pub waypoints: Vec<Vec3>,
+    pub next: usize,
+}
+
+impl MovementPath {
+    /// Waypoint the unit is heading to, if the path was built for this order
+    pub fn waypoint_for(&self, order: &MovementOrder) -> Option<Vec3> {
+        (self.destination == order.0).then(|| self.waypoints.get(self.next).copied()).flatten()
+    }
+
+    pub fn advance(&mut self) {
+        self.next += 1;
+    }
+}
+
+/// Half extents of the ground footprint of a structure; units are not blocked
+pub fn structure_footprint(shape: ShapeType) -> Option<Vec2> {
+    match shape {
+        ShapeType::Tower => Some(Vec2::splat(2.0)),
+        ShapeType::Farm | ShapeType::Mine | ShapeType::SteelFactory | ShapeType::PetrochemicalPlant => Some(Vec2::splat(1.0)),
+        ShapeType::Trench => Some(Vec2::new(1.0, 0.75)),
+        ShapeType::Cube | ShapeType::Infantry | ShapeType::Airplane => None,
+    }
+}
+
+type PlacedOrMoved = Or<(Added<ShapeType>, Changed<Transform>)>;
+
+/// Rebuilds the grid when structures are placed, moved or destroyed.
+/// Коллайдеры зданий увеличены для удобного клика, поэтому занятость считается по реальному размеру здания.
+pub fn update_nav_grid(
+    mut nav_grid: ResMut<NavGrid>,
+    structures: Query<(&Transform, &ShapeType)>,
+    changed: Query<&ShapeType, PlacedOrMoved>,
+    mut removed: RemovedComponents<ShapeType>,
+) {
+    let structure_changed = changed.iter().any(|shape| structure_footprint(*shape).is_some());
+    let structure_removed = removed.read().count() > 0;
+    if !structure_changed && !structure_removed {
+        return;
+    }
+
+    let mut blocked = vec![false; nav_grid.blocked.len()];
+    for (transform, shape) in structures.iter() {
+        if let Some(half_extents) = structure_footprint(*shape) {
+            nav_grid.block_footprint(&mut blocked, transform, half_extents);
+        }
+    }
+    // Маршруты перестраиваются только если занятость действительно изменилась
+    if blocked != nav_grid.blocked {
+        nav_grid.blocked = blocked;
+    }
+}
+
+/// Builds a path for every new ground order, and re-plans all routes after the grid changed
+pub fn plan_movement_paths(
+    mut commands: Commands,
+    nav_grid: Res<NavGrid>,
+    orders: Query<(Entity, &Transform, Ref<MovementOrder>), Without<Aircraft>>,
+) {
+    let replan_all = nav_grid.is_changed();
+
+    for (entity, transform, order) in orders.iter() {
+        if !replan_all && !order.is_changed() {
+            continue;
+        }
+
+        // Без пути юнит идет к цели напрямую, как раньше
+        match nav_grid.find_path(transform.translation, order.0) {
+            Some(waypoints) => {
+                commands.entity(entity).insert(MovementPath { destination: order.0, waypoints, next: 0 });
+            }
+            None => {
+                commands.entity(entity).remove::<MovementPath>();
+                info!("🧭 No path for {:?} to {:?}, moving straight", entity, order.0);
+            }
+        }
+    }
+}
+
+/// Registers the occupancy grid and path planning
+pub struct PathfindingPlugin;
+
+impl Plugin for PathfindingPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<NavGrid>()
+            .add_systems(Update, (update_nav_grid, plan_movement_paths).chain().in_set(PathfindingSet));
+    }
+}
@@ -0,0 +1,283 @@
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+use rand::Rng;
+
+use crate::game::{Enemy, EnemyTower, Garrisoned, Health, LinkedToEnemy};
+use crate::game::trench::TRENCH_DAMAGE_TAKEN;
+use crate::game::scene_colliders::ChildOfClickable;
+use crate::utils::GameRng;
+
+/// Ускорение свободного падения для снарядов и бомб
+const GRAVITY: f32 = 9.8;
+/// Снаряд, который ни во что не попал, исчезает через это время
+const PROJECTILE_LIFETIME: f32 = 6.0;
+/// Высота ствола и точки прицеливания над позицией юнита
+const MUZZLE_HEIGHT: f32 = 1.0;
+
+/// What a unit fires
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProjectileKind {
+    /// Пули пехоты - быстрые и летят по прямой
+    Bullet,
+    /// Снаряды танков - летят по баллистической дуге
+    Shell,
+    /// Бомбы авиации - падают с высоты
+    Bomb,
+}
+
+impl ProjectileKind {
+    pub fn for_shooter(is_tank: bool, is_aircraft: bool) -> Self {
+        if is_tank {
+            ProjectileKind::Shell
+        } else if is_aircraft {
+            ProjectileKind::Bomb
+        } else {
+            ProjectileKind::Bullet
+        }
+    }
+
+    /// Горизонтальная скорость полета
+    fn speed(self) -> f32 {
+        match self {
+            ProjectileKind::Bullet => 40.0,
+            ProjectileKind::Shell => 22.0,
+            ProjectileKind::Bomb => 10.0,
+        }
+    }
+
+    fn gravity(self) -> f32 {
+        match self {
+            ProjectileKind::Bullet => 0.0,
+            ProjectileKind::Shell | ProjectileKind::Bomb => GRAVITY,
+        }
+    }
+}
+
+/// Kind of target, used for hit probability
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TargetClass {
+    Infantry,
+    Vehicle,
+    Aircraft,
+    Structure,
+}
+
+impl TargetClass {
+    pub fn of(is_infantry: bool, is_tank: bool, is_aircraft: bool) -> Self {
+        if is_infantry {
+            TargetClass::Infantry
+        } else if is_tank {
+            TargetClass::Vehicle
+        } else if is_aircraft {
+            TargetClass::Aircraft
+        } else {
+            TargetClass::Structure
+        }
+    }
+
+    /// Точность в упор: по зданиям почти не промахиваются, по самолетам - часто
+    fn base_accuracy(self) -> f32 {
+        match self {
+            TargetClass::Infantry => 0.75,
+            TargetClass::Vehicle => 0.9,
+            TargetClass::Aircraft => 0.55,
+            TargetClass::Structure => 0.98,
+        }
+    }
+}
+
+/// Chance to hit: base accuracy against the target class, halved at the edge of the weapon range
+pub fn hit_probability(distance: f32, range: f32, target: TargetClass) -> f32 {
+    let falloff = 1.0 - 0.5 * (distance / range.max(0.1)).clamp(0.0, 1.0);
+    target.base_accuracy() * falloff
+}
+
+/// A shell, bullet or bomb in flight
+#[derive(Component, Debug)]
+pub struct Projectile {
+    pub kind: ProjectileKind,
+    pub velocity: Vec3,
+    pub damage: f32,
+    pub shooter: Entity,
+    /// Выпущен стороной ИИ (`Enemy`) - по своим снаряды не попадают
+    pub from_enemy: bool,
+    /// Цель, мимо которой снаряд пролетает после неудачного броска на попадание
+    pub missed_target: Option<Entity>,
+    pub lifetime: f32,
+}
+
+/// Sent when a projectile hits something or the ground
+#[derive(Event, Debug, Clone, Copy)]
+pub struct ProjectileImpact {
+    pub position: Vec3,
+    pub kind: ProjectileKind,
+    pub shooter: Entity,
+    /// Сущность со здоровьем, в которую попал снаряд (None - попадание в землю)
+    pub target: Option<Entity>,
+}
+
+/// Everything needed to fire one projectile
+pub struct Shot {
+    pub shooter: Entity,
+    pub from: Vec3,
+    pub target: Entity,
+    pub target_pos: Vec3,
+    pub target_class: TargetClass,
+    pub kind: ProjectileKind,
+    pub damage: f32,
+    pub range: f32,
+    pub from_enemy: bool,
+    /// Множитель меткости стрелка (сложность ИИ, 1.0 для игрока)
+    pub accuracy: f32,
+}
+
+/// Rolls the hit and spawns the projectile; a missed shot is aimed off to the side of the target.
+/// Returns whether the roll was a hit.
+pub fn fire_projectile(commands: &mut Commands, rng: &mut GameRng, shot: Shot) -> bool {
+    let from = shot.from + Vec3::Y * MUZZLE_HEIGHT;
+    let mut aim = shot.target_pos + Vec3::Y * MUZZLE_HEIGHT;
+
+    let chance = hit_probability(from.distance(aim), shot.range, shot.target_class) * shot.accuracy;
+    let hit = rng.gen::<f32>() < chance;
+    if !hit {
+        // Разброс мимо цели в горизонтальной плоскости
+        let angle = rng.gen_range(0.0..std::f32::consts::TAU);
+        let offset = rng.gen_range(2.5..5.0);
+        aim += Vec3::new(angle.cos() * offset, 0.0, angle.sin() * offset);
+    }
+
+    commands.spawn((
+        SpatialBundle::from_transform(Transform::from_translation(from)),
+        Projectile {
+            kind: shot.kind,
+            velocity: launch_velocity(from, aim, shot.kind),
+            damage: shot.damage,
+            shooter: shot.shooter,
+            from_enemy: shot.from_enemy,
+            missed_target: (!hit).then_some(shot.target),
+            lifetime: PROJECTILE_LIFETIME,
+        },
+        Name::new(format!("{:?}", shot.kind)),
+    ));
+    hit
+}
+
+/// Начальная скорость, с которой снаряд долетает до `to` с учетом гравитации
+fn launch_velocity(from: Vec3, to: Vec3, kind: ProjectileKind) -> Vec3 {
+    let delta = to - from;
+    let horizontal = Vec3::new(delta.x, 0.0, delta.z).length();
+    let flight_time = (horizontal / kind.speed()).max(0.05);
+    delta / flight_time + Vec3::Y * (0.5 * kind.gravity() * flight_time)
+}
+
+/// Projectile systems, so the game can pause them together with the rest of the match
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ProjectileSet;
+
+pub struct ProjectilePlugin;
+
+impl Plugin for ProjectilePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<ProjectileImpact>()
+            .add_systems(Update, move_projectiles.in_set(ProjectileSet));
+    }
+}
+
+type ProjectileTarget<'a> = (&'a mut Health, Has<Enemy>, Has<EnemyTower>, Has<Garrisoned>);
+
+/// Moves projectiles and checks the path travelled this frame against rapier colliders
+#[allow(clippy::too_many_arguments)]
+pub fn move_projectiles(
+    mut commands: Commands,
+    time: Res<Time>,
+    rapier_context: Res<RapierContext>,
+    mut projectiles: Query<(Entity, &mut Transform, &mut Projectile)>,
+    mut targets: Query<ProjectileTarget>,
+    child_colliders: Query<&ChildOfClickable>,
+    linked_colliders: Query<&LinkedToEnemy>,
+    mut impacts: EventWriter<ProjectileImpact>,
+) {
+    let delta_time = time.delta_seconds();
+
+    // Клики по дочерним мешам и отдельным коллайдерам ведут к сущности со здоровьем
+    let owner_of = |collider: Entity| -> Entity {
+        if let Ok(child) = child_colliders.get(collider) {
+            child.parent
+        } else if let Ok(linked) = linked_colliders.get(collider) {
+            linked.0
+        } else {
+            collider
+        }
+    };
+
+    for (entity, mut transform, mut projectile) in projectiles.iter_mut() {
+        projectile.lifetime -= delta_time;
+        if projectile.lifetime <= 0.0 {
+            commands.entity(entity).despawn_recursive();
+            continue;
+        }
+
+        let start = transform.translation;
+        projectile.velocity.y -= projectile.kind.gravity() * delta_time;
+        let step = projectile.velocity * delta_time;
+
+        // Свои юниты, стрелок и цель, по которой был промах, снаряд не задевает
+        let shooter = projectile.shooter;
+        let from_enemy = projectile.from_enemy;
+        let missed_target = projectile.missed_target;
+        let blocks = |collider: Entity| {
+            let owner = owner_of(collider);
+            owner != shooter
+                && Some(owner) != missed_target
+                && !targets.get(owner).is_ok_and(|(_, is_enemy, is_enemy_tower, _)| (is_enemy || is_enemy_tower) == from_enemy)
+        };
+        let filter = QueryFilter::new().predicate(&blocks);
+        let mut hit = rapier_context.cast_ray(start, step, 1.0, true, filter);
+        if let Some((_, toi)) = hit {
+            // Юниты одной покупки стоят в одной точке, и их коллайдеры совпадают - какой из них
+            // вернет rapier, зависит от порядка обхода BVH. Берем младшую сущность в точке попадания,
+            // чтобы headless-матчи повторялись при том же сиде
+            let probe = start + step * toi + step.normalize_or_zero() * 0.01;
+            rapier_context.intersections_with_point(probe, filter, |collider| {
+                if hit.is_none_or(|(best, _)| collider < best) {
+                    hit = Some((collider, toi));
+                }
+                true
+            });
+        }
+
+        let (impact_position, target) = match hit {
+            Some((collider, toi)) => (start + step * toi, Some(owner_of(collider))),
+            None if start.y + step.y <= 0.0 => (Vec3::new(start.x + step.x, 0.0, start.z + step.z), None),
+            None => {
+                transform.translation = start + step;
+                continue;
+            }
+        };
+
+        let mut damaged = None;
+        if let Some(target) = target {
+            if let Ok((mut health, _, _, in_trench)) = targets.get_mut(target) {
+                damaged = Some(target);
+                // Пехота в окопе получает только часть урона
+                let damage = if in_trench { projectile.damage * TRENCH_DAMAGE_TAKEN } else { projectile.damage };
+                health.current -= damage;
+                info!("💥 {:?} hit for {} damage ({:.0} hp left)", projectile.kind, damage, health.current);
+                if health.current <= 0.0 {
+                    if let Some(entity_commands) = commands.get_entity(target) {
+                        entity_commands.despawn_recursive();
+                    }
+                    info!("Target destroyed by {:?}!", projectile.kind);
+                }
+            }
+        }
+
+        impacts.send(ProjectileImpact {
+            position: impact_position,
+            kind: projectile.kind,
+            shooter: projectile.shooter,
+            target: damaged,
+        });
+        commands.entity(entity).despawn_recursive();
+    }
+}
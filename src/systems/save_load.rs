@@ -0,0 +1,770 @@
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use crate::game::{
+    Aircraft, AttackMove, CanShoot, ConstructionSite, Enemy, EnemyTower, FarmActive, FarmIncomeRate, ForestFarm, Garrisoned,
+    GarrisonOrder, Health, Mine, MovementOrder, PetrochemicalPlant, Selectable, Selection, ShapeType, SteelFactory, TowerSlot, Trench,
+    TrenchConstruction, TrenchGarrison, UnitStance,
+};
+use crate::game::units::{spawn_unit, AIFaction, PlayerFaction};
+use crate::game::units::catalog::{UnitCatalog, UnitClass, UnitVariant};
+use crate::menu::common::GameState;
+use crate::menu::main_menu::Faction;
+use crate::systems::ai_opponent::HomeGuard;
+use crate::systems::projectiles::Projectile;
+use crate::systems::tower::{TowerTurret, TurretRole, MAX_TURRET_LEVEL};
+use crate::systems::turn_system::{PlayerTurn, TurnState};
+use crate::ui::money_ui::{AIIron, AIMoney, AIOil, AISteel, AIWood, Iron, Money, Oil, PurchasableItem, Steel, Wood};
+use crate::input::key_bindings::{InputAction, KeyBindings};
+use crate::input::group_selection::{BoxSelection, ControlGroups};
+
+/// Версия формата сохранения. Увеличивать при несовместимых изменениях структуры
+pub const SAVE_VERSION: u32 = 2;
+
+/// Save slot used by the pause menu and the quick-save hotkey
+pub const SAVE_GAME_PATH: &str = "saves/savegame.json";
+
+/// Entities that are written to a save (units, buildings, towers, trenches)
+type SaveableFilter = Or<(
+    With<ShapeType>,
+    With<Aircraft>,
+    With<ForestFarm>,
+    With<Mine>,
+    With<SteelFactory>,
+    With<PetrochemicalPlant>,
+)>;
+
+/// What kind of saveable entity this is
+type SaveableKind = (
+    Option<&'static UnitVariant>,
+    Has<Aircraft>,
+    Option<&'static TowerSlot>,
+    Has<Trench>,
+    Has<ForestFarm>,
+    Has<Mine>,
+    Has<SteelFactory>,
+    Has<PetrochemicalPlant>,
+);
+
+/// Side, identity and turret of a saveable entity
+type SaveableIdentity = (
+    Has<Enemy>,
+    Has<EnemyTower>,
+    Has<Selectable>,
+    Option<&'static Name>,
+    Option<&'static TowerTurret>,
+    Option<&'static HomeGuard>,
+);
+
+/// Mutable game state of a saveable entity
+type SaveableState = (
+    Option<&'static Health>,
+    Option<&'static CanShoot>,
+    Option<&'static MovementOrder>,
+    (Option<&'static UnitStance>, Option<&'static AttackMove>),
+    Option<&'static TrenchConstruction>,
+    Option<&'static FarmActive>,
+    Option<&'static FarmIncomeRate>,
+    Option<&'static ConstructionSite>,
+);
+
+/// Trench garrisons and soldiers on their way into a trench
+type SaveableGarrison = (Option<&'static TrenchGarrison>, Option<&'static GarrisonOrder>);
+
+type SaveableEntity = (Entity, &'static Transform, SaveableKind, SaveableIdentity, SaveableState, SaveableGarrison);
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct SaveGame {
+    pub version: u32,
+    pub turn: SavedTurn,
+    pub player_resources: SavedResources,
+    pub ai_resources: SavedResources,
+    pub player_faction: Faction,
+    pub ai_faction: Faction,
+    pub entities: Vec<SavedEntity>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug)]
+pub struct SavedTurn {
+    pub current_player: PlayerTurn,
+    pub time_left: f32,
+    pub turn_number: u32,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug)]
+pub struct SavedResources {
+    pub money: f32,
+    pub wood: f32,
+    pub iron: f32,
+    pub steel: f32,
+    pub oil: f32,
+}
+
+/// What to spawn when restoring an entity
+#[derive(Serialize, Deserialize, Clone, Copy, Debug)]
+pub enum SavedKind {
+    Unit { faction: Faction, class: UnitClass, index: usize },
+    StartingAircraft,
+    Tower { slot: usize },
+    Trench,
+    Farm,
+    Mine,
+    SteelFactory,
+    PetrochemicalPlant,
+    ConstructionSite,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct SavedEntity {
+    pub kind: SavedKind,
+    /// Принадлежит ИИ (Enemy или EnemyTower)
+    pub enemy: bool,
+    pub selectable: bool,
+    #[serde(default)]
+    pub name: Option<String>,
+    pub translation: [f32; 3],
+    pub rotation: [f32; 4],
+    pub scale: [f32; 3],
+    #[serde(default)]
+    pub health: Option<SavedHealth>,
+    #[serde(default)]
+    pub can_shoot: Option<SavedCanShoot>,
+    #[serde(default)]
+    pub movement_order: Option<[f32; 3]>,
+    #[serde(default)]
+    pub stance: Option<UnitStance>,
+    #[serde(default)]
+    pub attack_move: Option<[f32; 3]>,
+    #[serde(default)]
+    pub trench_construction: Option<SavedTrenchConstruction>,
+    #[serde(default)]
+    pub farm: Option<SavedFarm>,
+    #[serde(default)]
+    pub construction_site: Option<SavedConstructionSite>,
+    /// Уровень турели башни
+    #[serde(default)]
+    pub turret_level: Option<u32>,
+    /// Пост юнита ИИ, оставленного охранять базу
+    #[serde(default)]
+    pub home_guard: Option<[f32; 3]>,
+    /// Бойцы в окопе по местам: индексы в `SaveGame::entities`
+    #[serde(default)]
+    pub garrison: Option<Vec<Option<usize>>>,
+    /// Окоп, в который боец идет занимать место: индекс в `SaveGame::entities`
+    #[serde(default)]
+    pub garrison_order: Option<usize>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug)]
+pub struct SavedHealth {
+    pub current: f32,
+    pub max: f32,
+}
+
+/// `last_shot` is stored relative to the save time, since game time restarts between sessions
+#[derive(Serialize, Deserialize, Clone, Copy, Debug)]
+pub struct SavedCanShoot {
+    pub cooldown: f32,
+    pub seconds_since_shot: f32,
+    pub range: f32,
+    pub damage: f32,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug)]
+pub struct SavedTrenchConstruction {
+    pub time_remaining: f32,
+    pub total_construction_time: f32,
+}
+
+/// `building` is one of the building kinds (Farm, Mine, SteelFactory, PetrochemicalPlant)
+#[derive(Serialize, Deserialize, Clone, Copy, Debug)]
+pub struct SavedConstructionSite {
+    pub building: SavedKind,
+    pub progress: f32,
+    pub work_required: f32,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug)]
+pub struct SavedFarm {
+    pub active: bool,
+    #[serde(default)]
+    pub income_rate: Option<f32>,
+}
+
+/// Only the version, read first so newer or older saves fail with a clear error
+#[derive(Deserialize)]
+struct SaveHeader {
+    version: u32,
+}
+
+#[derive(Debug)]
+pub enum SaveGameError {
+    Io(std::io::Error),
+    Json(serde_json::Error),
+    UnsupportedVersion(u32),
+}
+
+impl std::fmt::Display for SaveGameError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SaveGameError::Io(err) => write!(f, "could not access save file: {}", err),
+            SaveGameError::Json(err) => write!(f, "could not parse save file: {}", err),
+            SaveGameError::UnsupportedVersion(version) => {
+                write!(f, "save version {} is not supported (expected {})", version, SAVE_VERSION)
+            }
+        }
+    }
+}
+
+impl std::error::Error for SaveGameError {}
+
+/// Kind of the building a construction site turns into
+fn building_kind(building: ShapeType) -> Option<SavedKind> {
+    match building {
+        ShapeType::Farm => Some(SavedKind::Farm),
+        ShapeType::Mine => Some(SavedKind::Mine),
+        ShapeType::SteelFactory => Some(SavedKind::SteelFactory),
+        ShapeType::PetrochemicalPlant => Some(SavedKind::PetrochemicalPlant),
+        _ => None,
+    }
+}
+
+fn building_shape(kind: SavedKind) -> Option<ShapeType> {
+    match kind {
+        SavedKind::Farm => Some(ShapeType::Farm),
+        SavedKind::Mine => Some(ShapeType::Mine),
+        SavedKind::SteelFactory => Some(ShapeType::SteelFactory),
+        SavedKind::PetrochemicalPlant => Some(ShapeType::PetrochemicalPlant),
+        _ => None,
+    }
+}
+
+pub fn write_save_game(save: &SaveGame, path: &str) -> Result<(), SaveGameError> {
+    if let Some(dir) = std::path::Path::new(path).parent() {
+        std::fs::create_dir_all(dir).map_err(SaveGameError::Io)?;
+    }
+    let json = serde_json::to_string_pretty(save).map_err(SaveGameError::Json)?;
+    std::fs::write(path, json).map_err(SaveGameError::Io)
+}
+
+pub fn read_save_game(path: &str) -> Result<SaveGame, SaveGameError> {
+    let json = std::fs::read_to_string(path).map_err(SaveGameError::Io)?;
+    let header: SaveHeader = serde_json::from_str(&json).map_err(SaveGameError::Json)?;
+    if header.version != SAVE_VERSION {
+        return Err(SaveGameError::UnsupportedVersion(header.version));
+    }
+    serde_json::from_str(&json).map_err(SaveGameError::Json)
+}
+
+/// Save restored on the next frame of GameState::Game
+#[derive(Resource)]
+pub struct PendingLoad(pub SaveGame);
+
+/// Everything that is read to build a save
+#[derive(SystemParam)]
+pub struct SaveGameSource<'w, 's> {
+    time: Res<'w, Time>,
+    turn_state: Res<'w, TurnState>,
+    money: Res<'w, Money>,
+    wood: Res<'w, Wood>,
+    iron: Res<'w, Iron>,
+    steel: Res<'w, Steel>,
+    oil: Res<'w, Oil>,
+    ai_money: Res<'w, AIMoney>,
+    ai_wood: Res<'w, AIWood>,
+    ai_iron: Res<'w, AIIron>,
+    ai_steel: Res<'w, AISteel>,
+    ai_oil: Res<'w, AIOil>,
+    player_faction: Res<'w, PlayerFaction>,
+    ai_faction: Res<'w, AIFaction>,
+    entities: Query<'w, 's, SaveableEntity, SaveableFilter>,
+}
+
+impl SaveGameSource<'_, '_> {
+    pub fn capture(&self) -> SaveGame {
+        let now = self.time.elapsed_seconds();
+        let mut entities = Vec::new();
+        // Ссылки на окопы и бойцов пишутся индексами, когда известны индексы всех сущностей
+        let mut indices = bevy::utils::HashMap::new();
+        let mut garrisons = Vec::new();
+
+        for (entity, transform, kind, (is_enemy, is_enemy_tower, selectable, name, turret, home_guard), state, garrison) in self.entities.iter() {
+            let (variant, is_aircraft, tower_slot, is_trench, is_farm, is_mine, is_steel_factory, is_petrochemical_plant) = kind;
+            let (health, can_shoot, movement_order, (stance, attack_move), trench_construction, farm_active, farm_income, construction_site) = state;
+
+            let kind = if let Some(variant) = variant {
+                SavedKind::Unit { faction: variant.faction, class: variant.class, index: variant.index }
+            } else if is_aircraft {
+                SavedKind::StartingAircraft
+            } else if let Some(slot) = tower_slot {
+                SavedKind::Tower { slot: slot.0 }
+            } else if construction_site.is_some() {
+                SavedKind::ConstructionSite
+            } else if is_trench {
+                SavedKind::Trench
+            } else if is_farm {
+                SavedKind::Farm
+            } else if is_mine {
+                SavedKind::Mine
+            } else if is_steel_factory {
+                SavedKind::SteelFactory
+            } else if is_petrochemical_plant {
+                SavedKind::PetrochemicalPlant
+            } else {
+                continue;
+            };
+
+            entities.push(SavedEntity {
+                kind,
+                enemy: is_enemy || is_enemy_tower,
+                selectable,
+                name: name.map(|name| name.as_str().to_string()),
+                translation: transform.translation.to_array(),
+                rotation: transform.rotation.to_array(),
+                scale: transform.scale.to_array(),
+                health: health.map(|health| SavedHealth { current: health.current, max: health.max }),
+                can_shoot: can_shoot.map(|can_shoot| SavedCanShoot {
+                    cooldown: can_shoot.cooldown,
+                    seconds_since_shot: now - can_shoot.last_shot,
+                    range: can_shoot.range,
+                    damage: can_shoot.damage,
+                }),
+                movement_order: movement_order.map(|order| order.0.to_array()),
+                stance: stance.copied(),
+                attack_move: attack_move.map(|order| order.0.to_array()),
+                trench_construction: trench_construction.map(|construction| SavedTrenchConstruction {
+                    time_remaining: construction.time_remaining,
+                    total_construction_time: construction.total_construction_time,
+                }),
+                farm: farm_active.map(|active| SavedFarm {
+                    active: active.0,
+                    income_rate: farm_income.map(|rate| rate.0),
+                }),
+                construction_site: construction_site.and_then(|site| {
+                    Some(SavedConstructionSite {
+                        building: building_kind(site.building)?,
+                        progress: site.progress,
+                        work_required: site.work_required,
+                    })
+                }),
+                turret_level: turret.map(|turret| turret.level),
+                home_guard: home_guard.map(|guard| guard.post.to_array()),
+                garrison: None,
+                garrison_order: None,
+            });
+            indices.insert(entity, entities.len() - 1);
+            garrisons.push(garrison);
+        }
+
+        for (saved, (trench_garrison, garrison_order)) in entities.iter_mut().zip(garrisons) {
+            saved.garrison = trench_garrison.map(|garrison| {
+                garrison.occupants.iter()
+                    .map(|occupant| occupant.and_then(|occupant| indices.get(&occupant).copied()))
+                    .collect()
+            });
+            saved.garrison_order = garrison_order.and_then(|order| indices.get(&order.0).copied());
+        }
+
+        SaveGame {
+            version: SAVE_VERSION,
+            turn: SavedTurn {
+                current_player: self.turn_state.current_player,
+                time_left: self.turn_state.time_left,
+                turn_number: self.turn_state.turn_number,
+            },
+            player_resources: SavedResources {
+                money: self.money.0,
+                wood: self.wood.0,
+                iron: self.iron.0,
+                steel: self.steel.0,
+                oil: self.oil.0,
+            },
+            ai_resources: SavedResources {
+                money: self.ai_money.0,
+                wood: self.ai_wood.0,
+                iron: self.ai_iron.0,
+                steel: self.ai_steel.0,
+                oil: self.ai_oil.0,
+            },
+            player_faction: self.player_faction.0,
+            ai_faction: self.ai_faction.0,
+            entities,
+        }
+    }
+}
+
+/// Resources overwritten when a save is restored; the selection is cleared since it points at despawned entities
+#[derive(SystemParam)]
+pub struct SaveGameTarget<'w> {
+    selection: ResMut<'w, Selection>,
+    box_selection: ResMut<'w, BoxSelection>,
+    control_groups: ResMut<'w, ControlGroups>,
+    turn_state: ResMut<'w, TurnState>,
+    money: ResMut<'w, Money>,
+    wood: ResMut<'w, Wood>,
+    iron: ResMut<'w, Iron>,
+    steel: ResMut<'w, Steel>,
+    oil: ResMut<'w, Oil>,
+    ai_money: ResMut<'w, AIMoney>,
+    ai_wood: ResMut<'w, AIWood>,
+    ai_iron: ResMut<'w, AIIron>,
+    ai_steel: ResMut<'w, AISteel>,
+    ai_oil: ResMut<'w, AIOil>,
+    player_faction: ResMut<'w, PlayerFaction>,
+    ai_faction: ResMut<'w, AIFaction>,
+}
+
+impl SaveGameTarget<'_> {
+    fn restore(&mut self, save: &SaveGame) {
+        self.selection.clear();
+        *self.box_selection = BoxSelection::default();
+        *self.control_groups = ControlGroups::default();
+
+        self.turn_state.current_player = save.turn.current_player;
+        self.turn_state.time_left = save.turn.time_left;
+        self.turn_state.turn_number = save.turn.turn_number;
+
+        self.money.0 = save.player_resources.money;
+        self.wood.0 = save.player_resources.wood;
+        self.iron.0 = save.player_resources.iron;
+        self.steel.0 = save.player_resources.steel;
+        self.oil.0 = save.player_resources.oil;
+
+        self.ai_money.0 = save.ai_resources.money;
+        self.ai_wood.0 = save.ai_resources.wood;
+        self.ai_iron.0 = save.ai_resources.iron;
+        self.ai_steel.0 = save.ai_resources.steel;
+        self.ai_oil.0 = save.ai_resources.oil;
+
+        self.player_faction.0 = save.player_faction;
+        self.ai_faction.0 = save.ai_faction;
+    }
+}
+
+/// Quick-save (F5 by default) into the save slot
+pub fn quick_save_system(keys: Res<ButtonInput<KeyCode>>, key_bindings: Res<KeyBindings>, source: SaveGameSource) {
+    if !key_bindings.just_pressed(InputAction::QuickSave, &keys) {
+        return;
+    }
+
+    let save = source.capture();
+    match write_save_game(&save, SAVE_GAME_PATH) {
+        Ok(()) => info!("💾 Quick-saved {} entities to {}", save.entities.len(), SAVE_GAME_PATH),
+        Err(err) => error!("Quick-save failed: {}", err),
+    }
+}
+
+/// Assets the saved entities are spawned back with
+#[derive(SystemParam)]
+pub struct SaveGameAssets<'w> {
+    meshes: ResMut<'w, Assets<Mesh>>,
+    materials: ResMut<'w, Assets<StandardMaterial>>,
+    asset_server: Res<'w, AssetServer>,
+    unit_catalog: Res<'w, UnitCatalog>,
+}
+
+/// Replaces the current match with the pending save
+pub fn apply_pending_load(
+    mut commands: Commands,
+    pending: Res<PendingLoad>,
+    mut target: SaveGameTarget,
+    existing: Query<Entity, SaveableFilter>,
+    projectiles: Query<Entity, With<Projectile>>,
+    assets: SaveGameAssets,
+    time: Res<Time>,
+) {
+    let save = &pending.0;
+    let now = time.elapsed_seconds();
+    let SaveGameAssets { mut meshes, mut materials, asset_server, unit_catalog } = assets;
+
+    // Снаряды в полете не сохраняются: загруженный бой начинается без них
+    for entity in existing.iter().chain(projectiles.iter()) {
+        commands.entity(entity).despawn_recursive();
+    }
+
+    target.restore(save);
+
+    let mut spawned = Vec::with_capacity(save.entities.len());
+    for saved in &save.entities {
+        let position = Vec3::from_array(saved.translation);
+        let building = match saved.kind {
+            SavedKind::Farm => Some(PurchasableItem::Farm),
+            SavedKind::Mine => Some(PurchasableItem::Mine),
+            SavedKind::SteelFactory => Some(PurchasableItem::SteelFactory),
+            SavedKind::PetrochemicalPlant => Some(PurchasableItem::PetrochemicalPlant),
+            _ => None,
+        };
+
+        let entity = match (saved.kind, building) {
+            (SavedKind::Unit { faction, class, index }, _) => {
+                spawn_unit(&mut commands, &asset_server, &unit_catalog, faction, class, index, position)
+            }
+            (SavedKind::StartingAircraft, _) => Some(crate::systems::aircraft::spawn_starting_aircraft(
+                &mut commands, &mut meshes, &mut materials, Transform::from_translation(position),
+            )),
+            (SavedKind::Tower { slot }, _) => {
+                let faction = if saved.enemy { save.ai_faction } else { save.player_faction };
+                Some(crate::game::setup::spawn_faction_tower(&mut commands, &asset_server, faction, slot, position, saved.enemy))
+            }
+            (SavedKind::ConstructionSite, _) => saved.construction_site
+                .and_then(|site| building_shape(site.building))
+                .map(|building| crate::game::construction::spawn_construction_site(&mut commands, &mut meshes, &mut materials, building, position)),
+            (SavedKind::Trench, _) if saved.trench_construction.is_some() => {
+                Some(crate::game::trench::spawn_constructing_trench(&mut commands, &mut meshes, &mut materials, position, Quat::from_array(saved.rotation)))
+            }
+            (SavedKind::Trench, _) => {
+                Some(crate::game::trench::spawn_completed_trench(&mut commands, &mut meshes, &mut materials, position, Quat::from_array(saved.rotation)))
+            }
+            (_, Some(item)) if saved.enemy => {
+                crate::systems::ai_opponent::spawn_ai_building(&mut commands, &asset_server, item, crate::systems::ai_opponent::AISide::Enemy, position)
+            }
+            (SavedKind::Mine, _) => Some(crate::game::mine::spawn_active_mine(&mut commands, &mut meshes, &mut materials, position, &asset_server)),
+            (SavedKind::SteelFactory, _) => Some(crate::game::steel_factory::spawn_active_steel_factory(&mut commands, &mut meshes, &mut materials, position, &asset_server)),
+            (SavedKind::PetrochemicalPlant, _) => Some(crate::game::petrochemical_plant::spawn_active_petrochemical_plant(&mut commands, &mut meshes, &mut materials, position, &asset_server)),
+            (SavedKind::Farm, _) => Some(crate::game::farm::spawn_active_forest_farm(&mut commands, &mut meshes, &mut materials, position, &asset_server)),
+        };
+        spawned.push(entity);
+        let Some(entity) = entity else {
+            continue;
+        };
+
+        // Перезаписываем состояние, выставленное функциями создания
+        let mut entity_commands = commands.entity(entity);
+        entity_commands.insert(Transform {
+            translation: position,
+            rotation: Quat::from_array(saved.rotation),
+            scale: Vec3::from_array(saved.scale),
+        });
+        if saved.enemy && !matches!(saved.kind, SavedKind::Tower { .. }) {
+            entity_commands.insert(Enemy);
+        }
+        // Стройплощадки создаются выделяемыми, стройки противника - нет
+        if saved.selectable {
+            entity_commands.insert(Selectable);
+        } else {
+            entity_commands.remove::<Selectable>();
+        }
+        if let Some(name) = &saved.name {
+            entity_commands.insert(Name::new(name.clone()));
+        }
+        if let Some(health) = saved.health {
+            entity_commands.insert(Health { current: health.current, max: health.max });
+        }
+        if let Some(can_shoot) = saved.can_shoot {
+            entity_commands.insert(CanShoot {
+                cooldown: can_shoot.cooldown,
+                last_shot: now - can_shoot.seconds_since_shot,
+                range: can_shoot.range,
+                damage: can_shoot.damage,
+            });
+        }
+        if let Some(order) = saved.movement_order {
+            entity_commands.insert(MovementOrder(Vec3::from_array(order)));
+        }
+        if let Some(stance) = saved.stance {
+            entity_commands.insert(stance);
+        }
+        if let Some(order) = saved.attack_move {
+            entity_commands.insert(AttackMove(Vec3::from_array(order)));
+        }
+        if let Some(construction) = saved.trench_construction {
+            entity_commands.insert(TrenchConstruction {
+                time_remaining: construction.time_remaining,
+                total_construction_time: construction.total_construction_time,
+            });
+        }
+        if let Some(site) = saved.construction_site {
+            if let Some(building) = building_shape(site.building) {
+                entity_commands.insert(ConstructionSite { building, progress: site.progress, work_required: site.work_required });
+            }
+        }
+        if let (SavedKind::Tower { slot }, Some(level)) = (saved.kind, saved.turret_level) {
+            entity_commands.insert(TowerTurret {
+                level: level.clamp(1, MAX_TURRET_LEVEL),
+                ..TowerTurret::new(TurretRole::for_slot(slot))
+            });
+        }
+        if let Some(post) = saved.home_guard {
+            entity_commands.insert(HomeGuard { post: Vec3::from_array(post) });
+        }
+        if let Some(farm) = saved.farm {
+            entity_commands.insert(FarmActive(farm.active));
+            if let Some(income_rate) = farm.income_rate {
+                entity_commands.insert(FarmIncomeRate(income_rate));
+            }
+        }
+    }
+
+    // Гарнизоны восстанавливаются после всех сущностей: окоп и его бойцы ссылаются друг на друга
+    let spawned_at = |index: usize| spawned.get(index).copied().flatten();
+    for (saved, entity) in save.entities.iter().zip(spawned.iter()) {
+        let Some(entity) = *entity else {
+            continue;
+        };
+        if let Some(occupants) = &saved.garrison {
+            let occupants: Vec<Option<Entity>> = occupants.iter().map(|occupant| occupant.and_then(spawned_at)).collect();
+            for soldier in occupants.iter().flatten() {
+                commands.entity(*soldier).insert(Garrisoned { trench: entity });
+            }
+            commands.entity(entity).insert(TrenchGarrison { occupants });
+        }
+        if let Some(trench) = saved.garrison_order.and_then(spawned_at) {
+            commands.entity(entity).insert(GarrisonOrder(trench));
+        }
+    }
+
+    commands.remove_resource::<PendingLoad>();
+    info!("📂 Restored save: turn {}, {} entities", save.turn.turn_number, save.entities.len());
+}
+
+pub struct SaveLoadPlugin;
+
+impl Plugin for SaveLoadPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+               Update,
+               (
+                   quick_save_system,
+                   apply_pending_load.run_if(resource_exists::<PendingLoad>),
+               ).run_if(in_state(GameState::Game)),
+           );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::ecs::system::RunSystemOnce;
+    use crate::utils::read_balance_file;
+
+    fn match_app() -> App {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, AssetPlugin::default()))
+            .init_asset::<Mesh>()
+            .init_asset::<StandardMaterial>()
+            .init_asset::<Scene>()
+            .insert_resource(read_balance_file::<UnitCatalog>().expect("unit catalog"))
+            .init_resource::<TurnState>()
+            .init_resource::<Selection>()
+            .init_resource::<BoxSelection>()
+            .init_resource::<ControlGroups>()
+            .init_resource::<PlayerFaction>()
+            .init_resource::<AIFaction>()
+            .init_resource::<Money>()
+            .init_resource::<Wood>()
+            .init_resource::<Iron>()
+            .init_resource::<Steel>()
+            .init_resource::<Oil>()
+            .init_resource::<AIMoney>()
+            .init_resource::<AIWood>()
+            .init_resource::<AIIron>()
+            .init_resource::<AISteel>()
+            .init_resource::<AIOil>();
+        app
+    }
+
+    fn spawn_match(
+        mut commands: Commands,
+        mut meshes: ResMut<Assets<Mesh>>,
+        mut materials: ResMut<Assets<StandardMaterial>>,
+        asset_server: Res<AssetServer>,
+        unit_catalog: Res<UnitCatalog>,
+    ) {
+        let unit = spawn_unit(&mut commands, &asset_server, &unit_catalog, Faction::Entente, UnitClass::Infantry, 0, Vec3::new(3.0, 0.0, 4.0))
+            .expect("catalog has Entente infantry");
+        commands.entity(unit).insert((
+            Selectable,
+            Health { current: 42.0, max: 100.0 },
+            MovementOrder(Vec3::new(10.0, 0.0, -2.0)),
+            UnitStance::ReturnFire,
+            HomeGuard { post: Vec3::new(3.0, 0.0, 4.0) },
+        ));
+
+        let tower = crate::game::setup::spawn_faction_tower(&mut commands, &asset_server, Faction::CentralPowers, 1, Vec3::new(0.0, 0.0, -40.0), true);
+        commands.entity(tower).insert((
+            Health { current: 250.0, max: 600.0 },
+            TowerTurret { level: 2, ..TowerTurret::new(TurretRole::for_slot(1)) },
+        ));
+
+        let site = crate::game::construction::spawn_construction_site(&mut commands, &mut meshes, &mut materials, ShapeType::Mine, Vec3::new(-15.0, 0.0, 2.0));
+        commands.entity(site).insert(ConstructionSite { building: ShapeType::Mine, progress: 3.5, work_required: 10.0 });
+
+        let trench = crate::game::trench::spawn_completed_trench(&mut commands, &mut meshes, &mut materials, Vec3::new(5.0, 0.0, 5.0), Quat::from_rotation_y(0.5));
+        let defender = spawn_unit(&mut commands, &asset_server, &unit_catalog, Faction::Entente, UnitClass::Infantry, 0, Vec3::new(5.25, 0.0, 5.0))
+            .expect("catalog has Entente infantry");
+        commands.entity(defender).insert((Selectable, Garrisoned { trench }));
+        commands.entity(trench).insert(TrenchGarrison { occupants: vec![None, Some(defender)] });
+        let reinforcement = spawn_unit(&mut commands, &asset_server, &unit_catalog, Faction::Entente, UnitClass::Infantry, 0, Vec3::new(9.0, 0.0, 9.0))
+            .expect("catalog has Entente infantry");
+        commands.entity(reinforcement).insert((Selectable, MovementOrder(Vec3::new(5.0, 0.0, 5.0)), GarrisonOrder(trench)));
+        let farm = crate::game::farm::spawn_active_forest_farm(&mut commands, &mut meshes, &mut materials, Vec3::new(0.0, 0.0, -15.0), &asset_server);
+        commands.entity(farm).insert(FarmActive(false));
+    }
+
+    /// Сравниваем сохранения без учета порядка обхода сущностей: ссылки-индексы заменяются позициями сущностей
+    fn normalized(save: &SaveGame) -> serde_json::Value {
+        let position = |index: usize| serde_json::to_value(save.entities[index].translation).expect("position serializes");
+        let mut value = serde_json::to_value(save).expect("save serializes");
+        if let Some(entities) = value.get_mut("entities").and_then(|entities| entities.as_array_mut()) {
+            for (entity, saved) in entities.iter_mut().zip(&save.entities) {
+                entity["garrison"] = serde_json::to_value(saved.garrison.as_ref().map(|occupants| {
+                    occupants.iter().map(|occupant| occupant.map(position)).collect::<Vec<_>>()
+                })).expect("garrison serializes");
+                entity["garrison_order"] = serde_json::to_value(saved.garrison_order.map(position)).expect("order serializes");
+            }
+            entities.sort_by_key(|entity| entity.to_string());
+        }
+        value
+    }
+
+    #[test]
+    fn save_and_load_round_trip() {
+        let mut original = match_app();
+        original.world.run_system_once(spawn_match);
+        original.world.resource_mut::<Money>().0 = 123.0;
+        original.world.resource_mut::<AIOil>().0 = 7.5;
+        original.world.resource_mut::<AIFaction>().0 = Faction::CentralPowers;
+        {
+            let mut turn_state = original.world.resource_mut::<TurnState>();
+            turn_state.turn_number = 5;
+            turn_state.current_player = PlayerTurn::AI;
+            turn_state.time_left = 12.0;
+        }
+        let save = original.world.run_system_once(|source: SaveGameSource| source.capture());
+        assert_eq!(save.entities.len(), 7);
+        let trench = save.entities.iter().find(|saved| matches!(saved.kind, SavedKind::Trench)).expect("trench is saved");
+        assert!(matches!(trench.garrison.as_deref(), Some([None, Some(_)])));
+
+        let path = std::env::temp_dir().join(format!("coalitions_round_trip_{}.json", std::process::id()));
+        let path = path.to_str().expect("temp path is utf-8");
+        write_save_game(&save, path).expect("save is written");
+        let loaded = read_save_game(path).expect("save is read back");
+        std::fs::remove_file(path).ok();
+
+        let mut restored = match_app();
+        let stale = restored.world.spawn_empty().id();
+        restored.world.resource_mut::<Selection>().0.push(stale);
+        restored.world.resource_mut::<ControlGroups>().0[0].push(stale);
+        restored.world.insert_resource(PendingLoad(loaded));
+        restored.world.run_system_once(apply_pending_load);
+        assert!(!restored.world.contains_resource::<PendingLoad>());
+        assert!(restored.world.resource::<Selection>().0.is_empty());
+        assert!(restored.world.resource::<ControlGroups>().0[0].is_empty());
+
+        let recaptured = restored.world.run_system_once(|source: SaveGameSource| source.capture());
+        assert_eq!(normalized(&save), normalized(&recaptured));
+        let garrisoned = restored.world.query::<&Garrisoned>().iter(&restored.world).count();
+        assert_eq!(garrisoned, 1);
+    }
+
+    #[test]
+    fn save_with_unknown_version_is_rejected() {
+        let path = std::env::temp_dir().join(format!("coalitions_old_version_{}.json", std::process::id()));
+        let path = path.to_str().expect("temp path is utf-8");
+        std::fs::write(path, r#"{"version": 999}"#).expect("file is written");
+        let result = read_save_game(path);
+        std::fs::remove_file(path).ok();
+
+        assert!(matches!(result, Err(SaveGameError::UnsupportedVersion(999))));
+    }
+}
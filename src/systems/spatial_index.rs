@@ -0,0 +1,150 @@
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+
+use crate::game::{CanShoot, Enemy, EnemyTower, Health, Tower};
+use crate::systems::ai_opponent::AISide;
+
+/// Сторона ячейки сетки целей: порядка дальности выстрела пехоты
+pub const TARGET_CELL_SIZE: f32 = 8.0;
+
+/// Rebuilds the target index before any system looks for targets in this frame
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TargetIndexSet;
+
+/// What kind of target an indexed entity is
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TargetKind {
+    /// Юнит с оружием, может уйти с места
+    Unit,
+    Building,
+    Tower,
+}
+
+impl TargetKind {
+    pub fn of(armed: bool, is_tower: bool) -> Self {
+        if is_tower {
+            TargetKind::Tower
+        } else if armed {
+            TargetKind::Unit
+        } else {
+            TargetKind::Building
+        }
+    }
+}
+
+/// Anything that can be shot at: a unit, a building or a tower
+#[derive(Clone, Copy, Debug)]
+pub struct IndexedTarget {
+    pub entity: Entity,
+    pub position: Vec3,
+    /// Кому принадлежит цель (`Enemy` или `EnemyTower` - сторона ИИ)
+    pub side: AISide,
+    pub kind: TargetKind,
+}
+
+/// Uniform grid over every entity with `Health`, rebuilt each frame from the `Transform`s.
+/// Targeting asks it for the neighbourhood instead of scanning every entity of the other side.
+#[derive(Resource, Default)]
+pub struct TargetIndex {
+    cells: HashMap<IVec2, Vec<IndexedTarget>>,
+}
+
+impl TargetIndex {
+    fn cell_of(position: Vec3) -> IVec2 {
+        (Vec2::new(position.x, position.z) / TARGET_CELL_SIZE).floor().as_ivec2()
+    }
+
+    pub fn clear(&mut self) {
+        self.cells.clear();
+    }
+
+    pub fn insert(&mut self, target: IndexedTarget) {
+        self.cells.entry(Self::cell_of(target.position)).or_default().push(target);
+    }
+
+    /// Targets of `side` whose cells lie in the ring `ring` cells away from `center`
+    fn ring(&self, center: IVec2, ring: i32, side: AISide) -> impl Iterator<Item = &IndexedTarget> {
+        // Кольцо 0 - одна центральная клетка; без take она попала бы в обход дважды
+        let cell_count = if ring == 0 { 1 } else { 8 * ring as usize };
+        (-ring..=ring)
+            .flat_map(move |x| [IVec2::new(x, -ring), IVec2::new(x, ring)])
+            .chain((1 - ring..ring).flat_map(move |z| [IVec2::new(-ring, z), IVec2::new(ring, z)]))
+            .take(cell_count)
+            .filter_map(move |offset| self.cells.get(&(center + offset)))
+            .flatten()
+            .filter(move |target| target.side == side)
+    }
+
+    /// Targets of `side` no farther than `radius` from `position`
+    pub fn within_radius(&self, position: Vec3, radius: f32, side: AISide) -> impl Iterator<Item = &IndexedTarget> {
+        let rings = (radius / TARGET_CELL_SIZE).ceil() as i32 + 1;
+        let center = Self::cell_of(position);
+        (0..=rings)
+            .flat_map(move |ring| self.ring(center, ring, side))
+            .filter(move |target| target.position.distance(position) <= radius)
+    }
+
+    /// Nearest target of `side` within `max_distance` that passes `filter`.
+    /// Rings of cells are searched outwards until no closer target can remain; ties go to the lower entity.
+    pub fn nearest(
+        &self,
+        position: Vec3,
+        max_distance: f32,
+        side: AISide,
+        filter: impl Fn(&IndexedTarget) -> bool,
+    ) -> Option<&IndexedTarget> {
+        let rings = (max_distance / TARGET_CELL_SIZE).ceil() as i32 + 1;
+        let center = Self::cell_of(position);
+        let mut best: Option<(f32, &IndexedTarget)> = None;
+
+        for ring in 0..=rings {
+            // Все цели в кольце ring не ближе (ring - 1) клеток
+            if best.is_some_and(|(distance, _)| distance <= (ring - 1) as f32 * TARGET_CELL_SIZE) {
+                break;
+            }
+            for target in self.ring(center, ring, side) {
+                let distance = target.position.distance(position);
+                if distance > max_distance || !filter(target) {
+                    continue;
+                }
+                let closer = best.is_none_or(|(best_distance, best_target)| {
+                    distance < best_distance || (distance == best_distance && target.entity < best_target.entity)
+                });
+                if closer {
+                    best = Some((distance, target));
+                }
+            }
+        }
+
+        best.map(|(_, target)| target)
+    }
+}
+
+type IndexedTargetData<'a> = (Entity, &'a Transform, Has<Enemy>, Has<EnemyTower>, Has<CanShoot>, Has<Tower>);
+
+/// Puts every entity with `Health` into the index under its side
+pub fn update_target_index(
+    mut index: ResMut<TargetIndex>,
+    targets: Query<IndexedTargetData, With<Health>>,
+) {
+    index.clear();
+    for (entity, transform, is_enemy, is_enemy_tower, armed, is_tower) in targets.iter() {
+        index.insert(IndexedTarget {
+            entity,
+            position: transform.translation,
+            side: AISide::of(is_enemy || is_enemy_tower),
+            kind: TargetKind::of(armed, is_tower),
+        });
+    }
+}
+
+/// Registers the target index; it is rebuilt first thing in `PreUpdate`, before the AI scores its units
+pub struct TargetIndexPlugin;
+
+impl Plugin for TargetIndexPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<TargetIndex>()
+            .configure_sets(PreUpdate, TargetIndexSet.before(big_brain::prelude::BigBrainSet::Scorers))
+            .add_systems(PreUpdate, update_target_index.in_set(TargetIndexSet));
+    }
+}
@@ -0,0 +1,221 @@
+use bevy::prelude::*;
+
+use crate::game::{Aircraft, AttackMove, CanShoot, Enemy, EnemyTower, Health, MovementOrder, Selectable, Selection, Tank, UnitStance};
+use crate::game::units::infantry::Infantry;
+use crate::game::units::armor::{ArmorClass, DamageMatrix, DamageType};
+use crate::game::units::{weapon_stats, MilitaryUnit};
+use crate::systems::ai_opponent::AISide;
+use crate::systems::movement::MovingTank;
+use crate::systems::projectiles::{fire_projectile, ProjectileImpact, ProjectileKind, Shot, TargetClass};
+use crate::systems::fog_of_war::FogOfWar;
+use crate::systems::spatial_index::TargetIndex;
+use crate::systems::turn_system::{PlayerTurn, TurnState};
+use crate::utils::GameRng;
+use crate::input::key_bindings::{InputAction, KeyBindings};
+
+/// Сколько секунд юнит в режиме ответного огня помнит, кто по нему стрелял
+const RETURN_FIRE_MEMORY: f32 = 10.0;
+/// Расстояние до точки атакующего марша, на котором приказ считается выполненным
+const ATTACK_MOVE_ARRIVAL: f32 = 1.0;
+
+/// Who last hit this unit, for the return fire stance
+#[derive(Component, Clone, Copy)]
+pub struct LastAttacker {
+    pub entity: Entity,
+    pub time: f32,
+}
+
+/// Set by the attack-move hotkey: the next ground click gives an attack-move order instead of a plain move
+#[derive(Resource, Default)]
+pub struct AttackMoveArmed(pub bool);
+
+/// Player units that can pick their own targets
+type PlayerShooterFilter = (With<Selectable>, Without<Enemy>);
+type AutoFireShooter<'a> = (
+    Entity,
+    &'a Transform,
+    &'a mut CanShoot,
+    Option<&'a MilitaryUnit>,
+    Option<&'a UnitStance>,
+    Option<&'a LastAttacker>,
+    Has<AttackMove>,
+    (Has<Tank>, Has<Aircraft>, Has<Infantry>),
+);
+type EnemyTarget<'a> = (Entity, &'a Transform, Has<Infantry>, Has<Tank>, Has<Aircraft>);
+type EnemyTargetFilter = (With<Health>, Or<(With<Enemy>, With<EnemyTower>)>);
+
+/// Hotkeys for the selected units: G - aggressive, R - return fire, H - hold fire, T - attack-move (see `KeyBindings`)
+pub fn unit_order_hotkeys(
+    mut commands: Commands,
+    keys: Res<ButtonInput<KeyCode>>,
+    key_bindings: Res<KeyBindings>,
+    selection: Res<Selection>,
+    units: Query<(), (With<CanShoot>, PlayerShooterFilter)>,
+    turn_state: Res<TurnState>,
+    mut attack_move_armed: ResMut<AttackMoveArmed>,
+) {
+    let selected_units: Vec<Entity> = selection.iter().filter(|unit| units.get(*unit).is_ok()).collect();
+    if selected_units.is_empty() {
+        attack_move_armed.0 = false;
+        return;
+    }
+    if turn_state.current_player != PlayerTurn::Human {
+        return;
+    }
+
+    let stance = if key_bindings.just_pressed(InputAction::StanceAggressive, &keys) {
+        Some(UnitStance::Aggressive)
+    } else if key_bindings.just_pressed(InputAction::StanceReturnFire, &keys) {
+        Some(UnitStance::ReturnFire)
+    } else if key_bindings.just_pressed(InputAction::StanceHoldFire, &keys) {
+        Some(UnitStance::HoldFire)
+    } else {
+        None
+    };
+    if let Some(stance) = stance {
+        for unit in &selected_units {
+            commands.entity(*unit).insert(stance);
+        }
+        info!("🎖️ {} unit(s) stance: {:?}", selected_units.len(), stance);
+    }
+
+    if key_bindings.just_pressed(InputAction::AttackMove, &keys) {
+        attack_move_armed.0 = true;
+        info!("⚔️ Attack-move: click on the ground to set the destination");
+    }
+}
+
+/// Remembers who hit each player unit, so return fire knows whom to answer
+pub fn record_attackers(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut impacts: EventReader<ProjectileImpact>,
+    units: Query<(), PlayerShooterFilter>,
+) {
+    for impact in impacts.read() {
+        let Some(target) = impact.target.filter(|target| units.get(*target).is_ok()) else {
+            continue;
+        };
+        if let Some(mut entity_commands) = commands.get_entity(target) {
+            entity_commands.insert(LastAttacker { entity: impact.shooter, time: time.elapsed_seconds() });
+        }
+    }
+}
+
+/// Player units fire on their own according to their stance, using the same cooldowns as ordered shots
+#[allow(clippy::too_many_arguments)]
+pub fn player_auto_fire_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    turn_state: Res<TurnState>,
+    asset_server: Res<AssetServer>,
+    damage_matrix: Res<DamageMatrix>,
+    target_index: Res<TargetIndex>,
+    fog: Res<FogOfWar>,
+    mut rng: ResMut<GameRng>,
+    mut shooters: Query<AutoFireShooter, PlayerShooterFilter>,
+    targets: Query<EnemyTarget, EnemyTargetFilter>,
+) {
+    // Юниты ИИ стреляют только в свой ход, юниты игрока - только в его
+    if turn_state.current_player != PlayerTurn::Human {
+        return;
+    }
+
+    let current_time = time.elapsed_seconds();
+
+    for (shooter, transform, mut can_shoot, military_unit, stance, last_attacker, attack_moving, (is_tank, is_aircraft, is_infantry)) in shooters.iter_mut() {
+        // Атакующий марш всегда ведет огонь, иначе решает стойка
+        let stance = if attack_moving { UnitStance::Aggressive } else { stance.copied().unwrap_or_default() };
+        if stance == UnitStance::HoldFire {
+            continue;
+        }
+
+        let (damage, cooldown) = weapon_stats(&can_shoot, military_unit);
+        if current_time - can_shoot.last_shot < cooldown {
+            continue;
+        }
+
+        let in_range = |target_transform: &Transform| transform.translation.distance(target_transform.translation) <= can_shoot.range;
+        let target = match stance {
+            UnitStance::Aggressive => target_index
+                .nearest(transform.translation, can_shoot.range, AISide::Enemy, |target| {
+                    fog.knows(AISide::Player, target.position, target.kind)
+                })
+                .and_then(|target| targets.get(target.entity).ok()),
+            UnitStance::ReturnFire => last_attacker
+                .filter(|attacker| current_time - attacker.time <= RETURN_FIRE_MEMORY)
+                .and_then(|attacker| targets.get(attacker.entity).ok())
+                .filter(|(_, target_transform, ..)| in_range(target_transform)),
+            UnitStance::HoldFire => None,
+        };
+        let Some((target, target_transform, target_infantry, target_tank, target_aircraft)) = target else {
+            continue;
+        };
+
+        fire_projectile(&mut commands, &mut rng, Shot {
+            shooter,
+            from: transform.translation,
+            target,
+            target_pos: target_transform.translation,
+            target_class: TargetClass::of(target_infantry, target_tank, target_aircraft),
+            kind: ProjectileKind::for_shooter(is_tank, is_aircraft),
+            damage: damage_matrix.apply(
+                damage,
+                DamageType::for_shooter(is_tank, is_aircraft),
+                ArmorClass::of(target_infantry, target_tank, target_aircraft),
+            ),
+            range: can_shoot.range,
+            from_enemy: false,
+            accuracy: 1.0,
+        });
+        can_shoot.last_shot = current_time;
+
+        let audio_source = if is_tank {
+            asset_server.load("audio/tank_shot.mp3")
+        } else if is_aircraft {
+            asset_server.load("audio/aircraft_gun.mp3")
+        } else if is_infantry {
+            asset_server.load("audio/infantry_shot.ogg")
+        } else {
+            asset_server.load("audio/gun.mp3")
+        };
+        commands.spawn(AudioBundle {
+            source: audio_source,
+            settings: PlaybackSettings::ONCE,
+        });
+        info!("🎯 Unit {:?} ({:?}) opened fire on {:?}", shooter, stance, target);
+    }
+}
+
+/// Attack-move: halts the unit while an enemy is in range and resumes the march once it is clear
+pub fn attack_move_system(
+    mut commands: Commands,
+    turn_state: Res<TurnState>,
+    target_index: Res<TargetIndex>,
+    fog: Res<FogOfWar>,
+    units: Query<(Entity, &Transform, &CanShoot, &AttackMove, Has<MovementOrder>), PlayerShooterFilter>,
+) {
+    if turn_state.current_player != PlayerTurn::Human {
+        return;
+    }
+
+    for (unit, transform, can_shoot, attack_move, moving) in units.iter() {
+        let enemy_in_range = target_index.within_radius(transform.translation, can_shoot.range, AISide::Enemy)
+            .any(|target| fog.knows(AISide::Player, target.position, target.kind));
+
+        if enemy_in_range {
+            if moving {
+                commands.entity(unit).remove::<(MovementOrder, MovingTank)>();
+            }
+            continue;
+        }
+
+        let to_destination = attack_move.0 - transform.translation;
+        if Vec2::new(to_destination.x, to_destination.z).length() <= ATTACK_MOVE_ARRIVAL {
+            commands.entity(unit).remove::<AttackMove>();
+            info!("⚔️ Unit {:?} finished attack-move", unit);
+        } else if !moving {
+            commands.entity(unit).insert(MovementOrder(attack_move.0));
+        }
+    }
+}
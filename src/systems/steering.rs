@@ -0,0 +1,193 @@
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+
+use crate::game::{Aircraft, MovementOrder, Tank};
+use crate::game::units::infantry::Infantry;
+use crate::game::units::{move_speed, MilitaryUnit};
+use crate::systems::formations::FormationSpeed;
+use crate::systems::pathfinding::MovementPath;
+
+/// Сторона ячейки пространственного хэша - не меньше радиуса, в котором ищутся соседи
+pub const UNIT_HASH_CELL_SIZE: f32 = 4.0;
+/// Радиус, в котором юнит замечает соседей
+const NEIGHBOR_RADIUS: f32 = 4.0;
+/// Дополнительное личное пространство сверх суммы радиусов
+const SEPARATION_MARGIN: f32 = 0.4;
+/// Насколько вперед (в секундах) юнит просчитывает столкновения
+const AVOIDANCE_HORIZON: f32 = 1.5;
+const SEPARATION_WEIGHT: f32 = 1.5;
+const AVOIDANCE_WEIGHT: f32 = 1.0;
+const INFANTRY_RADIUS: f32 = 0.4;
+const TANK_RADIUS: f32 = 1.0;
+
+/// Steering runs after the paths are planned and before units move
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SteeringSet;
+
+/// Ground unit as seen by its neighbours this frame
+#[derive(Clone, Copy, Debug)]
+pub struct HashedUnit {
+    pub entity: Entity,
+    pub position: Vec3,
+    /// Желаемая скорость на плоскости XZ; ноль - юнит стоит
+    pub velocity: Vec3,
+    pub radius: f32,
+}
+
+/// Spatial hash of ground unit positions, rebuilt every frame
+#[derive(Resource, Default)]
+pub struct UnitSpatialHash {
+    cells: HashMap<IVec2, Vec<HashedUnit>>,
+}
+
+type HashedUnitData<'a> = (
+    Entity,
+    &'a Transform,
+    Option<&'a MovementOrder>,
+    Option<&'a MovementPath>,
+    Option<&'a MilitaryUnit>,
+    Option<&'a FormationSpeed>,
+    Has<Tank>,
+);
+type GroundUnitFilter = (Or<(With<Infantry>, With<Tank>)>, Without<Aircraft>);
+
+fn flat(vector: Vec3) -> Vec3 {
+    Vec3::new(vector.x, 0.0, vector.z)
+}
+
+/// Footprint radius of a ground unit
+pub fn unit_radius(is_tank: bool) -> f32 {
+    if is_tank { TANK_RADIUS } else { INFANTRY_RADIUS }
+}
+
+/// Speed of a unit following an order: its own speed, capped by the group's pace in formation
+pub fn order_speed(military_unit: Option<&MilitaryUnit>, formation_speed: Option<&FormationSpeed>) -> f32 {
+    formation_speed.map_or(move_speed(military_unit), |pace| move_speed(military_unit).min(pace.0))
+}
+
+impl UnitSpatialHash {
+    fn cell_of(position: Vec3) -> IVec2 {
+        (Vec2::new(position.x, position.z) / UNIT_HASH_CELL_SIZE).floor().as_ivec2()
+    }
+
+    pub fn clear(&mut self) {
+        self.cells.clear();
+    }
+
+    pub fn insert(&mut self, unit: HashedUnit) {
+        self.cells.entry(Self::cell_of(unit.position)).or_default().push(unit);
+    }
+
+    /// Units whose cells overlap the square of `radius` around `position`
+    pub fn neighbors(&self, position: Vec3, radius: f32) -> impl Iterator<Item = &HashedUnit> {
+        let min = Self::cell_of(position - Vec3::new(radius, 0.0, radius));
+        let max = Self::cell_of(position + Vec3::new(radius, 0.0, radius));
+        (min.y..=max.y)
+            .flat_map(move |y| (min.x..=max.x).map(move |x| IVec2::new(x, y)))
+            .filter_map(|cell| self.cells.get(&cell))
+            .flatten()
+    }
+
+    /// Another unit is standing on `goal`, so the mover can stop next to it
+    pub fn goal_taken(&self, entity: Entity, goal: Vec3, radius: f32) -> bool {
+        self.neighbors(goal, radius + TANK_RADIUS).any(|other| {
+            other.entity != entity
+                && other.velocity == Vec3::ZERO
+                && flat(other.position - goal).length() < radius + other.radius
+        })
+    }
+
+    /// Adjusts the desired velocity so the unit keeps its distance from neighbours (separation)
+    /// and sidesteps the ones it would run into within the horizon (reciprocal avoidance).
+    /// Moving neighbours dodge their half of the way, stationary ones are walked around.
+    pub fn steer(&self, entity: Entity, position: Vec3, velocity: Vec3, radius: f32) -> Vec3 {
+        let desired = flat(velocity);
+        let max_speed = desired.length();
+        if max_speed <= f32::EPSILON {
+            return velocity;
+        }
+
+        let mut separation = Vec3::ZERO;
+        let mut avoidance = Vec3::ZERO;
+        for other in self.neighbors(position, NEIGHBOR_RADIUS) {
+            if other.entity == entity {
+                continue;
+            }
+
+            let offset = flat(position - other.position);
+            let distance = offset.length();
+            let personal_space = radius + other.radius + SEPARATION_MARGIN;
+            if distance < personal_space {
+                // Юниты в одной точке расходятся в стороны по порядку сущностей, а не по случайности
+                let away = if distance > 1e-4 {
+                    offset / distance
+                } else if entity < other.entity {
+                    Vec3::new(-desired.z, 0.0, desired.x) / max_speed
+                } else {
+                    Vec3::new(desired.z, 0.0, -desired.x) / max_speed
+                };
+                separation += away * (1.0 - distance / personal_space);
+            }
+
+            let moving = other.velocity != Vec3::ZERO;
+            let relative_velocity = desired - other.velocity;
+            let speed_squared = relative_velocity.length_squared();
+            if speed_squared <= f32::EPSILON {
+                continue;
+            }
+            // Момент наибольшего сближения и промах в этот момент
+            let time_to_closest = (-offset).dot(relative_velocity) / speed_squared;
+            if time_to_closest <= 0.0 || time_to_closest > AVOIDANCE_HORIZON {
+                continue;
+            }
+            let miss = offset + relative_velocity * time_to_closest;
+            let combined_radius = radius + other.radius;
+            if miss.length() >= combined_radius {
+                continue;
+            }
+            // Лоб в лоб оба уходят вправо и расходятся
+            let dodge = if miss.length_squared() > 1e-4 {
+                miss.normalize()
+            } else {
+                Vec3::new(-relative_velocity.z, 0.0, relative_velocity.x).normalize()
+            };
+            let responsibility = if moving { 0.5 } else { 1.0 };
+            avoidance += dodge * responsibility * (1.0 - time_to_closest / AVOIDANCE_HORIZON);
+        }
+
+        let steering = (separation * SEPARATION_WEIGHT + avoidance * AVOIDANCE_WEIGHT) * max_speed;
+        let steered = (desired + steering).clamp_length_max(max_speed);
+        Vec3::new(steered.x, velocity.y, steered.z)
+    }
+}
+
+/// Puts every ground unit into the hash together with the velocity it wants this frame
+pub fn update_unit_spatial_hash(
+    mut spatial_hash: ResMut<UnitSpatialHash>,
+    units: Query<HashedUnitData, GroundUnitFilter>,
+) {
+    spatial_hash.clear();
+    for (entity, transform, order, path, military_unit, formation_speed, is_tank) in units.iter() {
+        let velocity = order
+            .map(|order| path.and_then(|path| path.waypoint_for(order)).unwrap_or(order.0))
+            .map(|target| flat(target - transform.translation))
+            .filter(|direction| direction.length_squared() > 0.01)
+            .map_or(Vec3::ZERO, |direction| direction.normalize() * order_speed(military_unit, formation_speed));
+        spatial_hash.insert(HashedUnit {
+            entity,
+            position: transform.translation,
+            velocity,
+            radius: unit_radius(is_tank),
+        });
+    }
+}
+
+/// Registers the unit spatial hash used for local avoidance
+pub struct SteeringPlugin;
+
+impl Plugin for SteeringPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<UnitSpatialHash>()
+            .add_systems(Update, update_unit_spatial_hash.in_set(SteeringSet));
+    }
+}
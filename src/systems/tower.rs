@@ -1,24 +1,326 @@
 use bevy::prelude::*;
-use bevy_mod_picking::prelude::*;
-use crate::game::{Health, Tower};
+use crate::game::{Aircraft, EnemyTower, Health, Tank, Tower};
+use crate::game::units::infantry::Infantry;
+use crate::game::units::armor::{ArmorClass, DamageMatrix, DamageType};
+use crate::systems::ai_opponent::{AIPlayers, AISide, Wallet};
+use crate::systems::fog_of_war::FogOfWar;
+use crate::systems::projectiles::{fire_projectile, ProjectileKind, Shot, TargetClass};
+use crate::systems::spatial_index::{TargetIndex, TargetKind};
+use crate::systems::turn_system::{PlayerTurn, TurnState};
+use crate::ui::money_ui::ResourceCost;
+use crate::utils::GameRng;
 
-/// System to repair tower when clicked while selected
+/// Цена одного очка прочности башни
+pub const TOWER_REPAIR_COST_PER_HP: ResourceCost = ResourceCost { money: 0.5, wood: 0.3, iron: 0.0, steel: 0.1, oil: 0.0 };
+/// Сколько прочности можно вернуть одной башне за ход
+pub const TOWER_REPAIR_PER_TURN: f32 = 150.0;
+/// Сколько секунд после ремонта над башней виден индикатор
+const TOWER_REPAIR_INDICATOR_TIME: f32 = 1.5;
+
+/// Repair a tower got in the current turn: drives the per-turn limit and the progress indicator
+#[derive(Component, Debug, Clone, Copy)]
+pub struct TowerRepair {
+    pub turn_number: u32,
+    pub player: PlayerTurn,
+    pub repaired: f32,
+    /// Время последнего ремонта, для индикатора
+    pub last_repair: Option<f32>,
+}
+
+impl Default for TowerRepair {
+    fn default() -> Self {
+        Self {
+            turn_number: 0,
+            player: PlayerTurn::Human,
+            repaired: 0.0,
+            last_repair: None,
+        }
+    }
+}
+
+impl TowerRepair {
+    fn is_current(&self, turn_state: &TurnState) -> bool {
+        self.turn_number == turn_state.turn_number && self.player == turn_state.current_player
+    }
+
+    /// Прочность, которую еще можно вернуть в этот ход
+    pub fn remaining(&self, turn_state: &TurnState) -> f32 {
+        if self.is_current(turn_state) {
+            (TOWER_REPAIR_PER_TURN - self.repaired).max(0.0)
+        } else {
+            TOWER_REPAIR_PER_TURN
+        }
+    }
+}
+
+/// What came out of an attempt to repair a tower
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TowerRepairResult {
+    Repaired(f32),
+    Intact,
+    TurnLimit,
+    NotEnoughResources,
+}
+
+/// Price of `hp` points of tower repair
+pub fn tower_repair_cost(hp: f32) -> ResourceCost {
+    ResourceCost {
+        money: TOWER_REPAIR_COST_PER_HP.money * hp,
+        wood: TOWER_REPAIR_COST_PER_HP.wood * hp,
+        iron: TOWER_REPAIR_COST_PER_HP.iron * hp,
+        steel: TOWER_REPAIR_COST_PER_HP.steel * hp,
+        oil: TOWER_REPAIR_COST_PER_HP.oil * hp,
+    }
+}
+
+/// How many points of repair `stock` pays for
+fn affordable_repair(stock: &ResourceCost) -> f32 {
+    [
+        (stock.money, TOWER_REPAIR_COST_PER_HP.money),
+        (stock.wood, TOWER_REPAIR_COST_PER_HP.wood),
+        (stock.iron, TOWER_REPAIR_COST_PER_HP.iron),
+        (stock.steel, TOWER_REPAIR_COST_PER_HP.steel),
+        (stock.oil, TOWER_REPAIR_COST_PER_HP.oil),
+    ]
+    .into_iter()
+    .filter(|(_, price)| *price > 0.0)
+    .map(|(amount, price)| (amount / price).max(0.0))
+    .fold(f32::INFINITY, f32::min)
+}
+
+/// Restores up to `amount` points of a tower, within this turn's limit and paid from `wallet`.
+/// Used by engineers for the player and by the AI commander for its own towers
 pub fn repair_tower(
-    mut click_events: EventReader<Pointer<Click>>,
-    mut query: Query<&mut Health, With<Tower>>,
-    _time: Res<Time>,
+    health: &mut Health,
+    repair: &mut TowerRepair,
+    wallet: &mut Wallet,
+    amount: f32,
+    turn_state: &TurnState,
+    now: f32,
+) -> TowerRepairResult {
+    if health.current >= health.max {
+        return TowerRepairResult::Intact;
+    }
+    if !repair.is_current(turn_state) {
+        *repair = TowerRepair {
+            turn_number: turn_state.turn_number,
+            player: turn_state.current_player,
+            ..default()
+        };
+    }
+    let remaining = repair.remaining(turn_state);
+    if remaining <= 0.0 {
+        return TowerRepairResult::TurnLimit;
+    }
+
+    let hp = amount
+        .min(health.max - health.current)
+        .min(remaining)
+        .min(affordable_repair(&wallet.amounts()));
+    if hp <= 0.0 {
+        return TowerRepairResult::NotEnoughResources;
+    }
+
+    wallet.deduct(&tower_repair_cost(hp));
+    health.current += hp;
+    repair.repaired += hp;
+    repair.last_repair = Some(now);
+    TowerRepairResult::Repaired(hp)
+}
+
+/// Draws a bar over towers under repair: health in green, this turn's repair limit used in yellow
+pub fn draw_tower_repair_progress(
+    mut gizmos: Gizmos,
+    time: Res<Time>,
+    turn_state: Res<TurnState>,
+    towers: Query<(&Transform, &Health, &TowerRepair), With<Tower>>,
 ) {
-    for event in click_events.read() {
-        if event.button != PointerButton::Secondary {
+    let now = time.elapsed_seconds();
+    for (transform, health, repair) in towers.iter() {
+        if repair.last_repair.is_none_or(|last| now - last > TOWER_REPAIR_INDICATOR_TIME) {
             continue;
         }
-        
-        if let Ok(mut health) = query.get_mut(event.target) {
-            // Right-click on a tower repairs it
-            let repair_amount = 10.0;
-            health.current = (health.current + repair_amount).min(health.max);
-            info!("Tower repaired: {}/{}", health.current, health.max);
+        let start = transform.translation + Vec3::new(-2.0, 8.0, 0.0);
+        let health_fraction = (health.current / health.max).clamp(0.0, 1.0);
+        let used_fraction = 1.0 - repair.remaining(&turn_state) / TOWER_REPAIR_PER_TURN;
+
+        gizmos.line(start, start + Vec3::new(4.0, 0.0, 0.0), Color::DARK_GRAY);
+        gizmos.line(start, start + Vec3::new(4.0 * health_fraction, 0.0, 0.0), Color::GREEN);
+        let limit_start = start - Vec3::Y * 0.3;
+        gizmos.line(limit_start, limit_start + Vec3::new(4.0 * used_fraction, 0.0, 0.0), Color::YELLOW);
+    }
+}
+
+/// Последний уровень турели башни
+pub const MAX_TURRET_LEVEL: u32 = 3;
+/// Слот центральной башни; на ней стоит зенитная турель
+const ANTI_AIR_SLOT: usize = 1;
+
+/// What a tower turret is built to shoot at first
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TurretRole {
+    /// Зенитка: сначала самолеты, потом наземные цели
+    AntiAir,
+    /// Пушка: сначала наземные цели, потом самолеты
+    Ground,
+}
+
+impl TurretRole {
+    pub fn for_slot(slot: usize) -> Self {
+        if slot == ANTI_AIR_SLOT { TurretRole::AntiAir } else { TurretRole::Ground }
+    }
+
+    fn damage_type(self) -> DamageType {
+        match self {
+            TurretRole::AntiAir => DamageType::SmallArms,
+            TurretRole::Ground => DamageType::Cannon,
+        }
+    }
+
+    fn projectile(self) -> ProjectileKind {
+        match self {
+            TurretRole::AntiAir => ProjectileKind::Bullet,
+            TurretRole::Ground => ProjectileKind::Shell,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            TurretRole::AntiAir => "AA",
+            TurretRole::Ground => "Cannon",
+        }
+    }
+}
+
+/// The gun on top of a tower: a `CanShoot` whose stats come from the upgrade level.
+/// Towers keep it separate from `CanShoot`, so they never count as units
+#[derive(Component, Debug, Clone, Copy)]
+pub struct TowerTurret {
+    pub role: TurretRole,
+    pub level: u32,
+    pub last_shot: f32,
+}
+
+impl TowerTurret {
+    pub fn new(role: TurretRole) -> Self {
+        Self { role, level: 1, last_shot: 0.0 }
+    }
+
+    /// Дальность не больше обзора башни (18), иначе она стреляла бы вслепую
+    pub fn range(&self) -> f32 {
+        let base = match self.role {
+            TurretRole::AntiAir => 14.0,
+            TurretRole::Ground => 12.0,
+        };
+        base + 2.0 * (self.level - 1) as f32
+    }
+
+    pub fn damage(&self) -> f32 {
+        let base = match self.role {
+            TurretRole::AntiAir => 18.0,
+            TurretRole::Ground => 30.0,
+        };
+        base * (1.0 + 0.35 * (self.level - 1) as f32)
+    }
+
+    /// Секунды между выстрелами
+    pub fn cooldown(&self) -> f32 {
+        let base = match self.role {
+            TurretRole::AntiAir => 1.5,
+            TurretRole::Ground => 3.0,
+        };
+        base * (1.0 - 0.15 * (self.level - 1) as f32)
+    }
+
+    /// Price of the next level, None at the last one
+    pub fn upgrade_cost(&self) -> Option<ResourceCost> {
+        match self.level {
+            1 => Some(ResourceCost { money: 150.0, wood: 20.0, iron: 30.0, steel: 15.0, oil: 0.0 }),
+            2 => Some(ResourceCost { money: 300.0, wood: 30.0, iron: 60.0, steel: 40.0, oil: 10.0 }),
+            _ => None,
+        }
+    }
+}
+
+type TurretTower<'a> = (Entity, &'a Transform, &'a mut TowerTurret, &'a Health, Has<EnemyTower>);
+type TurretTarget<'a> = (Entity, &'a Transform, Has<Infantry>, Has<Tank>, Has<Aircraft>);
+
+/// Tower turrets pick the nearest enemy unit in range, preferring their role's targets,
+/// and fire through the same projectile and damage matrix pipeline as units, only on their side's turn
+#[allow(clippy::too_many_arguments)]
+pub fn tower_turret_fire_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    turn_state: Res<TurnState>,
+    asset_server: Res<AssetServer>,
+    damage_matrix: Res<DamageMatrix>,
+    target_index: Res<TargetIndex>,
+    fog: Res<FogOfWar>,
+    ai_players: Res<AIPlayers>,
+    mut rng: ResMut<GameRng>,
+    mut towers: Query<TurretTower, With<Tower>>,
+    targets: Query<TurretTarget, With<Health>>,
+) {
+    let current_time = time.elapsed_seconds();
+
+    for (tower, transform, mut turret, health, is_enemy_tower) in towers.iter_mut() {
+        if health.current <= 0.0 || current_time - turret.last_shot < turret.cooldown() {
+            continue;
+        }
+        let side = AISide::of(is_enemy_tower);
+        if turn_state.current_player != side.turn() {
+            continue;
         }
+        let range = turret.range();
+        let ai_player = ai_players.get(side);
+        // Башни игрока бьют только по видимым сквозь туман целям, башни ИИ - по правилам его сложности
+        let respects_fog = ai_player.is_none_or(|ai_player| ai_player.behavior.difficulty.respects_fog_of_war());
+
+        // Сначала цели своего приоритета, если таких нет - любой видимый юнит в радиусе
+        let wants_air = turret.role == TurretRole::AntiAir;
+        let find_target = |preferred_only: bool| {
+            target_index
+                .nearest(transform.translation, range, side.opponent(), |target| {
+                    target.kind == TargetKind::Unit
+                        && (!respects_fog || fog.knows(side, target.position, target.kind))
+                        && (!preferred_only || targets.get(target.entity).is_ok_and(|(.., aircraft)| aircraft == wants_air))
+                })
+                .and_then(|target| targets.get(target.entity).ok())
+        };
+        let Some((target, target_transform, target_infantry, target_tank, target_aircraft)) =
+            find_target(true).or_else(|| find_target(false))
+        else {
+            continue;
+        };
+
+        fire_projectile(&mut commands, &mut rng, Shot {
+            shooter: tower,
+            from: transform.translation,
+            target,
+            target_pos: target_transform.translation,
+            target_class: TargetClass::of(target_infantry, target_tank, target_aircraft),
+            kind: turret.role.projectile(),
+            damage: damage_matrix.apply(
+                turret.damage(),
+                turret.role.damage_type(),
+                ArmorClass::of(target_infantry, target_tank, target_aircraft),
+            ),
+            range,
+            from_enemy: is_enemy_tower,
+            // Башни ИИ целятся с меткостью его сложности, как и его юниты
+            accuracy: ai_player.map_or(1.0, |ai_player| ai_player.behavior.difficulty.hit_chance()),
+        });
+        turret.last_shot = current_time;
+
+        let audio_source = match turret.role {
+            TurretRole::AntiAir => asset_server.load("audio/gun.mp3"),
+            TurretRole::Ground => asset_server.load("audio/tank_shot.mp3"),
+        };
+        commands.spawn(AudioBundle {
+            source: audio_source,
+            settings: PlaybackSettings::ONCE,
+        });
+        info!("🏰 Tower {:?} ({} lvl {}) fired at {:?}", tower, turret.role.label(), turret.level, target);
     }
 }
 
@@ -4,6 +4,7 @@ use crate::menu::common::GameState;
 use crate::game::components::*;
 use crate::ui::money_ui::*;
 use crate::systems::twitter_client::TwitterClient;
+use crate::systems::ai_opponent::AISide;
 use serde::Deserialize;
 
 #[derive(Resource, Default)]
@@ -11,6 +12,45 @@ pub struct VictoryState {
     pub game_ended: bool,
     pub victory_timer: Option<Timer>,
     pub defeat_timer: Option<Timer>,
+    /// Все шесть башен были на поле (разрушенные башни удаляются из мира)
+    pub towers_spawned: bool,
+}
+
+impl VictoryState {
+    /// Winner by towers, or None while both sides still have a tower standing.
+    /// Nothing is decided until all three towers of each side have been seen.
+    pub fn tower_winner(&mut self, player_towers_alive: usize, enemy_towers_alive: usize) -> Option<AISide> {
+        if player_towers_alive >= 3 && enemy_towers_alive >= 3 {
+            self.towers_spawned = true;
+        }
+        if !self.towers_spawned {
+            return None;
+        }
+
+        if enemy_towers_alive == 0 {
+            Some(AISide::Player)
+        } else if player_towers_alive == 0 {
+            Some(AISide::Enemy)
+        } else {
+            None
+        }
+    }
+}
+
+/// Башни игрока
+pub type PlayerTowerFilter = (With<Tower>, Without<EnemyTower>);
+/// Башни ИИ
+pub type EnemyTowerFilter = (With<Tower>, With<EnemyTower>);
+
+/// Number of towers with health left on each side (player, enemy)
+pub fn count_towers_alive(
+    player_towers: &Query<&Health, PlayerTowerFilter>,
+    enemy_towers: &Query<&Health, EnemyTowerFilter>,
+) -> (usize, usize) {
+    (
+        player_towers.iter().filter(|h| h.current > 0.0).count(),
+        enemy_towers.iter().filter(|h| h.current > 0.0).count(),
+    )
 }
 
 #[derive(Component)]
@@ -102,8 +142,8 @@ pub fn check_victory_conditions(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
     mut victory_state: ResMut<VictoryState>,
-    player_towers: Query<&Health, (With<Tower>, Without<EnemyTower>)>,
-    enemy_towers: Query<&Health, (With<Tower>, With<EnemyTower>)>,
+    player_towers: Query<&Health, PlayerTowerFilter>,
+    enemy_towers: Query<&Health, EnemyTowerFilter>,
     twitter_config: Res<TwitterConfig>,
 ) {
     if victory_state.game_ended {
@@ -118,18 +158,13 @@ pub fn check_victory_conditions(
     if total_player_towers > 0 || total_enemy_towers > 0 {
         println!("🏰 Tower count - Player: {}, Enemy: {}", total_player_towers, total_enemy_towers);
     }
-    
-    // Don't check victory conditions until all towers are spawned (should be 3 each)
-    if total_player_towers < 3 || total_enemy_towers < 3 {
-        return;
-    }
 
     // Count alive towers
-    let player_towers_alive = player_towers.iter().filter(|h| h.current > 0.0).count();
-    let enemy_towers_alive = enemy_towers.iter().filter(|h| h.current > 0.0).count();
+    let (player_towers_alive, enemy_towers_alive) = count_towers_alive(&player_towers, &enemy_towers);
+    let winner = victory_state.tower_winner(player_towers_alive, enemy_towers_alive);
 
     // Check victory condition (all enemy towers destroyed)
-    if enemy_towers_alive == 0 {
+    if winner == Some(AISide::Player) {
         victory_state.game_ended = true;
         victory_state.victory_timer = Some(Timer::from_seconds(3.0, TimerMode::Once));
         
@@ -153,7 +188,7 @@ pub fn check_victory_conditions(
         println!("🎉 VICTORY! All enemy towers destroyed!");
     }
     // Check defeat condition (all player towers destroyed)
-    else if player_towers_alive == 0 {
+    else if winner == Some(AISide::Enemy) {
         victory_state.game_ended = true;
         victory_state.defeat_timer = Some(Timer::from_seconds(3.0, TimerMode::Once));
         
@@ -268,6 +303,7 @@ pub fn handle_victory_timers(
             game_state.set(GameState::Menu);
             victory_state.victory_timer = None;
             victory_state.game_ended = false;
+            victory_state.towers_spawned = false;
             
             println!("🏠 Returning to main menu after victory");
         }
@@ -292,6 +328,7 @@ pub fn handle_victory_timers(
             game_state.set(GameState::Menu);
             victory_state.defeat_timer = None;
             victory_state.game_ended = false;
+            victory_state.towers_spawned = false;
             
             println!("🏠 Returning to main menu after defeat");
         }
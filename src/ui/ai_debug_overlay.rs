@@ -0,0 +1,86 @@
+use bevy::prelude::*;
+use crate::menu::common::GameState;
+use crate::game_plugin::OnGameScreen;
+use crate::systems::ai_scheduler::{AIDecision, AIScheduler};
+use crate::input::key_bindings::{InputAction, KeyBindings};
+
+/// Текст отладочной панели расписания ИИ
+#[derive(Component)]
+pub struct AIDebugOverlayText;
+
+/// Plugin for the AI scheduler debug overlay (toggled with F3)
+pub struct AIDebugOverlayPlugin;
+
+impl Plugin for AIDebugOverlayPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (toggle_ai_debug_overlay, update_ai_debug_overlay).chain().run_if(in_state(GameState::Game)),
+        );
+    }
+}
+
+fn toggle_ai_debug_overlay(
+    mut commands: Commands,
+    keys: Res<ButtonInput<KeyCode>>,
+    key_bindings: Res<KeyBindings>,
+    asset_server: Res<AssetServer>,
+    overlay_query: Query<Entity, With<AIDebugOverlayText>>,
+) {
+    if !key_bindings.just_pressed(InputAction::AIDebugOverlay, &keys) {
+        return;
+    }
+
+    if let Ok(entity) = overlay_query.get_single() {
+        commands.entity(entity).despawn_recursive();
+        return;
+    }
+
+    commands.spawn((
+        TextBundle::from_section(
+            "",
+            TextStyle {
+                font: asset_server.load("fonts/GrenzeGotisch-Light.ttf"),
+                font_size: 16.0,
+                color: Color::YELLOW,
+            },
+        )
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            top: Val::Px(160.0),
+            right: Val::Px(10.0),
+            padding: UiRect::all(Val::Px(8.0)),
+            ..default()
+        })
+        .with_background_color(Color::rgba(0.0, 0.0, 0.0, 0.7)),
+        AIDebugOverlayText,
+        OnGameScreen,
+    ));
+    info!("🐞 AI scheduler overlay enabled");
+}
+
+fn update_ai_debug_overlay(
+    scheduler: Res<AIScheduler>,
+    mut overlay_query: Query<&mut Text, With<AIDebugOverlayText>>,
+) {
+    let Ok(mut text) = overlay_query.get_single_mut() else {
+        return;
+    };
+
+    let mut lines = vec!["AI scheduler".to_string()];
+    for schedule in scheduler.schedules.iter() {
+        let timers: Vec<String> = AIDecision::ALL
+            .iter()
+            .map(|decision| {
+                let timer = schedule.timer(*decision);
+                if timer.finished() {
+                    format!("{:?}: ready", decision)
+                } else {
+                    format!("{:?}: {:.1}s", decision, timer.remaining_secs())
+                }
+            })
+            .collect();
+        lines.push(format!("{:?} [{:?}]  {}", schedule.side, schedule.difficulty, timers.join("  ")));
+    }
+    text.sections[0].value = lines.join("\n");
+}
@@ -0,0 +1,48 @@
+use bevy::prelude::*;
+use serde::Deserialize;
+use crate::utils::BalanceAsset;
+use super::money_ui::{PurchasableItem, ResourceCost};
+
+/// Путь к таблице цен внутри assets/
+pub const COST_TABLE_PATH: &str = "balance/default.costs.toml";
+
+/// Prices of purchasable items loaded from assets/balance/default.costs.toml.
+/// The file is watched, so edits are picked up while the game is running.
+/// Until `BalanceLoaded<CostTable>` exists every price is zero, so purchases wait for it.
+#[derive(Asset, TypePath, Resource, Deserialize, Clone, Debug, Default)]
+pub struct CostTable {
+    #[serde(default)]
+    pub tank: ResourceCost,
+    #[serde(default)]
+    pub infantry: ResourceCost,
+    #[serde(default)]
+    pub airplane: ResourceCost,
+    #[serde(default)]
+    pub farm: ResourceCost,
+    #[serde(default)]
+    pub mine: ResourceCost,
+    #[serde(default)]
+    pub steel_factory: ResourceCost,
+    #[serde(default)]
+    pub petrochemical_plant: ResourceCost,
+}
+
+impl CostTable {
+    pub fn get(&self, item: PurchasableItem) -> ResourceCost {
+        match item {
+            PurchasableItem::Tank => self.tank,
+            PurchasableItem::Infantry => self.infantry,
+            PurchasableItem::Airplane => self.airplane,
+            PurchasableItem::Farm => self.farm,
+            PurchasableItem::Mine => self.mine,
+            PurchasableItem::SteelFactory => self.steel_factory,
+            PurchasableItem::PetrochemicalPlant => self.petrochemical_plant,
+        }
+    }
+}
+
+impl BalanceAsset for CostTable {
+    const PATH: &'static str = COST_TABLE_PATH;
+    const EXTENSION: &'static str = "costs.toml";
+    const NAME: &'static str = "Cost table";
+}
@@ -4,21 +4,25 @@ use crate::game::components::{
     Farm, Mine, SteelFactory, PetrochemicalPlant
 };
 use crate::game::units::infantry::Infantry;
+use crate::systems::fog_of_war::FogHidden;
 
-/// System to draw health bars above towers and units
+/// Health and position of everything matching `F`, except enemies hidden by the fog of war
+type HealthBarQuery<'w, 's, F> = Query<'w, 's, (&'static Transform, &'static Health), (F, Without<FogHidden>)>;
+
+/// System to draw health bars above towers and units (enemies hidden by the fog get none)
 pub fn draw_health_bars(
     mut gizmos: Gizmos,
     // Towers
-    tower_query: Query<(&Transform, &Health), With<Tower>>,
+    tower_query: HealthBarQuery<With<Tower>>,
     // Units
-    tank_query: Query<(&Transform, &Health), (With<Tank>, Without<Tower>)>,
-    aircraft_query: Query<(&Transform, &Health), (With<Aircraft>, Without<Tower>, Without<Tank>)>,
-    infantry_query: Query<(&Transform, &Health), (With<Infantry>, Without<Tower>, Without<Tank>, Without<Aircraft>)>,
+    tank_query: HealthBarQuery<(With<Tank>, Without<Tower>)>,
+    aircraft_query: HealthBarQuery<(With<Aircraft>, Without<Tower>, Without<Tank>)>,
+    infantry_query: HealthBarQuery<(With<Infantry>, Without<Tower>, Without<Tank>, Without<Aircraft>)>,
     // Buildings
-    farm_query: Query<(&Transform, &Health), (With<Farm>, Without<Tower>, Without<Tank>, Without<Aircraft>, Without<Infantry>)>,
-    mine_query: Query<(&Transform, &Health), (With<Mine>, Without<Tower>, Without<Tank>, Without<Aircraft>, Without<Infantry>, Without<Farm>)>,
-    steel_factory_query: Query<(&Transform, &Health), (With<SteelFactory>, Without<Tower>, Without<Tank>, Without<Aircraft>, Without<Infantry>, Without<Farm>, Without<Mine>)>,
-    oil_pump_query: Query<(&Transform, &Health), (With<PetrochemicalPlant>, Without<Tower>, Without<Tank>, Without<Aircraft>, Without<Infantry>, Without<Farm>, Without<Mine>, Without<SteelFactory>)>,
+    farm_query: HealthBarQuery<(With<Farm>, Without<Tower>, Without<Tank>, Without<Aircraft>, Without<Infantry>)>,
+    mine_query: HealthBarQuery<(With<Mine>, Without<Tower>, Without<Tank>, Without<Aircraft>, Without<Infantry>, Without<Farm>)>,
+    steel_factory_query: HealthBarQuery<(With<SteelFactory>, Without<Tower>, Without<Tank>, Without<Aircraft>, Without<Infantry>, Without<Farm>, Without<Mine>)>,
+    oil_pump_query: HealthBarQuery<(With<PetrochemicalPlant>, Without<Tower>, Without<Tank>, Without<Aircraft>, Without<Infantry>, Without<Farm>, Without<Mine>, Without<SteelFactory>)>,
 ) {
     // Draw health bars for towers
     for (transform, health) in tower_query.iter() {
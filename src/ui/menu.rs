@@ -3,7 +3,7 @@ use bevy::prelude::*;
 use crate::menu::{
     common::{GameState, MenuState, despawn_screen},
     main_menu::{main_menu_plugin, menu_action, OnMainMenuScreen},
-    settings_menu::{settings_menu_plugin, OnSettingsMenuScreen, OnDisplaySettingsMenuScreen, OnSoundSettingsMenuScreen},
+    settings_menu::{settings_menu_plugin, OnSettingsMenuScreen, OnDisplaySettingsMenuScreen, OnSoundSettingsMenuScreen, OnControlsSettingsMenuScreen},
     pause_menu::pause_menu_plugin,
 };
 
@@ -25,7 +25,8 @@ pub fn menu_plugin(app: &mut App) {
         .add_systems(OnExit(MenuState::Main), despawn_screen::<OnMainMenuScreen>)
         .add_systems(OnExit(MenuState::Settings), despawn_screen::<OnSettingsMenuScreen>)
         .add_systems(OnExit(MenuState::SettingsDisplay), despawn_screen::<OnDisplaySettingsMenuScreen>)
-        .add_systems(OnExit(MenuState::SettingsSound), despawn_screen::<OnSoundSettingsMenuScreen>);
+        .add_systems(OnExit(MenuState::SettingsSound), despawn_screen::<OnSoundSettingsMenuScreen>)
+        .add_systems(OnExit(MenuState::SettingsControls), despawn_screen::<OnControlsSettingsMenuScreen>);
 }
 
 fn cleanup_all_menu_ui(
@@ -150,7 +151,7 @@ fn reset_game_state(
     // Состояние игры
     mut turn_state: ResMut<crate::systems::turn_system::TurnState>,
     mut victory_state: ResMut<crate::systems::victory_system::VictoryState>,
-    mut selected_entity: ResMut<crate::game::SelectedEntity>,
+    mut selection: ResMut<crate::game::Selection>,
     // Состояние размещения
     mut placement_state: ResMut<crate::game::PlacementState>,
     // Другие ресурсы которые могли быть изменены
@@ -170,12 +171,13 @@ fn reset_game_state(
     victory_state.game_ended = false;
     
     // Сбрасываем выделенный юнит
-    selected_entity.0 = None;
+    selection.clear();
     
     // Сбрасываем состояние размещения
     placement_state.active = false;
     placement_state.shape_type = None;
     placement_state.unit_type_index = None;
+    placement_state.cost = None;
     
     // Сбрасываем состояние камеры
     camera_movement_state.manual_camera_mode = false;
@@ -0,0 +1,335 @@
+use bevy::prelude::*;
+use bevy::render::render_asset::RenderAssetUsages;
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+
+use crate::game::{CameraMovementState, CanShoot, Enemy, EnemyTower, Health, MainCamera, Tower};
+use crate::game_plugin::OnGameScreen;
+use crate::input::camera::{ground_point, CameraRig};
+use crate::menu::common::{GameState, OnMatchEnd, OnMatchStart};
+use crate::systems::ai_opponent::AISide;
+use crate::systems::fog_of_war::{FogHidden, FogOfWar, FogState};
+use crate::systems::pathfinding::NAV_GRID_HALF_EXTENT;
+use crate::systems::spatial_index::TargetKind;
+use crate::input::key_bindings::{InputAction, KeyBindings};
+
+/// Сторона текстуры миникарты в пикселях: один пиксель на единицу поля 120x120
+const MINIMAP_TEXTURE_SIZE: u32 = 120;
+/// Размер миникарты на экране
+const MINIMAP_SCREEN_SIZE: f32 = 180.0;
+/// Сколько секунд живет метка
+const PING_LIFETIME: f32 = 4.0;
+/// Радиус расходящегося кольца метки на миникарте, в пикселях
+const PING_MAX_RADIUS: f32 = 8.0;
+
+const GROUND_COLOR: [u8; 3] = [60, 100, 60];
+const PLAYER_COLOR: [u8; 3] = [40, 230, 40];
+const ENEMY_COLOR: [u8; 3] = [230, 40, 40];
+const FRUSTUM_COLOR: [u8; 3] = [255, 255, 255];
+const PING_COLOR: [u8; 3] = [255, 220, 0];
+
+/// The minimap frame; clicks and drags on it move the camera
+#[derive(Component)]
+pub struct MinimapFrame;
+
+/// Image node showing the minimap texture
+#[derive(Component)]
+pub struct MinimapImage(pub Handle<Image>);
+
+/// A ping dropped by the player: a point of interest that fades after a few seconds
+#[derive(Clone, Copy, Debug)]
+pub struct MinimapPing {
+    pub position: Vec3,
+    pub age: f32,
+}
+
+/// Active pings
+#[derive(Resource, Default)]
+pub struct MinimapPings(pub Vec<MinimapPing>);
+
+/// The left button went down on the minimap: dragging pans the camera until it is released
+#[derive(Resource, Default)]
+pub struct MinimapDrag(pub bool);
+
+type MinimapDot<'a> = (&'a Transform, Has<Enemy>, Has<EnemyTower>, Has<CanShoot>, Has<Tower>, Has<FogHidden>);
+
+/// Pixel of the minimap texture for a world position (top of the map is -Z, the enemy side)
+fn world_to_pixel(position: Vec3) -> IVec2 {
+    let scale = MINIMAP_TEXTURE_SIZE as f32 / (NAV_GRID_HALF_EXTENT * 2.0);
+    ((Vec2::new(position.x, position.z) + NAV_GRID_HALF_EXTENT) * scale).floor().as_ivec2()
+}
+
+fn pixel_to_world(pixel: Vec2) -> Vec3 {
+    let scale = NAV_GRID_HALF_EXTENT * 2.0 / MINIMAP_TEXTURE_SIZE as f32;
+    let xz = pixel * scale - NAV_GRID_HALF_EXTENT;
+    Vec3::new(xz.x, 0.0, xz.y)
+}
+
+fn put_pixel(data: &mut [u8], pixel: IVec2, color: [u8; 3]) {
+    let size = MINIMAP_TEXTURE_SIZE as i32;
+    if pixel.x < 0 || pixel.y < 0 || pixel.x >= size || pixel.y >= size {
+        return;
+    }
+    let index = ((pixel.y * size + pixel.x) * 4) as usize;
+    data[index..index + 3].copy_from_slice(&color);
+    data[index + 3] = 255;
+}
+
+fn fill_square(data: &mut [u8], center: IVec2, half_size: i32, color: [u8; 3]) {
+    for y in -half_size..=half_size {
+        for x in -half_size..=half_size {
+            put_pixel(data, center + IVec2::new(x, y), color);
+        }
+    }
+}
+
+/// Bresenham line, clipped to the texture
+fn draw_line(data: &mut [u8], from: IVec2, to: IVec2, color: [u8; 3]) {
+    let delta = IVec2::new((to.x - from.x).abs(), -(to.y - from.y).abs());
+    let step = IVec2::new((to.x - from.x).signum(), (to.y - from.y).signum());
+    let mut error = delta.x + delta.y;
+    let mut current = from;
+    // Отрезки за пределами поля могут быть длинными - ограничиваем число шагов
+    for _ in 0..MINIMAP_TEXTURE_SIZE * 4 {
+        put_pixel(data, current, color);
+        if current == to {
+            break;
+        }
+        let doubled = error * 2;
+        if doubled >= delta.y {
+            error += delta.y;
+            current.x += step.x;
+        }
+        if doubled <= delta.x {
+            error += delta.x;
+            current.y += step.y;
+        }
+    }
+}
+
+fn draw_ring(data: &mut [u8], center: IVec2, radius: f32, color: [u8; 3]) {
+    let points = (radius * 8.0).max(8.0) as i32;
+    for index in 0..points {
+        let angle = index as f32 / points as f32 * std::f32::consts::TAU;
+        let offset = (Vec2::new(angle.cos(), angle.sin()) * radius).round().as_ivec2();
+        put_pixel(data, center + offset, color);
+    }
+}
+
+/// Spawns the minimap in the bottom-left corner
+pub fn spawn_minimap(mut commands: Commands, mut images: ResMut<Assets<Image>>) {
+    let size = Extent3d { width: MINIMAP_TEXTURE_SIZE, height: MINIMAP_TEXTURE_SIZE, depth_or_array_layers: 1 };
+    let image = images.add(Image::new_fill(
+        size,
+        TextureDimension::D2,
+        &[GROUND_COLOR[0], GROUND_COLOR[1], GROUND_COLOR[2], 255],
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::default(),
+    ));
+
+    commands.spawn((
+        ButtonBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                left: Val::Px(10.0),
+                bottom: Val::Px(10.0),
+                width: Val::Px(MINIMAP_SCREEN_SIZE),
+                height: Val::Px(MINIMAP_SCREEN_SIZE),
+                border: UiRect::all(Val::Px(2.0)),
+                ..default()
+            },
+            border_color: Color::rgb(0.8, 0.8, 0.8).into(),
+            background_color: Color::BLACK.into(),
+            ..default()
+        },
+        MinimapFrame,
+        OnGameScreen,
+    )).with_children(|frame| {
+        frame.spawn((
+            ImageBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    ..default()
+                },
+                image: UiImage::new(image.clone()),
+                ..default()
+            },
+            MinimapImage(image),
+        ));
+    });
+}
+
+/// Redraws the minimap: ground under the fog, a dot per unit, building and tower, pings and the camera view
+pub fn draw_minimap(
+    fog: Res<FogOfWar>,
+    pings: Res<MinimapPings>,
+    minimap: Query<&MinimapImage>,
+    mut images: ResMut<Assets<Image>>,
+    dots: Query<MinimapDot, With<Health>>,
+    camera: Query<(&Camera, &GlobalTransform), With<MainCamera>>,
+) {
+    let Ok(minimap) = minimap.get_single() else {
+        return;
+    };
+    let Some(image) = images.get_mut(&minimap.0) else {
+        return;
+    };
+    let data = &mut image.data;
+
+    // Земля: неразведанное темнее, разведанное приглушено
+    for y in 0..MINIMAP_TEXTURE_SIZE as i32 {
+        for x in 0..MINIMAP_TEXTURE_SIZE as i32 {
+            let world = pixel_to_world(Vec2::new(x as f32 + 0.5, y as f32 + 0.5));
+            let shade = match fog.revealed {
+                true => 1.0,
+                false => match fog.state(AISide::Player, world) {
+                    FogState::Visible => 1.0,
+                    FogState::Explored => 0.6,
+                    FogState::Unexplored => 0.25,
+                },
+            };
+            put_pixel(data, IVec2::new(x, y), GROUND_COLOR.map(|channel| (channel as f32 * shade) as u8));
+        }
+    }
+
+    for (transform, is_enemy, is_enemy_tower, armed, is_tower, hidden) in dots.iter() {
+        if hidden {
+            continue;
+        }
+        let color = match AISide::of(is_enemy || is_enemy_tower) {
+            AISide::Player => PLAYER_COLOR,
+            AISide::Enemy => ENEMY_COLOR,
+        };
+        let half_size = match TargetKind::of(armed, is_tower) {
+            TargetKind::Unit => 0,
+            TargetKind::Building => 1,
+            TargetKind::Tower => 2,
+        };
+        fill_square(data, world_to_pixel(transform.translation), half_size, color);
+    }
+
+    for ping in pings.0.iter() {
+        let progress = ping.age / PING_LIFETIME;
+        draw_ring(data, world_to_pixel(ping.position), 1.0 + progress * PING_MAX_RADIUS, PING_COLOR);
+    }
+
+    // Контур того, что видит камера, на земле
+    if let Ok((camera, camera_transform)) = camera.get_single() {
+        if let Some(viewport) = camera.logical_viewport_rect() {
+            let corners = [
+                Vec2::new(viewport.min.x, viewport.min.y),
+                Vec2::new(viewport.max.x, viewport.min.y),
+                Vec2::new(viewport.max.x, viewport.max.y),
+                Vec2::new(viewport.min.x, viewport.max.y),
+            ];
+            let ground: Vec<IVec2> = corners.iter()
+                .filter_map(|corner| ground_point(camera, camera_transform, *corner - viewport.min))
+                .map(world_to_pixel)
+                .collect();
+            if ground.len() == corners.len() {
+                for index in 0..ground.len() {
+                    draw_line(data, ground[index], ground[(index + 1) % ground.len()], FRUSTUM_COLOR);
+                }
+            }
+        }
+    }
+}
+
+/// Click or drag on the minimap: the camera keeps its angle and height and looks at the chosen point
+pub fn minimap_camera_control(
+    buttons: Res<ButtonInput<MouseButton>>,
+    windows: Query<&Window>,
+    frame: Query<(&Node, &GlobalTransform, &Interaction), With<MinimapFrame>>,
+    mut drag: ResMut<MinimapDrag>,
+    mut camera_movement_state: ResMut<CameraMovementState>,
+    mut camera_rig: ResMut<CameraRig>,
+) {
+    let Ok((node, frame_transform, interaction)) = frame.get_single() else {
+        return;
+    };
+    if buttons.just_pressed(MouseButton::Left) && *interaction == Interaction::Pressed {
+        drag.0 = true;
+    }
+    if !buttons.pressed(MouseButton::Left) {
+        drag.0 = false;
+    }
+    if !drag.0 {
+        return;
+    }
+
+    let Some(cursor) = windows.get_single().ok().and_then(|window| window.cursor_position()) else {
+        return;
+    };
+    let rect = node.logical_rect(frame_transform);
+    let uv = ((cursor - rect.min) / rect.size()).clamp(Vec2::ZERO, Vec2::ONE);
+    let target = pixel_to_world(uv * MINIMAP_TEXTURE_SIZE as f32);
+
+    // Камера прыгает сразу, без плавного перелета, чтобы перетаскивание не отставало
+    camera_rig.focus = target;
+    camera_rig.target_focus = target;
+    // Камера больше не следит за выделенным юнитом
+    camera_movement_state.manual_camera_mode = true;
+}
+
+/// C drops a ping under the cursor: on the minimap if the cursor is over it, otherwise on the ground
+pub fn drop_minimap_ping(
+    keys: Res<ButtonInput<KeyCode>>,
+    key_bindings: Res<KeyBindings>,
+    windows: Query<&Window>,
+    frame: Query<(&Node, &GlobalTransform), With<MinimapFrame>>,
+    camera: Query<(&Camera, &GlobalTransform), With<MainCamera>>,
+    mut pings: ResMut<MinimapPings>,
+) {
+    if !key_bindings.just_pressed(InputAction::MinimapPing, &keys) {
+        return;
+    }
+    let Some(cursor) = windows.get_single().ok().and_then(|window| window.cursor_position()) else {
+        return;
+    };
+
+    let on_minimap = frame.get_single().ok().and_then(|(node, frame_transform)| {
+        let rect = node.logical_rect(frame_transform);
+        rect.contains(cursor).then(|| pixel_to_world((cursor - rect.min) / rect.size() * MINIMAP_TEXTURE_SIZE as f32))
+    });
+    let position = on_minimap.or_else(|| {
+        camera.get_single().ok().and_then(|(camera, camera_transform)| ground_point(camera, camera_transform, cursor))
+    });
+
+    if let Some(position) = position {
+        pings.0.push(MinimapPing { position, age: 0.0 });
+        info!("📍 Ping at ({:.1}, {:.1})", position.x, position.z);
+    }
+}
+
+/// Ages the pings and marks them in the world as well
+pub fn update_minimap_pings(time: Res<Time>, mut pings: ResMut<MinimapPings>, mut gizmos: Gizmos) {
+    for ping in pings.0.iter_mut() {
+        ping.age += time.delta_seconds();
+        let progress = ping.age / PING_LIFETIME;
+        gizmos.circle(ping.position + Vec3::Y * 0.1, Direction3d::Y, 1.0 + progress * 3.0, Color::rgba(1.0, 0.85, 0.0, 1.0 - progress));
+    }
+    pings.0.retain(|ping| ping.age < PING_LIFETIME);
+}
+
+fn reset_minimap(mut pings: ResMut<MinimapPings>, mut drag: ResMut<MinimapDrag>) {
+    pings.0.clear();
+    drag.0 = false;
+}
+
+/// Minimap in the corner of the game screen
+pub struct MinimapPlugin;
+
+impl Plugin for MinimapPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<MinimapPings>()
+            .init_resource::<MinimapDrag>()
+            .add_systems(OnMatchStart, spawn_minimap)
+            .add_systems(OnMatchEnd, reset_minimap)
+            .add_systems(
+                Update,
+                (minimap_camera_control, drop_minimap_ping, update_minimap_pings, draw_minimap)
+                    .chain()
+                    .run_if(in_state(GameState::Game)),
+            );
+    }
+}
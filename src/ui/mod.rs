@@ -9,6 +9,8 @@ pub mod splash;
 
 pub mod money_ui;
 
+pub mod cost_table;
+
 pub mod confirm_dialog;
 
 pub mod purchase_menu;
@@ -19,6 +21,12 @@ pub mod turn_ui;
 
 pub mod enemy_highlighting;
 
+pub mod ai_debug_overlay;
+
+pub mod minimap;
+
+pub mod gizmos;
+
 use bevy::prelude::*;
 use crate::menu::common::GameState;
 use crate::game_plugin::OnGameScreen;
@@ -83,9 +91,12 @@ pub struct PlacementStateText;
 
 pub fn ui_plugin(app: &mut App) {
     app.add_plugins((
+            crate::utils::BalancePlugin::<cost_table::CostTable>::default(),
             purchase_menu::PurchaseMenuPlugin,
             notification_system::NotificationSystemPlugin,
             turn_ui::TurnUiPlugin,
+            ai_debug_overlay::AIDebugOverlayPlugin,
+            minimap::MinimapPlugin,
         ))
        .add_systems(
         Update,
@@ -97,6 +108,8 @@ pub fn ui_plugin(app: &mut App) {
             enemy_highlighting::highlight_enemy_entities,
             enemy_highlighting::highlight_player_entities,
             enemy_highlighting::highlight_primitive_player_entities,
+            // Превью строя для выделенной группы
+            gizmos::draw_formation_preview,
         ).run_if(in_state(GameState::Game))
     );
 }
\ No newline at end of file
@@ -1,10 +1,13 @@
 use bevy::prelude::*;
 use bevy::ecs::system::Resource;
 use crate::game::ShapeType;
-use crate::menu::common::{GameState, MenuState};
+use crate::menu::common::{GameState, MenuState, OnMatchEnd, OnMatchStart};
 use crate::ui::confirm_dialog::{ConfirmDialog, ConfirmDialogAction, spawn_confirm_dialog};
+use crate::ui::cost_table::CostTable;
+use crate::utils::BalanceLoaded;
 use crate::game_plugin::OnGameScreen;
 use bevy_mod_picking::prelude::*;
+use crate::systems::fog_of_war::FogOfWar;
 
 // Resource for player's money
 #[derive(Resource, Debug, Default)]
@@ -52,7 +55,29 @@ pub struct GameTime {
 #[derive(Component)]
 pub struct UICamera;
 
-// Enum for purchasable items and their costs
+// Full multi-resource price of a unit or building
+#[derive(Debug, Clone, Copy, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct ResourceCost {
+    pub money: f32,
+    pub wood: f32,
+    pub iron: f32,
+    pub steel: f32,
+    pub oil: f32,
+}
+
+impl ResourceCost {
+    /// Хватает ли этих запасов на `cost`
+    pub fn covers(&self, cost: &ResourceCost) -> bool {
+        self.money >= cost.money &&
+        self.wood >= cost.wood &&
+        self.iron >= cost.iron &&
+        self.steel >= cost.steel &&
+        self.oil >= cost.oil
+    }
+}
+
+// Enum for purchasable items (prices live in the CostTable)
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum PurchasableItem {
     Tank,
@@ -65,66 +90,6 @@ pub enum PurchasableItem {
 }
 
 impl PurchasableItem {
-    pub fn cost(&self) -> f32 {
-        match self {
-            PurchasableItem::Tank => 45.0,      // Средняя стоимость танков
-            PurchasableItem::Infantry => 16.0,  // Средняя стоимость пехоты  
-            PurchasableItem::Airplane => 40.0,  // Средняя стоимость самолетов
-            PurchasableItem::Farm => 85.0,      // Высокая стоимость фермы для баланса
-            PurchasableItem::Mine => 30.0,      // Стоимость шахты
-            PurchasableItem::SteelFactory => 40.0,  // Стоимость сталелитейного завода
-            PurchasableItem::PetrochemicalPlant => 50.0, // Стоимость нефтезавода
-        }
-    }
-    
-    pub fn wood_cost(&self) -> f32 {
-        match self {
-            PurchasableItem::Tank => 5.0,       // Средние требования танков
-            PurchasableItem::Infantry => 0.0,   // Пехота не требует дерева
-            PurchasableItem::Airplane => 9.0,   // Средние требования самолетов
-            PurchasableItem::Farm => 25.0,      // Фермы требуют много дерева для постройки
-            PurchasableItem::Mine => 8.0,       // Требования шахты
-            PurchasableItem::SteelFactory => 12.0, // Требования завода
-            PurchasableItem::PetrochemicalPlant => 15.0, // Требования нефтезавода
-        }
-    }
-
-    pub fn iron_cost(&self) -> f32 {
-        match self {
-            PurchasableItem::Tank => 7.0,       // Средние требования танков
-            PurchasableItem::Infantry => 0.0,   // Пехота не требует железа
-            PurchasableItem::Airplane => 4.0,   // Средние требования самолетов
-            PurchasableItem::Farm => 3.0,       // Фермы требуют немного железа для инструментов
-            PurchasableItem::Mine => 0.0,       // Шахта не требует железа
-            PurchasableItem::SteelFactory => 15.0, // Требования завода
-            PurchasableItem::PetrochemicalPlant => 10.0, // Требования нефтезавода
-        }
-    }
-    
-    pub fn steel_cost(&self) -> f32 {
-        match self {
-            PurchasableItem::Tank => 6.0,       // Средние требования танков
-            PurchasableItem::Infantry => 0.0,   // Пехота не требует стали
-            PurchasableItem::Airplane => 5.0,   // Средние требования самолетов
-            PurchasableItem::Farm => 0.0,       // Фермы не требуют стали
-            PurchasableItem::Mine => 0.0,       // Шахта не требует стали
-            PurchasableItem::SteelFactory => 0.0, // Завод не требует стали
-            PurchasableItem::PetrochemicalPlant => 8.0, // Требования нефтезавода
-        }
-    }
-
-    pub fn oil_cost(&self) -> f32 {
-        match self {
-            PurchasableItem::Tank => 11.0,      // Средние требования танков
-            PurchasableItem::Infantry => 0.0,   // Пехота не требует нефти
-            PurchasableItem::Airplane => 17.0,  // Средние требования самолетов
-            PurchasableItem::Farm => 0.0,       // Фермы не требуют нефти
-            PurchasableItem::Mine => 0.0,       // Шахта не требует нефти
-            PurchasableItem::SteelFactory => 0.0, // Завод не требует нефти
-            PurchasableItem::PetrochemicalPlant => 0.0, // Нефтезавод не требует нефти
-        }
-    }
-
     pub fn shape_type(&self) -> ShapeType {
         match self {
             PurchasableItem::Tank => ShapeType::Cube,
@@ -136,6 +101,17 @@ impl PurchasableItem {
             PurchasableItem::PetrochemicalPlant => ShapeType::PetrochemicalPlant,
         }
     }
+
+    /// Здание, которое строится на стройплощадке этого типа (None для юнитов и прочего)
+    pub fn for_building(shape: ShapeType) -> Option<Self> {
+        match shape {
+            ShapeType::Farm => Some(PurchasableItem::Farm),
+            ShapeType::Mine => Some(PurchasableItem::Mine),
+            ShapeType::SteelFactory => Some(PurchasableItem::SteelFactory),
+            ShapeType::PetrochemicalPlant => Some(PurchasableItem::PetrochemicalPlant),
+            _ => None,
+        }
+    }
 }
 
 // Marker components for UI elements
@@ -201,16 +177,17 @@ impl Plugin for MoneyUiPlugin {
             .insert_resource(Steel(0.0))
             .insert_resource(Oil(0.0))
             .insert_resource(GameTime { seconds: 0.0 })
-            .add_systems(OnEnter(GameState::Game), setup_money_ui)
+            .add_systems(OnMatchStart, setup_money_ui)
             .add_systems(Update, update_resources_text.run_if(in_state(GameState::Game)))
             .add_systems(Update, update_ai_resources_text.run_if(in_state(GameState::Game)))
+            .add_systems(Update, hide_ai_resources_in_fog.run_if(in_state(GameState::Game)))
             .add_systems(Update, update_game_time.run_if(in_state(GameState::Game)))
-            .add_systems(Update, handle_spawn_buttons.run_if(in_state(GameState::Game)))
+            .add_systems(Update, handle_spawn_buttons.run_if(in_state(GameState::Game).and_then(resource_exists::<BalanceLoaded<CostTable>>)))
             .add_systems(Update, handle_exit_button.run_if(in_state(GameState::Game)))
             .add_systems(Update, handle_confirm_dialog.run_if(in_state(GameState::Game)))
             .add_systems(Update, update_wood_from_forest.run_if(in_state(GameState::Game)))
             .add_systems(Update, update_iron_from_mines.run_if(in_state(GameState::Game)))
-            .add_systems(OnExit(GameState::Game), cleanup_game_entities);
+            .add_systems(OnMatchEnd, cleanup_game_entities);
     }
 }
 
@@ -650,12 +627,13 @@ fn handle_spawn_buttons(
         ),
         (Changed<Interaction>, Or<(With<SpawnCubeButton>, With<SpawnInfantryButton>, With<SpawnAirplaneButton>, With<SpawnMineButton>, With<SpawnSteelFactoryButton>, With<SpawnPetrochemicalPlantButton>)>)
     >,
-    mut money: ResMut<Money>,
-    mut wood: ResMut<Wood>,
-    mut iron: ResMut<Iron>,
-    mut steel: ResMut<Steel>,
-    mut oil: ResMut<Oil>,
+    money: Res<Money>,
+    wood: Res<Wood>,
+    iron: Res<Iron>,
+    steel: Res<Steel>,
+    oil: Res<Oil>,
     mut placement_state: ResMut<crate::game::PlacementState>,
+    costs: Res<CostTable>,
     _time: Res<Time>,
 ) {
     for (interaction, mut color, _entity, is_cube, is_infantry, is_airplane, is_mine, is_steel_factory, is_petrochemical_plant) in &mut interaction_query {
@@ -679,19 +657,20 @@ fn handle_spawn_buttons(
 
                 // Check if player has enough resources
                 info!("🔥 OLD UI: Button pressed for item {:?}", item);
-                if can_afford_item(item, &money, &wood, &iron, &steel, &oil) {
+                if can_afford_item(item, &costs, &money, &wood, &iron, &steel, &oil) {
                     // Set the object placement state for units
                     info!("🔥 OLD UI: Setting placement state active for {:?}", item.shape_type());
                     placement_state.active = true;
                     placement_state.shape_type = Some(item.shape_type());
-                    
-                    // Deduct resources in advance
-                    deduct_resources(item, &mut money, &mut wood, &mut iron, &mut steel, &mut oil);
+                    placement_state.unit_type_index = None;
+                    // Resources are deducted once the object is placed
+                    placement_state.cost = Some(costs.get(item));
                     
                     info!("Placement mode activated for {:?}", item.shape_type());
                 } else {
+                    let cost = costs.get(item);
                     info!("Not enough resources to purchase {:?}! Need: Money: {}, Wood: {}, Iron: {}, Steel: {}, Oil: {}", 
-                          item, item.cost(), item.wood_cost(), item.iron_cost(), item.steel_cost(), item.oil_cost());
+                          item, cost.money, cost.wood, cost.iron, cost.steel, cost.oil);
                 }
 
                 *color = Color::GRAY.into();
@@ -1070,68 +1049,48 @@ fn update_ai_resources_text(
     }
 }
 
+type AIResourceTextFilter = Or<(With<AIMoneyText>, With<AIWoodText>, With<AIIronText>, With<AISteelText>, With<AIOilText>)>;
+
+/// AI resource counters are enemy intel: shown only while the map is revealed
+fn hide_ai_resources_in_fog(
+    fog: Res<FogOfWar>,
+    mut texts: Query<&mut Visibility, AIResourceTextFilter>,
+) {
+    let visibility = if fog.revealed { Visibility::Inherited } else { Visibility::Hidden };
+    for mut text_visibility in texts.iter_mut() {
+        if *text_visibility != visibility {
+            *text_visibility = visibility;
+        }
+    }
+}
+
 // Helper function to check if player can afford an item
 pub fn can_afford_item(
     item: PurchasableItem,
+    costs: &CostTable,
     money: &Money,
     wood: &Wood,
     iron: &Iron,
     steel: &Steel,
     oil: &Oil,
 ) -> bool {
-    money.0 >= item.cost() && 
-    wood.0 >= item.wood_cost() && 
-    iron.0 >= item.iron_cost() && 
-    steel.0 >= item.steel_cost() && 
-    oil.0 >= item.oil_cost()
+    can_afford_cost(&costs.get(item), money, wood, iron, steel, oil)
 }
 
-// Helper function to check if AI can afford an item
-pub fn can_afford_item_ai(
-    item: PurchasableItem,
-    money: &AIMoney,
-    wood: &AIWood,
-    iron: &AIIron,
-    steel: &AISteel,
-    oil: &AIOil,
+// Helper function to check if player can afford an arbitrary price
+pub fn can_afford_cost(
+    cost: &ResourceCost,
+    money: &Money,
+    wood: &Wood,
+    iron: &Iron,
+    steel: &Steel,
+    oil: &Oil,
 ) -> bool {
-    money.0 >= item.cost() && 
-    wood.0 >= item.wood_cost() && 
-    iron.0 >= item.iron_cost() && 
-    steel.0 >= item.steel_cost() && 
-    oil.0 >= item.oil_cost()
-}
-
-// Helper function to deduct resources from player
-pub fn deduct_resources(
-    item: PurchasableItem,
-    money: &mut Money,
-    wood: &mut Wood,
-    iron: &mut Iron,
-    steel: &mut Steel,
-    oil: &mut Oil,
-) {
-    money.0 -= item.cost();
-    wood.0 -= item.wood_cost();
-    iron.0 -= item.iron_cost();
-    steel.0 -= item.steel_cost();
-    oil.0 -= item.oil_cost();
-}
-
-// Helper function to deduct resources from AI
-pub fn deduct_resources_ai(
-    item: PurchasableItem,
-    money: &mut AIMoney,
-    wood: &mut AIWood,
-    iron: &mut AIIron,
-    steel: &mut AISteel,
-    oil: &mut AIOil,
-) {
-    money.0 -= item.cost();
-    wood.0 -= item.wood_cost();
-    iron.0 -= item.iron_cost();
-    steel.0 -= item.steel_cost();
-    oil.0 -= item.oil_cost();
+    money.0 >= cost.money && 
+    wood.0 >= cost.wood && 
+    iron.0 >= cost.iron && 
+    steel.0 >= cost.steel && 
+    oil.0 >= cost.oil
 }
 
 // Helper function to place shapes for player
@@ -1143,239 +1102,51 @@ pub fn place_shape(
     materials: &mut ResMut<Assets<StandardMaterial>>,
     asset_server: &AssetServer,
     player_faction: &Res<crate::game::units::PlayerFaction>,
+    unit_catalog: &crate::game::units::catalog::UnitCatalog,
     unit_type_index: Option<usize>,
-) {
+) -> Option<Entity> {
     info!("🔥🔥🔥 place_shape: FUNCTION CALLED!!! shape_type {:?} at position {:?} faction {:?}", shape_type, position, player_faction.0);
     use crate::game::components::*;
-    use bevy_rapier3d::prelude::*;
-    use bevy_mod_picking::prelude::*;
     
     match shape_type {
-        ShapeType::Mine => {
-            crate::game::mine::spawn_active_mine(
-                commands,
-                meshes,
-                materials,
-                position,
-                asset_server,
-            );
-        },
-        ShapeType::SteelFactory => {
-            crate::game::steel_factory::spawn_active_steel_factory(
-                commands,
-                meshes,
-                materials,
-                position,
-                asset_server,
-            );
-        },
-        ShapeType::PetrochemicalPlant => {
-            crate::game::petrochemical_plant::spawn_active_petrochemical_plant(
+        // Купленное здание начинается со стройплощадки, достраивают его инженеры
+        ShapeType::Farm | ShapeType::Mine | ShapeType::SteelFactory | ShapeType::PetrochemicalPlant => {
+            Some(crate::game::construction::spawn_construction_site(
                 commands,
                 meshes,
                 materials,
+                shape_type,
                 position,
-                asset_server,
-            );
+            ))
         },
-        ShapeType::Cube => {
-            use crate::menu::main_menu::Faction;
-            
-            // Use the specific unit type index if provided, otherwise default to 0
-            let tank_type_index = unit_type_index.unwrap_or(0);
+        ShapeType::Cube | ShapeType::Airplane | ShapeType::Infantry => {
+            use crate::game::units::catalog::UnitClass;
             
-            let (model_path, scale) = match player_faction.0 {
-                Faction::Entente => {
-                    match tank_type_index {
-                        0 => ("models/entente/tanks/tsar_tank.glb#Scene0", 0.05), // tsar_tank возвращаем исходный размер
-                        1 => ("models/entente/tanks/mark1.glb#Scene0", 0.08), // mark1 возвращаем исходный размер
-                        _ => ("models/entente/tanks/renault_ft17.glb#Scene0", 0.4), // renault остается нормальным
-                    }
-                },
-                Faction::CentralPowers => {
-                    match tank_type_index {
-                        0 => ("models/central_powers/tanks/panzerwagen.glb#Scene0", 0.027), // panzerwagen уменьшен в 3 раза от предыдущего размера
-                        1 => ("models/central_powers/tanks/a7v.glb#Scene0", 0.04), // a7v уменьшен в 2 раза от предыдущего размера
-                        _ => ("models/central_powers/tanks/steam_wheel_tank.glb#Scene0", 0.08), // steam_wheel остается как был
-                    }
-                },
+            let (class, name) = match shape_type {
+                ShapeType::Cube => (UnitClass::Tank, "Player Tank"),
+                ShapeType::Airplane => (UnitClass::Aircraft, "Player Aircraft"),
+                _ => (UnitClass::Infantry, "Player Infantry"),
             };
             
-            // Адаптируем размер коллайдера под масштаб модели, но с гарантированно большим минимумом для кликабельности
-            let collider_size = (8.0_f32 * scale).max(5.0); // Гарантированно большой коллайдер для кликабельности
-            
-            info!("🔥 TANK: Loading model from path: {} with scale: {}", model_path, scale);
-            let entity_id = commands.spawn((
-                SceneBundle {
-                    scene: asset_server.load(model_path),
-                    transform: Transform::from_translation(position)
-                        .with_scale(Vec3::splat(scale)),
-                    ..default()
-                },
-                ShapeType::Cube,
-                crate::game::components::Selectable,
-                crate::game::components::Tank,
-                crate::game::components::Health {
-                    current: 100.0,
-                    max: 100.0,
-                },
-                crate::game::components::CanShoot {
-                    cooldown: 1.0,
-                    last_shot: 0.0,
-                    range: 10.0,
-                    damage: 10.0,
-                },
-                RigidBody::Dynamic,
-                Collider::cuboid(collider_size, collider_size, collider_size), // Пропорциональный коллайдер
-                Sensor, // Невидимый коллайдер для кликов
-                LockedAxes::ROTATION_LOCKED | LockedAxes::TRANSLATION_LOCKED_Y,
-                Restitution::coefficient(0.0),
-                Friction::coefficient(0.8),
-                bevy_mod_picking::prelude::PickableBundle::default(),
-                Name::new("Player Tank"),
-            )).id();
-            info!("🔥 TANK SPAWNED: Entity {:?} at position {:?} with scale {} and collider size {}", entity_id, position, scale, collider_size);
-        },
-        ShapeType::Airplane => {
-            use crate::menu::main_menu::Faction;
-            
             // Use the specific unit type index if provided, otherwise default to 0
-            let aircraft_type_index = unit_type_index.unwrap_or(0);
+            let unit_index = unit_type_index.unwrap_or(0);
             
-            let model_path = match player_faction.0 {
-                Faction::Entente => {
-                    match aircraft_type_index {
-                        0 => "models/entente/airplanes/sopwith_camel.glb#Scene0",
-                        1 => "models/entente/airplanes/breguet_14.glb#Scene0",
-                        _ => "models/entente/airplanes/ilya_muromets.glb#Scene0",
-                    }
-                },
-                Faction::CentralPowers => {
-                    match aircraft_type_index {
-                        0 => "models/central_powers/airplanes/fokker.glb#Scene0",
-                        1 => "models/central_powers/airplanes/albatros.glb#Scene0",
-                        _ => "models/central_powers/airplanes/red_baron.glb#Scene0",
-                    }
-                },
-            };
-            info!("🔥 AIRCRAFT: Loading model from path: {}", model_path);
-            let entity_id = commands.spawn((
-                SceneBundle {
-                    scene: asset_server.load(model_path),
-                    transform: Transform::from_translation(position + Vec3::new(0.0, 10.0, 0.0))
-                        .with_scale(Vec3::splat(0.6)),
-                    ..default()
-                },
-                ShapeType::Airplane,
-                crate::game::components::Selectable,
-                crate::game::components::Aircraft {
-                    height: 10.0,
-                    speed: 5.0,
-                },
-                crate::game::components::MovementOrder(Vec3::ZERO),
-                crate::game::components::Health {
-                    current: 75.0,
-                    max: 75.0,
-                },
-                crate::game::components::CanShoot {
-                    cooldown: 0.5,
-                    last_shot: 0.0,
-                    range: 20.0,
-                    damage: 15.0,
-                },
-                RigidBody::Fixed,
-                Collider::cuboid(7.0, 4.0, 8.0), // Очень большой коллайдер для самолетов
-                Sensor, // Невидимый коллайдер для кликов
-                LockedAxes::all(),
-                bevy_mod_picking::prelude::PickableBundle::default(),
-                Name::new("Player Aircraft"),
-            )).id();
-            info!("🔥 AIRCRAFT SPAWNED: Entity {:?} at position {:?} with Selectable component", entity_id, position);
-        },
-        ShapeType::Infantry => {
-            use crate::menu::main_menu::Faction;
-            use crate::game::units::infantry::{InfantryType, EntenteInfantryType, CentralPowersInfantryType, Infantry, InfantryAttributes};
-            use crate::game::components::{Health, CanShoot, Selectable, HoveredOutline};
-            use bevy_rapier3d::prelude::*;
-            
-            // Use the specific unit type index if provided, otherwise default to 0
-            let infantry_type_index = unit_type_index.unwrap_or(0);
-            
-            let infantry_type = match player_faction.0 {
-                Faction::Entente => {
-                    match infantry_type_index {
-                        0 => InfantryType::Entente(EntenteInfantryType::Russian),
-                        1 => InfantryType::Entente(EntenteInfantryType::British),
-                        _ => InfantryType::Entente(EntenteInfantryType::French),
-                    }
-                },
-                Faction::CentralPowers => {
-                    match infantry_type_index {
-                        0 => InfantryType::CentralPowers(CentralPowersInfantryType::German),
-                        1 => InfantryType::CentralPowers(CentralPowersInfantryType::Turkish),
-                        _ => InfantryType::CentralPowers(CentralPowersInfantryType::AustroHungarian),
-                    }
-                },
-            };
-            
-            // Get model path based on infantry type
-            let model_path = match infantry_type {
-                InfantryType::Entente(entente_type) => {
-                    match entente_type {
-                        EntenteInfantryType::Russian => "models/infantry/russian_soldier.glb#Scene0",
-                        EntenteInfantryType::British => "models/infantry/british_soldier.glb#Scene0",
-                        EntenteInfantryType::French => "models/infantry/french_soldier.glb#Scene0",
-                    }
-                },
-                InfantryType::CentralPowers(central_type) => {
-                    match central_type {
-                        CentralPowersInfantryType::German => "models/infantry/german_soldier.glb#Scene0",
-                        CentralPowersInfantryType::Turkish => "models/infantry/turkish_soldier.glb#Scene0",
-                        CentralPowersInfantryType::AustroHungarian => "models/infantry/austrian_soldier.glb#Scene0",
-                    }
-                },
-            };
-            
-            // Get stats for this infantry type
-            let stats = infantry_type.get_stats();
-            
-            info!("🔥 INFANTRY: Loading model from path: {}", model_path);
-            let entity_id = commands.spawn((
-                SceneBundle {
-                    scene: asset_server.load(model_path),
-                    transform: Transform::from_translation(position)
-                        .with_scale(Vec3::splat(0.8)),
-                    ..default()
-                },
-                Infantry,
-                InfantryAttributes {
-                    infantry_type,
-                },
-                stats,
-                Health {
-                    current: stats.health,
-                    max: stats.max_health,
-                },
-                CanShoot {
-                    cooldown: 1.0 / stats.attack_speed,
-                    last_shot: 0.0,
-                    range: 10.0,
-                    damage: stats.attack_damage,
-                },
-                ShapeType::Infantry,
-                Selectable,
-                HoveredOutline,
-                RigidBody::Dynamic,
-                LockedAxes::ROTATION_LOCKED | LockedAxes::TRANSLATION_LOCKED_Y,
-                Collider::ball(3.0), // Очень большой коллайдер для пехоты
-                Sensor, // Невидимый коллайдер для кликов
-                bevy_mod_picking::prelude::PickableBundle::default(),
-                Name::new("Player Infantry"),
-            )).id();
-            info!("🔥 INFANTRY SPAWNED: Entity {:?} at position {:?} with Selectable component", entity_id, position);
+            let entity_id = crate::game::units::spawn_unit(
+                commands,
+                asset_server,
+                unit_catalog,
+                player_faction.0,
+                class,
+                unit_index,
+                position,
+            )?;
+            commands.entity(entity_id).insert((Selectable, Name::new(name)));
+            info!("🔥 {:?} SPAWNED: Entity {:?} at position {:?} with Selectable component", class, entity_id, position);
+            Some(entity_id)
         },
         _ => {
             info!("Placement for {:?} not implemented yet", shape_type);
+            None
         }
     }
 } 
\ No newline at end of file
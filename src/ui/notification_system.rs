@@ -1,5 +1,10 @@
 use bevy::prelude::*;
 use crate::game_plugin::OnGameScreen;
+use crate::game::units::catalog::{UnitCatalog, UnitClass};
+use crate::game::units::armor::{ArmorClass, DamageMatrix, DamageType};
+use crate::menu::main_menu::Faction;
+use crate::ui::cost_table::CostTable;
+use crate::ui::money_ui::{PurchasableItem, ResourceCost};
 
 
 
@@ -277,6 +282,7 @@ pub enum CurrentTooltip {
 }
 
 // System to manage tooltips on hover - runs every frame
+#[allow(clippy::too_many_arguments)]
 pub fn manage_unit_tooltips(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
@@ -285,6 +291,9 @@ pub fn manage_unit_tooltips(
     aircraft_query: Query<(&Interaction, &AircraftUnitButton), With<Button>>,
     building_query: Query<(&Interaction, &BuildingButton), With<Button>>,
     existing_tooltips: Query<(Entity, &CurrentTooltip), With<UnitTooltip>>,
+    unit_catalog: Res<UnitCatalog>,
+    costs: Res<CostTable>,
+    damage_matrix: Res<DamageMatrix>,
 ) {
     let mut current_hover: Option<CurrentTooltip> = None;
     
@@ -356,15 +365,15 @@ pub fn manage_unit_tooltips(
                 
                 // Get tooltip content based on type
                 let (unit_name, stats) = match &hovered {
-                    CurrentTooltip::Infantry { unit_type, faction } => get_infantry_stats(*faction, *unit_type),
-                    CurrentTooltip::Tank { unit_type, faction } => get_tank_stats(*faction, *unit_type),
-                    CurrentTooltip::Aircraft { unit_type, faction } => get_aircraft_stats(*faction, *unit_type),
-                    CurrentTooltip::Building { building_type } => get_building_stats(*building_type),
+                    CurrentTooltip::Infantry { unit_type, faction } => get_unit_stats(&unit_catalog, &damage_matrix, *faction, UnitClass::Infantry, *unit_type),
+                    CurrentTooltip::Tank { unit_type, faction } => get_unit_stats(&unit_catalog, &damage_matrix, *faction, UnitClass::Tank, *unit_type),
+                    CurrentTooltip::Aircraft { unit_type, faction } => get_unit_stats(&unit_catalog, &damage_matrix, *faction, UnitClass::Aircraft, *unit_type),
+                    CurrentTooltip::Building { building_type } => get_building_stats(*building_type, &costs),
                 };
                 
                 // Create new tooltip with appropriate width
                 let tooltip_width = match &hovered {
-                    CurrentTooltip::Infantry { .. } => 250.0,
+                    CurrentTooltip::Infantry { .. } => 300.0,
                     CurrentTooltip::Tank { .. } => 350.0,
                     CurrentTooltip::Aircraft { .. } => 350.0,
                     CurrentTooltip::Building { .. } => 320.0,
@@ -432,164 +441,67 @@ fn tooltips_match(current: &CurrentTooltip, new: &CurrentTooltip) -> bool {
     }
 }
 
-// Helper function to get infantry unit cost
-pub fn get_infantry_cost(faction: crate::menu::main_menu::Faction, unit_type: usize) -> f32 {
-    match faction {
-        crate::menu::main_menu::Faction::Entente => {
-            match unit_type {
-                0 => 15.0, // Russian Infantry
-                1 => 18.0, // British Infantry
-                2 => 16.0, // French Infantry
-                _ => 15.0,
-            }
-        },
-        crate::menu::main_menu::Faction::CentralPowers => {
-            match unit_type {
-                0 => 20.0, // German Infantry
-                1 => 14.0, // Turkish Infantry
-                2 => 17.0, // Austro-Hungarian Infantry
-                _ => 15.0,
-            }
-        },
-    }
-}
-
-// Helper function to get tank unit costs and requirements
-pub fn get_tank_cost(faction: crate::menu::main_menu::Faction, unit_type: usize) -> (f32, f32, f32, f32, f32) {
-    // Returns (money, wood, iron, steel, oil)
-    match faction {
-        crate::menu::main_menu::Faction::Entente => {
-            match unit_type {
-                0 => (50.0, 5.0, 8.0, 6.0, 12.0), // Tsar Tank
-                1 => (45.0, 4.0, 7.0, 5.0, 10.0), // Mark I
-                2 => (40.0, 3.0, 6.0, 4.0, 8.0),  // Renault FT
-                _ => (45.0, 4.0, 7.0, 5.0, 10.0),
-            }
-        },
-        crate::menu::main_menu::Faction::CentralPowers => {
-            match unit_type {
-                0 => (48.0, 5.0, 7.0, 6.0, 11.0), // Austro-Daimler
-                1 => (55.0, 6.0, 9.0, 7.0, 13.0), // A7V
-                2 => (42.0, 4.0, 6.0, 5.0, 9.0),  // Ottoman Tank
-                _ => (48.0, 5.0, 7.0, 6.0, 11.0),
-            }
-        },
-    }
-}
-
-// Helper function to get aircraft unit costs and requirements
-pub fn get_aircraft_cost(faction: crate::menu::main_menu::Faction, unit_type: usize) -> (f32, f32, f32, f32, f32) {
-    // Returns (money, wood, iron, steel, oil)
-    match faction {
-        crate::menu::main_menu::Faction::Entente => {
-            match unit_type {
-                0 => (35.0, 8.0, 3.0, 4.0, 15.0), // Sopwith Camel
-                1 => (40.0, 9.0, 4.0, 5.0, 18.0), // SPAD S.XIII
-                2 => (60.0, 12.0, 6.0, 8.0, 25.0), // Sikorsky
-                _ => (35.0, 8.0, 3.0, 4.0, 15.0),
-            }
-        },
-        crate::menu::main_menu::Faction::CentralPowers => {
-            match unit_type {
-                0 => (38.0, 8.0, 4.0, 5.0, 16.0), // Fokker Dr.I
-                1 => (42.0, 9.0, 4.0, 6.0, 19.0), // Albatros D.III
-                2 => (55.0, 11.0, 5.0, 7.0, 22.0), // Gotha G.V
-                _ => (38.0, 8.0, 4.0, 5.0, 16.0),
-            }
-        },
-    }
-}
-
-// Helper function to get building costs
-pub fn get_building_cost(building_type: BuildingType) -> (f32, f32, f32, f32, f32) {
-    // Returns (money, wood, iron, steel, oil)
-    match building_type {
-        BuildingType::Mine => (30.0, 8.0, 0.0, 0.0, 0.0),
-        BuildingType::SteelFactory => (40.0, 12.0, 15.0, 0.0, 0.0),
-        BuildingType::PetrochemicalPlant => (50.0, 15.0, 10.0, 8.0, 0.0),
+// Helper function to format a multi-resource cost, skipping resources that are not required
+pub fn format_cost(cost: &ResourceCost) -> String {
+    let mut text = format!("Cost: ${}", cost.money);
+    for (amount, icon) in [(cost.wood, "🪵"), (cost.iron, "⛏️"), (cost.steel, "🔩"), (cost.oil, "⛽")] {
+        if amount > 0.0 {
+            text.push_str(&format!(" {}{}", icon, amount));
+        }
     }
+    text
 }
 
-// Helper function to get infantry unit stats
-fn get_infantry_stats(faction: crate::menu::main_menu::Faction, unit_type: usize) -> (String, String) {
-    let cost = get_infantry_cost(faction, unit_type);
-    
-    match faction {
-        crate::menu::main_menu::Faction::Entente => {
-            match unit_type {
-                0 => ("Russian Infantry".to_string(), format!("Cost: ${}\nHP: 100\nAttack: 25\nSpeed: Medium", cost)),
-                1 => ("British Infantry".to_string(), format!("Cost: ${}\nHP: 110\nAttack: 28\nSpeed: Medium", cost)),
-                2 => ("French Infantry".to_string(), format!("Cost: ${}\nHP: 105\nAttack: 26\nSpeed: Medium", cost)),
-                _ => ("Unknown".to_string(), "".to_string()),
-            }
-        },
-        crate::menu::main_menu::Faction::CentralPowers => {
-            match unit_type {
-                0 => ("German Infantry".to_string(), format!("Cost: ${}\nHP: 115\nAttack: 30\nSpeed: Medium", cost)),
-                1 => ("Turkish Infantry".to_string(), format!("Cost: ${}\nHP: 95\nAttack: 24\nSpeed: Fast", cost)),
-                2 => ("Austro-Hungarian Infantry".to_string(), format!("Cost: ${}\nHP: 108\nAttack: 27\nSpeed: Medium", cost)),
-                _ => ("Unknown".to_string(), "".to_string()),
-            }
-        },
-    }
+// Helper function to show the damage multipliers of a unit class against every armor class
+fn format_effectiveness(damage_matrix: &DamageMatrix, class: UnitClass) -> String {
+    let damage = DamageType::for_class(class);
+    let multipliers: Vec<String> = ArmorClass::ALL.iter()
+        .map(|armor| format!("{} x{:.2}", armor.label(), damage_matrix.multiplier(damage, *armor)))
+        .collect();
+    format!(
+        "Weapon: {} / Armor: {}\nvs {}\nvs {}",
+        damage.label(),
+        ArmorClass::for_class(class).label(),
+        multipliers[..2].join("  "),
+        multipliers[2..].join("  "),
+    )
 }
 
-// Helper function to get tank unit stats
-fn get_tank_stats(faction: crate::menu::main_menu::Faction, unit_type: usize) -> (String, String) {
-    let (money, wood, iron, steel, oil) = get_tank_cost(faction, unit_type);
-    
-    match faction {
-        crate::menu::main_menu::Faction::Entente => {
-            match unit_type {
-                0 => ("Tsar Tank".to_string(), format!("Cost: ${} 🪵{} ⛏️{} 🔩{} ⛽{}\nHP: 300\nAttack: 80\nSpeed: Slow\nHeavy armor, devastating firepower", money, wood, iron, steel, oil)),
-                1 => ("Mark I".to_string(), format!("Cost: ${} 🪵{} ⛏️{} 🔩{} ⛽{}\nHP: 250\nAttack: 70\nSpeed: Slow\nFirst battle tank, reliable", money, wood, iron, steel, oil)),
-                2 => ("Renault FT".to_string(), format!("Cost: ${} 🪵{} ⛏️{} 🔩{} ⛽{}\nHP: 200\nAttack: 60\nSpeed: Medium\nLight and maneuverable", money, wood, iron, steel, oil)),
-                _ => ("Unknown".to_string(), "".to_string()),
-            }
-        },
-        crate::menu::main_menu::Faction::CentralPowers => {
-            match unit_type {
-                0 => ("Austro-Daimler".to_string(), format!("Cost: ${} 🪵{} ⛏️{} 🔩{} ⛽{}\nHP: 280\nAttack: 75\nSpeed: Slow\nAustrian engineering", money, wood, iron, steel, oil)),
-                1 => ("A7V".to_string(), format!("Cost: ${} 🪵{} ⛏️{} 🔩{} ⛽{}\nHP: 320\nAttack: 85\nSpeed: Very Slow\nGerman super-heavy tank", money, wood, iron, steel, oil)),
-                2 => ("Ottoman Tank".to_string(), format!("Cost: ${} 🪵{} ⛏️{} 🔩{} ⛽{}\nHP: 220\nAttack: 65\nSpeed: Medium\nAdapted for desert warfare", money, wood, iron, steel, oil)),
-                _ => ("Unknown".to_string(), "".to_string()),
+// Helper function to get unit stats from the unit catalog
+fn get_unit_stats(catalog: &UnitCatalog, damage_matrix: &DamageMatrix, faction: Faction, class: UnitClass, unit_type: usize) -> (String, String) {
+    match catalog.get(faction, class, unit_type) {
+        Some(definition) => {
+            let mut stats = format!(
+                "{}\nHP: {}\nAttack: {}\nSpeed: {}\nRange: {}\n{}",
+                format_cost(&definition.cost),
+                definition.stats.health,
+                definition.stats.attack_damage,
+                definition.stats.speed,
+                definition.stats.range,
+                format_effectiveness(damage_matrix, class),
+            );
+            if !definition.description.is_empty() {
+                stats.push_str(&format!("\n{}", definition.description));
             }
-        },
-    }
-}
-
-// Helper function to get aircraft unit stats
-fn get_aircraft_stats(faction: crate::menu::main_menu::Faction, unit_type: usize) -> (String, String) {
-    let (money, wood, iron, steel, oil) = get_aircraft_cost(faction, unit_type);
-    
-    match faction {
-        crate::menu::main_menu::Faction::Entente => {
-            match unit_type {
-                0 => ("Sopwith Camel".to_string(), format!("Cost: ${} 🪵{} ⛏️{} 🔩{} ⛽{}\nHP: 80\nAttack: 45\nSpeed: Fast\nAgile fighter aircraft", money, wood, iron, steel, oil)),
-                1 => ("SPAD S.XIII".to_string(), format!("Cost: ${} 🪵{} ⛏️{} 🔩{} ⛽{}\nHP: 90\nAttack: 50\nSpeed: Fast\nFrench ace's choice", money, wood, iron, steel, oil)),
-                2 => ("Sikorsky".to_string(), format!("Cost: ${} 🪵{} ⛏️{} 🔩{} ⛽{}\nHP: 150\nAttack: 70\nSpeed: Medium\nHeavy bomber aircraft", money, wood, iron, steel, oil)),
-                _ => ("Unknown".to_string(), "".to_string()),
-            }
-        },
-        crate::menu::main_menu::Faction::CentralPowers => {
-            match unit_type {
-                0 => ("Fokker Dr.I".to_string(), format!("Cost: ${} 🪵{} ⛏️{} 🔩{} ⛽{}\nHP: 85\nAttack: 48\nSpeed: Fast\nTriplane fighter", money, wood, iron, steel, oil)),
-                1 => ("Albatros D.III".to_string(), format!("Cost: ${} 🪵{} ⛏️{} 🔩{} ⛽{}\nHP: 95\nAttack: 52\nSpeed: Fast\nGerman air superiority", money, wood, iron, steel, oil)),
-                2 => ("Gotha G.V".to_string(), format!("Cost: ${} 🪵{} ⛏️{} 🔩{} ⛽{}\nHP: 140\nAttack: 65\nSpeed: Medium\nStrategic bomber", money, wood, iron, steel, oil)),
-                _ => ("Unknown".to_string(), "".to_string()),
-            }
-        },
+            (definition.name.clone(), stats)
+        }
+        None => ("Unknown".to_string(), "".to_string()),
     }
 }
 
 // Helper function to get building stats
-fn get_building_stats(building_type: BuildingType) -> (String, String) {
-    let (money, wood, iron, steel, _oil) = get_building_cost(building_type);
+fn get_building_stats(building_type: BuildingType, costs: &CostTable) -> (String, String) {
+    let cost = match building_type {
+        BuildingType::Mine => costs.get(PurchasableItem::Mine),
+        BuildingType::SteelFactory => costs.get(PurchasableItem::SteelFactory),
+        BuildingType::PetrochemicalPlant => costs.get(PurchasableItem::PetrochemicalPlant),
+    };
+    let cost = format_cost(&cost);
     
     match building_type {
-        BuildingType::Mine => ("Mine".to_string(), format!("Cost: ${} 🪵{}\nProduces: Iron\nRate: +1 iron/sec\nLimited: One per player\nCan be rebuilt if destroyed", money, wood)),
-        BuildingType::SteelFactory => ("Steel Factory".to_string(), format!("Cost: ${} 🪵{} ⛏️{}\nProduces: Steel\nRate: +1 steel/sec\nLimited: One per player\nCan be rebuilt if destroyed", money, wood, iron)),
-        BuildingType::PetrochemicalPlant => ("Petrochemical Plant".to_string(), format!("Cost: ${} 🪵{} ⛏️{} 🔩{}\nProduces: Oil\nRate: +1 oil/sec\nLimited: One per player\nCan be rebuilt if destroyed", money, wood, iron, steel)),
+        BuildingType::Mine => ("Mine".to_string(), format!("{}\nProduces: Iron\nRate: +1 iron/sec\nLimited: One per player\nCan be rebuilt if destroyed", cost)),
+        BuildingType::SteelFactory => ("Steel Factory".to_string(), format!("{}\nProduces: Steel\nRate: +1 steel/sec\nLimited: One per player\nCan be rebuilt if destroyed", cost)),
+        BuildingType::PetrochemicalPlant => ("Petrochemical Plant".to_string(), format!("{}\nProduces: Oil\nRate: +1 oil/sec\nLimited: One per player\nCan be rebuilt if destroyed", cost)),
     }
 }
 
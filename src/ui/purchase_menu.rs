@@ -1,8 +1,15 @@
 use bevy::prelude::*;
+use crate::game::{EnemyTower, ShapeType, Tower, TowerSlot};
 use crate::game::units::PlayerFaction;
+use crate::game::units::catalog::{UnitCatalog, UnitClass};
 use crate::menu::main_menu::Faction;
+use crate::ui::money_ui::ResourceCost;
+use crate::ui::cost_table::CostTable;
+use crate::utils::BalanceLoaded;
 use crate::ui::notification_system::{BlinkingButton, NotificationState, HighlightedInfantryButton, InfantryUnitButton, TankUnitButton, AircraftUnitButton, BuildingButton, BuildingType};
 use crate::systems::turn_system::{TurnState, PlayerTurn};
+use crate::systems::ai_opponent::{AISide, SideResources};
+use crate::systems::tower::{TowerTurret, MAX_TURRET_LEVEL};
 
 // States for the purchase menu
 #[derive(States, Default, Debug, Clone, Eq, PartialEq, Hash)]
@@ -38,6 +45,20 @@ pub enum UnitPurchaseButton {
     PetrochemicalPlant,
 }
 
+/// Upgrade button for the turret of the player's tower in this slot
+#[derive(Component, Clone, Copy)]
+pub struct TowerUpgradeButton(pub usize);
+
+/// Text on a tower upgrade button; shows the current level and the price of the next one
+#[derive(Component, Clone, Copy)]
+pub struct TowerUpgradeLabel(pub usize);
+
+const TOWER_SLOT_NAMES: [&str; 3] = ["Left Tower", "Center Tower", "Right Tower"];
+
+type TowerUpgradeInteraction<'a> = (&'a Interaction, &'a TowerUpgradeButton);
+type PlayerTurret<'a> = (&'a TowerSlot, &'a mut TowerTurret);
+type PlayerTowerFilter = (With<Tower>, Without<EnemyTower>);
+
 // System to spawn the purchase menu button in the top-left corner
 pub fn spawn_purchase_button(
     mut commands: Commands, 
@@ -120,6 +141,37 @@ pub fn spawn_purchase_menu(
     asset_server: Res<AssetServer>,
     player_faction: Res<PlayerFaction>,
     notification_state: Res<NotificationState>,
+    unit_catalog: Res<UnitCatalog>,
+) {
+    build_purchase_menu(&mut commands, &asset_server, &player_faction, &notification_state, &unit_catalog);
+}
+
+/// Кнопки юнитов строятся из каталога: после загрузки или hot-reload открытое меню пересобирается
+pub fn rebuild_purchase_menu_on_catalog_change(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    player_faction: Res<PlayerFaction>,
+    notification_state: Res<NotificationState>,
+    unit_catalog: Res<UnitCatalog>,
+    menus: Query<Entity, With<PurchaseMenu>>,
+) {
+    if !unit_catalog.is_changed() || menus.is_empty() {
+        return;
+    }
+
+    info!("🔄 Unit catalog changed, rebuilding purchase menu");
+    for entity in &menus {
+        commands.entity(entity).despawn_recursive();
+    }
+    build_purchase_menu(&mut commands, &asset_server, &player_faction, &notification_state, &unit_catalog);
+}
+
+fn build_purchase_menu(
+    commands: &mut Commands,
+    asset_server: &AssetServer,
+    player_faction: &PlayerFaction,
+    notification_state: &NotificationState,
+    unit_catalog: &UnitCatalog,
 ) {
     // Main container
     commands
@@ -235,12 +287,9 @@ pub fn spawn_purchase_menu(
                 })
                 .with_children(|row| {
                     // Create infantry buttons based on faction
-                    let infantry_names = match player_faction.0 {
-                        Faction::Entente => ["Russian Infantry", "British Infantry", "French Infantry"],
-                        Faction::CentralPowers => ["German Infantry", "Turkish Infantry", "Austro-Hungarian Infantry"],
-                    };
+                    let infantry_units = unit_catalog.units(player_faction.0, UnitClass::Infantry);
 
-                    for i in 0..3 {
+                    for (i, definition) in infantry_units.iter().enumerate() {
                         let mut button_entity = row.spawn((
                             ButtonBundle {
                                 style: Style {
@@ -267,10 +316,9 @@ pub fn spawn_purchase_menu(
                         }
                         
                         button_entity.with_children(|button| {
-                            let cost = crate::ui::notification_system::get_infantry_cost(player_faction.0, i);
                             button.spawn(
                                 TextBundle::from_section(
-                                    format!("{}\n${}", infantry_names[i], cost),
+                                    format!("{}\n${}", definition.name, definition.cost.money),
                                     TextStyle {
                                         font: asset_server.load("fonts/GrenzeGotisch-Light.ttf"),
                                         font_size: 12.0,
@@ -316,12 +364,9 @@ pub fn spawn_purchase_menu(
                 })
                 .with_children(|row| {
                     // Create tank buttons based on faction
-                    let tank_names = match player_faction.0 {
-                        Faction::Entente => ["Tsar Tank", "Mark I", "Renault FT"],
-                        Faction::CentralPowers => ["Austro-Daimler", "A7V", "Ottoman Tank"],
-                    };
+                    let tank_units = unit_catalog.units(player_faction.0, UnitClass::Tank);
 
-                    for i in 0..3 {
+                    for (i, definition) in tank_units.iter().enumerate() {
                         row.spawn((
                             ButtonBundle {
                                 style: Style {
@@ -344,7 +389,7 @@ pub fn spawn_purchase_menu(
                         .with_children(|button| {
                             button.spawn(
                                 TextBundle::from_section(
-                                    tank_names[i],
+                                    definition.name.clone(),
                                     TextStyle {
                                         font: asset_server.load("fonts/GrenzeGotisch-Light.ttf"),
                                         font_size: 14.0,
@@ -390,12 +435,9 @@ pub fn spawn_purchase_menu(
                 })
                 .with_children(|row| {
                     // Create aircraft buttons based on faction
-                    let aircraft_names = match player_faction.0 {
-                        Faction::Entente => ["Sopwith Camel", "SPAD S.XIII", "Sikorsky"],
-                        Faction::CentralPowers => ["Fokker Dr.I", "Albatros D.III", "Gotha G.V"],
-                    };
+                    let aircraft_units = unit_catalog.units(player_faction.0, UnitClass::Aircraft);
 
-                    for i in 0..3 {
+                    for (i, definition) in aircraft_units.iter().enumerate() {
                         row.spawn((
                             ButtonBundle {
                                 style: Style {
@@ -418,7 +460,7 @@ pub fn spawn_purchase_menu(
                         .with_children(|button| {
                             button.spawn(
                                 TextBundle::from_section(
-                                    aircraft_names[i],
+                                    definition.name.clone(),
                                     TextStyle {
                                         font: asset_server.load("fonts/GrenzeGotisch-Light.ttf"),
                                         font_size: 14.0,
@@ -563,6 +605,72 @@ pub fn spawn_purchase_menu(
                         );
                     });
                 });
+
+            // Section title for tower upgrades
+            parent.spawn(
+                TextBundle::from_section(
+                    "Tower Turrets",
+                    TextStyle {
+                        font: asset_server.load("fonts/GrenzeGotisch-Light.ttf"),
+                        font_size: 20.0,
+                        color: Color::WHITE,
+                    },
+                )
+                .with_style(Style {
+                    margin: UiRect::vertical(Val::Px(10.0)),
+                    ..default()
+                }),
+            );
+
+            // Tower upgrade buttons row: left, center, right tower
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        width: Val::Percent(100.0),
+                        height: Val::Px(80.0),
+                        flex_direction: FlexDirection::Row,
+                        justify_content: JustifyContent::SpaceEvenly,
+                        ..default()
+                    },
+                    ..default()
+                })
+                .with_children(|row| {
+                    for (slot, name) in TOWER_SLOT_NAMES.iter().enumerate() {
+                        row.spawn((
+                            ButtonBundle {
+                                style: Style {
+                                    width: Val::Px(110.0),
+                                    height: Val::Px(70.0),
+                                    justify_content: JustifyContent::Center,
+                                    align_items: AlignItems::Center,
+                                    padding: UiRect::all(Val::Px(5.0)),
+                                    ..default()
+                                },
+                                background_color: Color::rgb(0.6, 0.5, 0.3).into(),
+                                ..default()
+                            },
+                            TowerUpgradeButton(slot),
+                        ))
+                        .with_children(|button| {
+                            // Текст заполняет update_tower_upgrade_buttons
+                            button.spawn((
+                                TextBundle::from_section(
+                                    *name,
+                                    TextStyle {
+                                        font: asset_server.load("fonts/GrenzeGotisch-Light.ttf"),
+                                        font_size: 12.0,
+                                        color: Color::WHITE,
+                                    },
+                                )
+                                .with_style(Style {
+                                    margin: UiRect::all(Val::Auto),
+                                    ..default()
+                                }),
+                                TowerUpgradeLabel(slot),
+                            ));
+                        });
+                    }
+                });
         });
 }
 
@@ -583,16 +691,20 @@ pub fn handle_unit_purchase(
     interaction_query: Query<(&Interaction, &UnitPurchaseButton), (Changed<Interaction>, With<Button>)>,
     mut placement_state: ResMut<crate::game::PlacementState>,
     turn_state: Res<TurnState>,
-    // Add resource checks and mutations
-    mut money: ResMut<crate::ui::money_ui::Money>,
-    mut wood: ResMut<crate::ui::money_ui::Wood>,
-    mut iron: ResMut<crate::ui::money_ui::Iron>,
-    mut steel: ResMut<crate::ui::money_ui::Steel>,
-    mut oil: ResMut<crate::ui::money_ui::Oil>,
+    // Resource checks; the price is charged on placement
+    money: Res<crate::ui::money_ui::Money>,
+    wood: Res<crate::ui::money_ui::Wood>,
+    iron: Res<crate::ui::money_ui::Iron>,
+    steel: Res<crate::ui::money_ui::Steel>,
+    oil: Res<crate::ui::money_ui::Oil>,
     // Add building queries to check limits (only player buildings without Enemy component)
     player_mines: Query<Entity, (With<crate::game::Mine>, Without<crate::game::Enemy>)>,
     player_steel_factories: Query<Entity, (With<crate::game::SteelFactory>, Without<crate::game::Enemy>)>,
     player_petrochemical_plants: Query<Entity, (With<crate::game::PetrochemicalPlant>, Without<crate::game::Enemy>)>,
+    player_sites: Query<&crate::game::ConstructionSite, Without<crate::game::Enemy>>,
+    player_faction: Res<PlayerFaction>,
+    unit_catalog: Res<UnitCatalog>,
+    costs: Res<CostTable>,
 ) {
     // Покупки доступны только в ход игрока
     if turn_state.current_player != PlayerTurn::Human {
@@ -614,7 +726,7 @@ pub fn handle_unit_purchase(
             // Check building limits first
             let can_build = match button_type {
                 UnitPurchaseButton::Mine => {
-                    let current_count = player_mines.iter().count();
+                    let current_count = player_mines.iter().count() + sites_of(&player_sites, ShapeType::Mine);
                     if current_count >= 1 {
                         info!("Cannot build more mines! Limit: 1, Current: {}", current_count);
                         false
@@ -623,7 +735,7 @@ pub fn handle_unit_purchase(
                     }
                 },
                 UnitPurchaseButton::SteelFactory => {
-                    let current_count = player_steel_factories.iter().count();
+                    let current_count = player_steel_factories.iter().count() + sites_of(&player_sites, ShapeType::SteelFactory);
                     if current_count >= 1 {
                         info!("Cannot build more steel factories! Limit: 1, Current: {}", current_count);
                         false
@@ -632,7 +744,7 @@ pub fn handle_unit_purchase(
                     }
                 },
                 UnitPurchaseButton::PetrochemicalPlant => {
-                    let current_count = player_petrochemical_plants.iter().count();
+                    let current_count = player_petrochemical_plants.iter().count() + sites_of(&player_sites, ShapeType::PetrochemicalPlant);
                     if current_count >= 1 {
                         info!("Cannot build more petrochemical plants! Limit: 1, Current: {}", current_count);
                         false
@@ -644,9 +756,12 @@ pub fn handle_unit_purchase(
                 _ => true,
             };
             
+            // Units are priced per model from the unit catalog, buildings by item
+            let cost = purchase_cost(button_type, item, player_faction.0, &unit_catalog, &costs);
+            
             // Check if player can afford the item and can build it
             info!("🔥 NEW UI: Button pressed for item {:?}", item);
-            if can_build && crate::ui::money_ui::can_afford_item(item, &money, &wood, &iron, &steel, &oil) {
+            if can_build && crate::ui::money_ui::can_afford_cost(&cost, &money, &wood, &iron, &steel, &oil) {
                 info!("🔥 NEW UI: Player can afford {:?}, setting placement state", item);
                 // Set the placement state based on the button type
                 info!("🔥 NEW UI: Setting placement_state.active = true for {:?}", item);
@@ -679,20 +794,45 @@ pub fn handle_unit_purchase(
                     },
                 }
                 
-                // Deduct resources after successful purchase
-                crate::ui::money_ui::deduct_resources(item, &mut money, &mut wood, &mut iron, &mut steel, &mut oil);
+                // Списываем только когда объект реально появится на карте
+                placement_state.cost = Some(cost);
                 
                 info!("Purchase approved for {:?}", item);
             } else if !can_build {
                 info!("Cannot build {:?}! Building limit reached.", item);
             } else {
                 info!("Not enough resources to purchase {:?}! Need: Money: {}, Wood: {}, Iron: {}, Steel: {}, Oil: {}", 
-                      item, item.cost(), item.wood_cost(), item.iron_cost(), item.steel_cost(), item.oil_cost());
+                      item, cost.money, cost.wood, cost.iron, cost.steel, cost.oil);
             }
         }
     }
 }
 
+// Helper function to get the price of a purchase button: catalog price for units, item price for buildings
+fn purchase_cost(
+    button_type: &UnitPurchaseButton,
+    item: crate::ui::money_ui::PurchasableItem,
+    faction: Faction,
+    unit_catalog: &UnitCatalog,
+    costs: &CostTable,
+) -> ResourceCost {
+    let unit = match button_type {
+        UnitPurchaseButton::Infantry(index) => Some((UnitClass::Infantry, *index)),
+        UnitPurchaseButton::Tank(index) => Some((UnitClass::Tank, *index)),
+        UnitPurchaseButton::Aircraft(index) => Some((UnitClass::Aircraft, *index)),
+        _ => None,
+    };
+    
+    unit.and_then(|(class, index)| unit_catalog.get(faction, class, index))
+        .map(|definition| definition.cost)
+        .unwrap_or_else(|| costs.get(item))
+}
+
+/// Недостроенное здание тоже занимает лимит
+fn sites_of(sites: &Query<&crate::game::ConstructionSite, Without<crate::game::Enemy>>, building: ShapeType) -> usize {
+    sites.iter().filter(|site| site.building == building).count()
+}
+
 // System to update button colors based on available resources - triggers on resource changes
 pub fn update_purchase_button_colors(
     money: Res<crate::ui::money_ui::Money>,
@@ -705,6 +845,10 @@ pub fn update_purchase_button_colors(
     player_mines: Query<Entity, (With<crate::game::Mine>, Without<crate::game::Enemy>)>,
     player_steel_factories: Query<Entity, (With<crate::game::SteelFactory>, Without<crate::game::Enemy>)>,
     player_petrochemical_plants: Query<Entity, (With<crate::game::PetrochemicalPlant>, Without<crate::game::Enemy>)>,
+    player_sites: Query<&crate::game::ConstructionSite, Without<crate::game::Enemy>>,
+    player_faction: Res<PlayerFaction>,
+    unit_catalog: Res<UnitCatalog>,
+    costs: Res<CostTable>,
 ) {
     // Force update every frame to ensure buttons show correct colors
     for (button_type, mut background_color, interaction) in button_query.iter_mut() {
@@ -724,15 +868,16 @@ pub fn update_purchase_button_colors(
         
         // Check building limits first
         let can_build = match button_type {
-            UnitPurchaseButton::Mine => player_mines.iter().count() < 1,
-            UnitPurchaseButton::SteelFactory => player_steel_factories.iter().count() < 1,
-            UnitPurchaseButton::PetrochemicalPlant => player_petrochemical_plants.iter().count() < 1,
+            UnitPurchaseButton::Mine => player_mines.iter().count() + sites_of(&player_sites, ShapeType::Mine) < 1,
+            UnitPurchaseButton::SteelFactory => player_steel_factories.iter().count() + sites_of(&player_sites, ShapeType::SteelFactory) < 1,
+            UnitPurchaseButton::PetrochemicalPlant => player_petrochemical_plants.iter().count() + sites_of(&player_sites, ShapeType::PetrochemicalPlant) < 1,
             // Units have no limits
             _ => true,
         };
         
         // Check if player can afford the item and can build it
-        let can_afford = crate::ui::money_ui::can_afford_item(item, &money, &wood, &iron, &steel, &oil);
+        let cost = purchase_cost(button_type, item, player_faction.0, &unit_catalog, &costs);
+        let can_afford = crate::ui::money_ui::can_afford_cost(&cost, &money, &wood, &iron, &steel, &oil);
         let can_purchase = can_build && can_afford;
         
         // Update button color based on purchase possibility
@@ -756,6 +901,86 @@ pub fn update_purchase_button_colors(
     }
 }
 
+// System to handle tower upgrade button clicks: the turret of the tower in that slot goes up one level
+pub fn handle_tower_upgrade_purchase(
+    interaction_query: Query<TowerUpgradeInteraction, (Changed<Interaction>, With<Button>)>,
+    turn_state: Res<TurnState>,
+    mut resources: SideResources,
+    mut towers: Query<PlayerTurret, PlayerTowerFilter>,
+) {
+    // Покупки доступны только в ход игрока
+    if turn_state.current_player != PlayerTurn::Human {
+        return;
+    }
+
+    for (interaction, button) in &interaction_query {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        let Some((_, mut turret)) = towers.iter_mut().find(|(slot, _)| slot.0 == button.0) else {
+            info!("Tower in slot {} is destroyed, nothing to upgrade", button.0);
+            continue;
+        };
+        let Some(cost) = turret.upgrade_cost() else {
+            info!("Tower turret in slot {} is already at max level {}", button.0, MAX_TURRET_LEVEL);
+            continue;
+        };
+
+        let mut wallet = resources.wallet(AISide::Player);
+        if !wallet.amounts().covers(&cost) {
+            info!("Not enough resources to upgrade tower turret! Need: Money: {}, Wood: {}, Iron: {}, Steel: {}, Oil: {}",
+                  cost.money, cost.wood, cost.iron, cost.steel, cost.oil);
+            continue;
+        }
+        wallet.deduct(&cost);
+        turret.level += 1;
+        info!("🏰 {} turret ({}) upgraded to level {}: range {}, damage {}, cooldown {:.2}s",
+              TOWER_SLOT_NAMES[button.0], turret.role.label(), turret.level, turret.range(), turret.damage(), turret.cooldown());
+    }
+}
+
+// System to update tower upgrade buttons: level and price on the label, color by availability
+#[allow(clippy::too_many_arguments)]
+pub fn update_tower_upgrade_buttons(
+    money: Res<crate::ui::money_ui::Money>,
+    wood: Res<crate::ui::money_ui::Wood>,
+    iron: Res<crate::ui::money_ui::Iron>,
+    steel: Res<crate::ui::money_ui::Steel>,
+    oil: Res<crate::ui::money_ui::Oil>,
+    mut buttons: Query<(&TowerUpgradeButton, &mut BackgroundColor, Option<&Interaction>), With<Button>>,
+    mut labels: Query<(&TowerUpgradeLabel, &mut Text)>,
+    towers: Query<(&TowerSlot, &TowerTurret), PlayerTowerFilter>,
+) {
+    let turret_in = |slot: usize| towers.iter().find(|(tower_slot, _)| tower_slot.0 == slot).map(|(_, turret)| *turret);
+
+    for (label, mut text) in labels.iter_mut() {
+        let value = match turret_in(label.0) {
+            Some(turret) => match turret.upgrade_cost() {
+                Some(cost) => format!("{} {}\nLv {} -> {}\n${}", TOWER_SLOT_NAMES[label.0], turret.role.label(), turret.level, turret.level + 1, cost.money),
+                None => format!("{} {}\nLv {} (max)", TOWER_SLOT_NAMES[label.0], turret.role.label(), turret.level),
+            },
+            None => format!("{}\ndestroyed", TOWER_SLOT_NAMES[label.0]),
+        };
+        if text.sections[0].value != value {
+            text.sections[0].value = value;
+        }
+    }
+
+    for (button, mut background_color, interaction) in buttons.iter_mut() {
+        if let Some(Interaction::Pressed) = interaction {
+            continue;
+        }
+        let cost = turret_in(button.0).and_then(|turret| turret.upgrade_cost());
+        *background_color = match cost {
+            Some(cost) if crate::ui::money_ui::can_afford_cost(&cost, &money, &wood, &iron, &steel, &oil) => Color::rgb(0.6, 0.5, 0.3).into(),
+            // Gray color when can't afford
+            Some(_) => Color::rgb(0.3, 0.3, 0.3).into(),
+            // Red color when the tower is at max level or destroyed
+            None => Color::rgb(0.6, 0.2, 0.2).into(),
+        };
+    }
+}
+
 // System to despawn the purchase menu
 pub fn despawn_purchase_menu(
     mut commands: Commands,
@@ -774,13 +999,24 @@ impl Plugin for PurchaseMenuPlugin {
     fn build(&self, app: &mut App) {
         app
             .init_state::<PurchaseMenuState>()
-            .add_systems(OnEnter(crate::menu::common::GameState::Game), spawn_purchase_button)
-            // OnGameScreen components are automatically despawned when leaving game state
-            .add_systems(Update, handle_purchase_button.run_if(in_state(crate::menu::common::GameState::Game)))
+            .add_systems(crate::menu::common::OnMatchStart, spawn_purchase_button)
+            // OnGameScreen components are automatically despawned when the match ends
+            .add_systems(
+                Update,
+                (handle_purchase_button, rebuild_purchase_menu_on_catalog_change)
+                    .run_if(in_state(crate::menu::common::GameState::Game)),
+            )
             .add_systems(OnEnter(PurchaseMenuState::Open), spawn_purchase_menu)
             .add_systems(
                 Update,
-                (handle_close_button, handle_unit_purchase, update_purchase_button_colors).run_if(in_state(PurchaseMenuState::Open))
+                (
+                    handle_close_button,
+                    // Пока таблица цен не загружена, все цены нулевые
+                    handle_unit_purchase.run_if(resource_exists::<BalanceLoaded<CostTable>>),
+                    update_purchase_button_colors,
+                    handle_tower_upgrade_purchase,
+                    update_tower_upgrade_buttons,
+                ).run_if(in_state(PurchaseMenuState::Open))
             )
             .add_systems(OnExit(PurchaseMenuState::Open), despawn_purchase_menu);
     }
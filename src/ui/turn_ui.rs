@@ -1,6 +1,6 @@
 use bevy::prelude::*;
 use crate::systems::turn_system::{TurnState, PlayerTurn};
-use crate::menu::common::GameState;
+use crate::menu::common::{GameState, OnMatchStart};
 use crate::game_plugin::OnGameScreen;
 
 #[derive(Component)]
@@ -21,7 +21,7 @@ impl Plugin for TurnUiPlugin {
     fn build(&self, app: &mut App) {
         app
             .init_resource::<TurnState>()
-            .add_systems(OnEnter(GameState::Game), setup_turn_ui)
+            .add_systems(OnMatchStart, setup_turn_ui)
             .add_systems(
                 Update,
                 (update_turn_ui, manage_ai_veil).run_if(in_state(GameState::Game)),
@@ -0,0 +1,144 @@
+use bevy::asset::{io::Reader, AssetLoader, AsyncReadExt, LoadContext};
+use bevy::prelude::*;
+use bevy::utils::BoxedFuture;
+use serde::de::DeserializeOwned;
+use std::marker::PhantomData;
+
+/// Balance data stored as a TOML file in assets/balance/.
+/// The file is loaded as an asset, watched for edits and mirrored into a resource of the same type.
+pub trait BalanceAsset: Asset + Resource + Clone + Default + DeserializeOwned {
+    /// Путь к файлу внутри assets/
+    const PATH: &'static str;
+    /// Расширение, по которому asset server выбирает загрузчик
+    const EXTENSION: &'static str;
+    /// Название для логов и ошибок
+    const NAME: &'static str;
+}
+
+/// Handle keeping a balance asset alive
+#[derive(Resource)]
+pub struct BalanceHandle<T: BalanceAsset>(pub Handle<T>);
+
+/// Inserted once the balance file has been loaded for the first time.
+/// Until then the resource holds `Default` values and must not be trusted.
+#[derive(Resource)]
+pub struct BalanceLoaded<T: BalanceAsset>(PhantomData<fn() -> T>);
+
+impl<T: BalanceAsset> Default for BalanceLoaded<T> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+#[derive(Debug)]
+pub enum BalanceLoaderError {
+    Io(std::io::Error),
+    Toml(toml::de::Error),
+}
+
+impl std::fmt::Display for BalanceLoaderError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BalanceLoaderError::Io(err) => write!(f, "could not read balance file: {}", err),
+            BalanceLoaderError::Toml(err) => write!(f, "could not parse balance file: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for BalanceLoaderError {}
+
+/// TOML loader shared by all balance files
+pub struct BalanceLoader<T: BalanceAsset> {
+    extensions: [&'static str; 1],
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T: BalanceAsset> Default for BalanceLoader<T> {
+    fn default() -> Self {
+        Self {
+            extensions: [T::EXTENSION],
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T: BalanceAsset> AssetLoader for BalanceLoader<T> {
+    type Asset = T;
+    type Settings = ();
+    type Error = BalanceLoaderError;
+
+    fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader,
+        _settings: &'a (),
+        _load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<Self::Asset, Self::Error>> {
+        Box::pin(async move {
+            let mut contents = String::new();
+            reader.read_to_string(&mut contents).await.map_err(BalanceLoaderError::Io)?;
+            toml::from_str(&contents).map_err(BalanceLoaderError::Toml)
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &self.extensions
+    }
+}
+
+/// Starts loading a balance file
+pub fn load_balance<T: BalanceAsset>(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(BalanceHandle::<T>(asset_server.load(T::PATH)));
+}
+
+/// Copies the balance asset into its resource once it is loaded or edited on disk
+pub fn sync_balance<T: BalanceAsset>(
+    mut commands: Commands,
+    mut events: EventReader<AssetEvent<T>>,
+    assets: Res<Assets<T>>,
+    handle: Option<Res<BalanceHandle<T>>>,
+    mut resource: ResMut<T>,
+) {
+    let Some(handle) = handle else {
+        return;
+    };
+
+    for event in events.read() {
+        match event {
+            AssetEvent::LoadedWithDependencies { id } | AssetEvent::Modified { id } if *id == handle.0.id() => {
+                if let Some(loaded) = assets.get(*id) {
+                    *resource = loaded.clone();
+                    commands.init_resource::<BalanceLoaded<T>>();
+                    info!("⚖️ {} (re)loaded from {}", T::NAME, T::PATH);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Reads a balance file from assets/ directly, without waiting for the asset server
+pub fn read_balance_file<T: BalanceAsset>() -> Result<T, String> {
+    let full_path = bevy::asset::io::file::FileAssetReader::get_base_path().join("assets").join(T::PATH);
+    let contents = std::fs::read_to_string(&full_path)
+        .map_err(|err| format!("could not read {} from {}: {}", T::NAME, full_path.display(), err))?;
+    toml::from_str(&contents).map_err(|err| format!("could not parse {} from {}: {}", T::NAME, full_path.display(), err))
+}
+
+/// Plugin that registers a balance asset and keeps its resource in sync
+pub struct BalancePlugin<T: BalanceAsset>(PhantomData<fn() -> T>);
+
+impl<T: BalanceAsset> Default for BalancePlugin<T> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<T: BalanceAsset> Plugin for BalancePlugin<T> {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<T>()
+           .init_asset_loader::<BalanceLoader<T>>()
+           .init_resource::<T>()
+           .add_systems(Startup, load_balance::<T>)
+           .add_systems(Update, sync_balance::<T>);
+    }
+}
@@ -1,6 +1,7 @@
 use bevy::prelude::*;
 use bevy_hanabi::prelude::*;
 use crate::game::ClickEffectHandle;
+use crate::systems::projectiles::{Projectile, ProjectileImpact, ProjectileKind};
 
 /// setup particle effect.
 pub fn setup_particle_effect(
@@ -54,4 +55,135 @@ pub fn setup_particle_effect(
     );
 
     commands.insert_resource(ClickEffectHandle(effect));
-}
\ No newline at end of file
+}
+
+/// Handles of the combat particle effects
+#[derive(Resource)]
+pub struct CombatEffects {
+    pub muzzle_flash: Handle<EffectAsset>,
+    pub tracer: Handle<EffectAsset>,
+    pub bullet_impact: Handle<EffectAsset>,
+    pub explosion: Handle<EffectAsset>,
+}
+
+/// Через сколько секунд убрать сущность одноразового эффекта
+#[derive(Component)]
+pub struct EffectLifetime(pub Timer);
+
+/// Одноразовая вспышка частиц: `colors` - цвет в начале и в конце жизни частицы
+fn burst_effect(
+    effects: &mut Assets<EffectAsset>,
+    name: &str,
+    spawner: Spawner,
+    colors: (Vec4, Vec4),
+    speed: f32,
+    size: f32,
+    lifetime: f32,
+) -> Handle<EffectAsset> {
+    let mut color_gradient = Gradient::new();
+    color_gradient.add_key(0.0, colors.0);
+    color_gradient.add_key(1.0, colors.1);
+
+    let mut size_gradient = Gradient::new();
+    size_gradient.add_key(0.0, Vec2::splat(size));
+    size_gradient.add_key(1.0, Vec2::splat(0.0));
+
+    let writer = ExprWriter::new();
+
+    let init_pos = SetPositionSphereModifier {
+        center: writer.lit(Vec3::ZERO).expr(),
+        radius: writer.lit(0.1).expr(),
+        dimension: ShapeDimension::Volume,
+    };
+    let init_vel = SetVelocitySphereModifier {
+        center: writer.lit(Vec3::ZERO).expr(),
+        speed: writer.lit(speed * 0.5).uniform(writer.lit(speed)).expr(),
+    };
+    let init_lifetime = SetAttributeModifier::new(
+        Attribute::LIFETIME,
+        writer.lit(lifetime * 0.5).uniform(writer.lit(lifetime)).expr(),
+    );
+
+    effects.add(
+        EffectAsset::new(256, spawner, writer.finish())
+            .with_name(name)
+            .with_simulation_space(SimulationSpace::Global)
+            .init(init_pos)
+            .init(init_vel)
+            .init(init_lifetime)
+            .render(ColorOverLifetimeModifier { gradient: color_gradient })
+            .render(SizeOverLifetimeModifier {
+                gradient: size_gradient,
+                screen_space_size: false,
+            }),
+    )
+}
+
+/// Creates the muzzle flash, tracer and impact effects
+pub fn setup_combat_effects(
+    mut commands: Commands,
+    mut effects: ResMut<Assets<EffectAsset>>,
+) {
+    let yellow = Vec4::new(1.0, 0.9, 0.4, 1.0);
+    let smoke = Vec4::new(0.3, 0.3, 0.3, 0.0);
+
+    commands.insert_resource(CombatEffects {
+        muzzle_flash: burst_effect(&mut effects, "muzzle_flash", Spawner::once(12.0.into(), true), (yellow, smoke), 2.0, 0.25, 0.15),
+        tracer: burst_effect(&mut effects, "tracer", Spawner::rate(60.0.into()), (yellow, smoke), 0.1, 0.12, 0.2),
+        bullet_impact: burst_effect(&mut effects, "bullet_impact", Spawner::once(10.0.into(), true), (Vec4::new(0.6, 0.5, 0.4, 1.0), smoke), 1.5, 0.15, 0.4),
+        explosion: burst_effect(&mut effects, "explosion", Spawner::once(60.0.into(), true), (Vec4::new(1.0, 0.5, 0.1, 1.0), smoke), 5.0, 0.6, 0.9),
+    });
+}
+
+/// Muzzle flash at the shooter and a tracer that follows each new projectile
+pub fn spawn_projectile_effects(
+    mut commands: Commands,
+    effects: Res<CombatEffects>,
+    projectiles: Query<(Entity, &Transform), Added<Projectile>>,
+) {
+    for (entity, transform) in projectiles.iter() {
+        commands.spawn((
+            ParticleEffectBundle {
+                transform: *transform,
+                ..ParticleEffectBundle::new(effects.muzzle_flash.clone())
+            },
+            EffectLifetime(Timer::from_seconds(1.0, TimerMode::Once)),
+        ));
+        commands.entity(entity).with_children(|parent| {
+            parent.spawn(ParticleEffectBundle::new(effects.tracer.clone()));
+        });
+    }
+}
+
+/// Пыль от пуль и взрывы от снарядов и бомб в точке попадания
+pub fn spawn_impact_effects(
+    mut commands: Commands,
+    effects: Res<CombatEffects>,
+    mut impacts: EventReader<ProjectileImpact>,
+) {
+    for impact in impacts.read() {
+        let effect = match impact.kind {
+            ProjectileKind::Bullet => effects.bullet_impact.clone(),
+            ProjectileKind::Shell | ProjectileKind::Bomb => effects.explosion.clone(),
+        };
+        commands.spawn((
+            ParticleEffectBundle {
+                transform: Transform::from_translation(impact.position),
+                ..ParticleEffectBundle::new(effect)
+            },
+            EffectLifetime(Timer::from_seconds(2.0, TimerMode::Once)),
+        ));
+    }
+}
+
+pub fn despawn_finished_effects(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut query: Query<(Entity, &mut EffectLifetime)>,
+) {
+    for (entity, mut lifetime) in query.iter_mut() {
+        if lifetime.0.tick(time.delta()).finished() {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
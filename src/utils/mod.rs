@@ -1,2 +1,6 @@
+pub mod balance;
 pub mod effects;
-pub use effects::*;
\ No newline at end of file
+pub mod rng;
+pub use balance::*;
+pub use effects::*;
+pub use rng::*;